@@ -0,0 +1,202 @@
+// This is the core of the renderer.
+// It's plain Rust: it doesn't know anything about browsers or
+// WebAssembly, so exactly the same code is used by main.rs (which
+// saves an image) and lib.rs (which draws into a web page).
+
+use num_complex::Complex64;
+
+/// Once |z| gets bigger than this, we say that the point escaped.
+/// Using a large number (instead of 2) makes the smooth coloring
+/// below look much better.
+const BAILOUT: f64 = 256.0;
+
+/// How many iterations it takes to go all the way through the gradient.
+const COLOR_PERIOD: f64 = 24.0;
+
+/// The colors of the gradient, and where along it (from 0 to 1)
+/// each one goes.
+const GRADIENT: [(f64, [u8; 3]); 6] = [
+    (0.0, [0, 7, 100]),
+    (0.16, [32, 107, 203]),
+    (0.42, [237, 255, 255]),
+    (0.6425, [255, 170, 0]),
+    (0.8575, [0, 2, 0]),
+    // The same as the first color, so the gradient repeats smoothly.
+    (1.0, [0, 7, 100]),
+];
+
+/// Which part of the Mandelbrot Set we're looking at.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct View {
+    /// The point in the middle of the image.
+    pub center: Complex64,
+    /// How far we've zoomed in. At 1, the whole set fits.
+    pub zoom: f64,
+}
+
+impl View {
+    /// A view of the whole set.
+    pub const DEFAULT: View = View {
+        center: Complex64::new(-0.5, 0.0),
+        zoom: 1.0,
+    };
+
+    /// How far apart (on the complex plane) two neighboring pixels are.
+    fn pixel_size(&self, width: u32, height: u32) -> f64 {
+        // At a zoom of 1, the shorter side of the image covers
+        // 3 units, which fits the whole set.
+        3.0 / self.zoom / f64::from(width.min(height).max(1))
+    }
+
+    /// Turns a pixel position into a point on the complex plane.
+    /// The position can be fractional, to point inside a pixel.
+    pub fn point_at(&self, x: f64, y: f64, width: u32, height: u32) -> Complex64 {
+        let size = self.pixel_size(width, height);
+
+        // Images count y downwards, but the imaginary axis goes up,
+        // so y is flipped.
+        Complex64::new(
+            self.center.re + (x - f64::from(width) / 2.0) * size,
+            self.center.im - (y - f64::from(height) / 2.0) * size,
+        )
+    }
+
+    /// Zooms in (or out, if `factor` is less than 1), keeping the
+    /// point under pixel (x, y) in the same place.
+    /// This makes zooming feel like it goes towards the mouse.
+    pub fn zoom_at(&mut self, x: f64, y: f64, width: u32, height: u32, factor: f64) {
+        let before = self.point_at(x, y, width, height);
+        self.zoom *= factor;
+        let after = self.point_at(x, y, width, height);
+
+        // Move the view so that `before` is under (x, y) again.
+        self.center += before - after;
+    }
+}
+
+/// Works out how many iterations it takes for the point `c` to
+/// escape, or returns None if it doesn't escape within
+/// `max_iterations` (meaning it's probably in the set).
+///
+/// The result is fractional (smooth), so that neighboring pixels
+/// get slightly different colors instead of flat bands.
+pub fn escape_time(c: Complex64, max_iterations: u32) -> Option<f64> {
+    let mut z = Complex64::new(0.0, 0.0);
+
+    for i in 0..max_iterations {
+        z = z * z + c;
+
+        // norm_sqr is |z|², which saves us a square root.
+        if z.norm_sqr() > BAILOUT * BAILOUT {
+            // iterations + 1 - log2(log(|z|))
+            return Some(f64::from(i) + 1.0 - z.norm().ln().log2());
+        }
+    }
+
+    None
+}
+
+/// Picks a color for a point, based on its escape time.
+pub fn color(escape_time: Option<f64>) -> [u8; 3] {
+    // Points in the set are black.
+    let Some(iterations) = escape_time else {
+        return [0, 0, 0];
+    };
+
+    // Where we are in the gradient, from 0 to 1.
+    // rem_euclid wraps around, so the gradient repeats.
+    let position = (iterations / COLOR_PERIOD).rem_euclid(1.0);
+
+    // Find the two colors that we're between, and blend them.
+    for pair in GRADIENT.windows(2) {
+        let (start, from) = pair[0];
+        let (end, to) = pair[1];
+
+        if position <= end {
+            let t = (position - start) / (end - start);
+            return [0, 1, 2].map(|i| (f64::from(from[i]) + (f64::from(to[i]) - f64::from(from[i])) * t).round() as u8);
+        }
+    }
+
+    // position is always less than 1, so we never get here.
+    GRADIENT[0].1
+}
+
+/// Renders an image, and returns its pixels.
+///
+/// Each pixel is 4 bytes: red, green, blue, and alpha (opacity),
+/// going left to right, then top to bottom.
+/// This is the same format that an HTML canvas uses.
+pub fn render(width: u32, height: u32, view: &View, max_iterations: u32) -> Vec<u8> {
+    let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+
+    for y in 0..height {
+        for x in 0..width {
+            // + 0.5 uses the middle of the pixel.
+            let c = view.point_at(f64::from(x) + 0.5, f64::from(y) + 0.5, width, height);
+            let [r, g, b] = color(escape_time(c, max_iterations));
+
+            pixels.extend_from_slice(&[r, g, b, 255]);
+        }
+    }
+
+    pixels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_in_the_set_never_escape() {
+        for c in [Complex64::new(0.0, 0.0), Complex64::new(-1.0, 0.0), Complex64::new(-0.5, 0.5)] {
+            assert_eq!(escape_time(c, 1_000), None);
+        }
+    }
+
+    #[test]
+    fn points_outside_the_set_escape() {
+        let near = escape_time(Complex64::new(0.5, 0.5), 1_000).unwrap();
+        let far = escape_time(Complex64::new(2.0, 2.0), 1_000).unwrap();
+
+        // Points further away escape faster.
+        assert!(far < near);
+        assert!(far >= 0.0);
+    }
+
+    #[test]
+    fn center_pixel_is_the_center() {
+        let view = View::DEFAULT;
+
+        assert_eq!(view.point_at(50.0, 40.0, 100, 80), view.center);
+    }
+
+    #[test]
+    fn zooming_keeps_the_point_under_the_mouse() {
+        let mut view = View::DEFAULT;
+        let before = view.point_at(10.0, 20.0, 100, 80);
+
+        view.zoom_at(10.0, 20.0, 100, 80, 4.0);
+        let after = view.point_at(10.0, 20.0, 100, 80);
+
+        assert_eq!(view.zoom, 4.0);
+        assert!((before - after).norm() < 1e-12);
+    }
+
+    #[test]
+    fn colors() {
+        assert_eq!(color(None), [0, 0, 0]);
+        // The start of the gradient, and a full period later.
+        assert_eq!(color(Some(0.0)), [0, 7, 100]);
+        assert_eq!(color(Some(COLOR_PERIOD)), [0, 7, 100]);
+    }
+
+    #[test]
+    fn render_size() {
+        let pixels = render(8, 5, &View::DEFAULT, 50);
+
+        assert_eq!(pixels.len(), 8 * 5 * 4);
+        // Every pixel is fully opaque.
+        assert!(pixels.chunks(4).all(|pixel| pixel[3] == 255));
+    }
+}
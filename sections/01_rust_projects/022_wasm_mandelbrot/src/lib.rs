@@ -0,0 +1,104 @@
+// This is the part of the renderer that runs in the browser.
+// The rendering itself is in fractal.rs, which doesn't know
+// anything about the web.
+//
+// Build it with `wasm-pack build --target web --out-dir www/pkg`,
+// then serve the www folder (for example, with
+// `python3 -m http.server --directory www`) and open it in a browser.
+
+pub mod fractal;
+
+use fractal::View;
+use wasm_bindgen::prelude::*;
+
+// #[wasm_bindgen] makes a type or function available to JavaScript.
+// wasm-pack generates a JavaScript file (www/pkg/project.js) that
+// lets JavaScript use it like a normal class:
+//
+//     const mandelbrot = new Mandelbrot(800, 600);
+//     const pixels = mandelbrot.render();
+
+/// A Mandelbrot Set renderer, which remembers where we're looking.
+#[wasm_bindgen]
+pub struct Mandelbrot {
+    width: u32,
+    height: u32,
+    view: View,
+    max_iterations: u32,
+}
+
+#[wasm_bindgen]
+impl Mandelbrot {
+    /// Creates a renderer for an image of the given size.
+    // `constructor` means that JavaScript creates one with
+    // `new Mandelbrot(...)`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: u32, height: u32) -> Mandelbrot {
+        Mandelbrot {
+            width,
+            height,
+            view: View::DEFAULT,
+            max_iterations: 200,
+        }
+    }
+
+    /// Renders the image, and returns its pixels.
+    ///
+    /// Each pixel is 4 bytes (red, green, blue, alpha), which is the
+    /// same format a canvas uses, so JavaScript can draw it directly.
+    // A Vec<u8> turns into a Uint8Array in JavaScript.
+    // wasm-bindgen copies it out of WebAssembly's memory for us.
+    pub fn render(&self) -> Vec<u8> {
+        fractal::render(self.width, self.height, &self.view, self.max_iterations)
+    }
+
+    // js_name changes what the function is called in JavaScript,
+    // since JavaScript usually uses camelCase.
+
+    /// Zooms in by `factor` (or out, if it's less than 1), towards
+    /// the pixel at (x, y).
+    #[wasm_bindgen(js_name = zoomAt)]
+    pub fn zoom_at(&mut self, x: f64, y: f64, factor: f64) {
+        self.view.zoom_at(x, y, self.width, self.height, factor);
+    }
+
+    /// Goes back to showing the whole set.
+    pub fn reset(&mut self) {
+        self.view = View::DEFAULT;
+    }
+
+    /// Changes the size of the image.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+    }
+
+    /// Sets how many iterations to try before deciding that a point
+    /// is in the set.
+    /// More iterations show more detail when zoomed in, but are slower.
+    #[wasm_bindgen(js_name = setMaxIterations)]
+    pub fn set_max_iterations(&mut self, max_iterations: u32) {
+        self.max_iterations = max_iterations.max(1);
+    }
+
+    // getter makes this a property in JavaScript, so it's used
+    // like `mandelbrot.zoom` instead of `mandelbrot.zoom()`.
+
+    /// How far we've zoomed in.
+    #[wasm_bindgen(getter)]
+    pub fn zoom(&self) -> f64 {
+        self.view.zoom
+    }
+
+    /// The real part of the point in the middle of the image.
+    #[wasm_bindgen(getter, js_name = centerRe)]
+    pub fn center_re(&self) -> f64 {
+        self.view.center.re
+    }
+
+    /// The imaginary part of the point in the middle of the image.
+    #[wasm_bindgen(getter, js_name = centerIm)]
+    pub fn center_im(&self) -> f64 {
+        self.view.center.im
+    }
+}
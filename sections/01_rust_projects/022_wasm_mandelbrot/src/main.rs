@@ -0,0 +1,37 @@
+// This renders the Mandelbrot Set to an image file, without a
+// browser.
+// It uses exactly the same code (in fractal.rs) as the WebAssembly
+// version, which is the whole point: the renderer doesn't care
+// where it's running.
+//
+// Run it with `cargo run --release`, then open mandelbrot.ppm.
+// (PPM is a very simple image format. Most image viewers can open
+// it, and if yours can't, there are plenty of online converters.)
+
+use project::fractal::{self, View};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+const WIDTH: u32 = 900;
+const HEIGHT: u32 = 600;
+const MAX_ITERATIONS: u32 = 500;
+
+fn main() -> std::io::Result<()> {
+    let pixels = fractal::render(WIDTH, HEIGHT, &View::DEFAULT, MAX_ITERATIONS);
+
+    let mut file = BufWriter::new(File::create("mandelbrot.ppm")?);
+
+    // A binary PPM starts with "P6", the size, and the largest
+    // color value, then the pixels as red, green, blue bytes.
+    writeln!(file, "P6\n{WIDTH} {HEIGHT}\n255")?;
+
+    // Our pixels have an alpha byte too, which PPM doesn't use.
+    for pixel in pixels.chunks(4) {
+        file.write_all(&pixel[..3])?;
+    }
+
+    file.flush()?;
+    println!("Saved mandelbrot.ppm ({WIDTH}x{HEIGHT}).");
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+// This connects the page to our Rust code.
+// pkg/project.js is generated by wasm-pack, and loads the .wasm file.
+import init, { Mandelbrot } from "./pkg/project.js";
+
+// init downloads and starts the WebAssembly module.
+// Nothing from Rust can be used until it's finished.
+await init();
+
+const canvas = document.getElementById("canvas");
+const context = canvas.getContext("2d");
+const status = document.getElementById("status");
+const iterations = document.getElementById("iterations");
+
+// This calls the #[wasm_bindgen(constructor)] function, Mandelbrot::new.
+const mandelbrot = new Mandelbrot(canvas.width, canvas.height);
+mandelbrot.setMaxIterations(Number(iterations.value));
+
+function draw() {
+    const start = performance.now();
+
+    // render returns a Uint8Array with 4 bytes per pixel, which is
+    // exactly what ImageData expects (as a Uint8ClampedArray).
+    const pixels = mandelbrot.render();
+    const image = new ImageData(new Uint8ClampedArray(pixels.buffer), canvas.width, canvas.height);
+    context.putImageData(image, 0, 0);
+
+    const time = Math.round(performance.now() - start);
+    status.textContent =
+        `Center: ${mandelbrot.centerRe.toFixed(6)} + ${mandelbrot.centerIm.toFixed(6)}i, ` +
+        `zoom: ${mandelbrot.zoom.toExponential(2)}, rendered in ${time}ms`;
+}
+
+// Clicking zooms in towards the mouse, and shift-clicking zooms out.
+canvas.addEventListener("click", (event) => {
+    mandelbrot.zoomAt(event.offsetX, event.offsetY, event.shiftKey ? 0.5 : 2);
+    draw();
+});
+
+// The mouse wheel zooms too.
+canvas.addEventListener("wheel", (event) => {
+    event.preventDefault();
+    mandelbrot.zoomAt(event.offsetX, event.offsetY, event.deltaY < 0 ? 1.25 : 0.8);
+    draw();
+}, { passive: false });
+
+// The buttons zoom towards the middle of the image.
+document.getElementById("zoom-in").addEventListener("click", () => {
+    mandelbrot.zoomAt(canvas.width / 2, canvas.height / 2, 2);
+    draw();
+});
+
+document.getElementById("zoom-out").addEventListener("click", () => {
+    mandelbrot.zoomAt(canvas.width / 2, canvas.height / 2, 0.5);
+    draw();
+});
+
+document.getElementById("reset").addEventListener("click", () => {
+    mandelbrot.reset();
+    draw();
+});
+
+iterations.addEventListener("change", () => {
+    mandelbrot.setMaxIterations(Number(iterations.value));
+    draw();
+});
+
+draw();
@@ -0,0 +1,387 @@
+// This is a todo list that runs in the console.
+// Try running it with `cargo run -- add "Learn Rust"`,
+// then `cargo run -- list`.
+// Everything after the `--` gets passed to our program
+// instead of to cargo.
+
+use clap::{Parser, Subcommand};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+// clap can read the struct below and generate a
+// command-line parser from it.
+// The doc comments (///) turn into the help text that you
+// see when you run `cargo run -- --help`.
+
+/// A simple todo list.
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    /// The command to run.
+    #[command(subcommand)]
+    command: Command,
+}
+
+// Each variant of this enum is a subcommand.
+// Variants with data turn into arguments, so `Add { text }`
+// means `add <TEXT>`.
+
+/// Every command that the todo list supports.
+#[derive(Subcommand)]
+enum Command {
+    /// Adds a new item to the list.
+    Add {
+        /// What needs to be done.
+        text: String,
+    },
+    /// Lists every item.
+    List {
+        /// Only show items that haven't been completed yet.
+        #[arg(short, long)]
+        pending: bool,
+    },
+    /// Marks an item as done.
+    Done {
+        /// The ID of the item (shown by `list`).
+        id: u32,
+    },
+    /// Removes an item from the list.
+    Rm {
+        /// The ID of the item (shown by `list`).
+        id: u32,
+    },
+}
+
+// Serialize and Deserialize come from serde.
+// They let serde_json turn our structs into JSON
+// and back again.
+
+/// A single item on the todo list.
+#[derive(Debug, Serialize, Deserialize)]
+struct Item {
+    /// The item's unique ID.
+    id: u32,
+    /// What needs to be done.
+    text: String,
+    /// Whether the item has been completed.
+    done: bool,
+}
+
+/// The whole todo list, as it's stored on disk.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TodoList {
+    /// The ID that the next item will get.
+    /// We keep track of this separately so that
+    /// IDs don't get reused after an item is removed.
+    next_id: u32,
+    /// Every item on the list.
+    items: Vec<Item>,
+}
+
+// Instead of printing out errors wherever they happen,
+// we'll collect every way our program can fail into a
+// single enum.
+// This lets us use the ? operator everywhere and
+// handle all errors in one place (main).
+
+/// Everything that can go wrong while running a command.
+#[derive(Debug)]
+enum TodoError {
+    /// We couldn't figure out where to store the list.
+    NoDataDir,
+    /// Reading or writing the list failed.
+    Io(std::io::Error),
+    /// The list file exists, but it isn't valid JSON.
+    Json(serde_json::Error),
+    /// There's no item with the given ID.
+    NotFound(u32),
+    /// Every ID has been used up, so a new item can't get one.
+    OutOfIds,
+}
+
+impl fmt::Display for TodoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TodoError::NoDataDir => write!(f, "could not find a data directory to store the list in"),
+            TodoError::Io(err) => write!(f, "could not access the list file: {err}"),
+            TodoError::Json(err) => write!(f, "the list file is corrupted: {err}"),
+            TodoError::NotFound(id) => write!(f, "there is no item with ID {id}"),
+            TodoError::OutOfIds => write!(f, "there are no IDs left to give to a new item"),
+        }
+    }
+}
+
+// These From implementations are what make ? work.
+// When ? sees an error of type std::io::Error in a
+// function that returns TodoError, it calls
+// TodoError::from(err) for us.
+
+impl From<std::io::Error> for TodoError {
+    fn from(err: std::io::Error) -> Self {
+        TodoError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for TodoError {
+    fn from(err: serde_json::Error) -> Self {
+        TodoError::Json(err)
+    }
+}
+
+/// Returns the path to the file that the list is stored in.
+/// On Linux, this is usually ~/.local/share/todo/todo.json.
+fn list_path() -> Result<PathBuf, TodoError> {
+    // data_dir returns None if it can't figure out where
+    // the data directory is.
+    // ok_or turns that None into an error.
+    let dir = dirs::data_dir().ok_or(TodoError::NoDataDir)?;
+    Ok(dir.join("todo").join("todo.json"))
+}
+
+impl TodoList {
+    /// Loads the list from disk.
+    /// If the file doesn't exist yet, an empty list is returned.
+    fn load(path: &Path) -> Result<TodoList, TodoError> {
+        // Not having a file isn't an error - it just means
+        // that nothing has been added yet.
+        if !path.exists() {
+            return Ok(TodoList::default());
+        }
+
+        let contents = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    /// Saves the list to disk, creating its directory if needed.
+    fn save(&self, path: &Path) -> Result<(), TodoError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let contents = serde_json::to_string_pretty(self)?;
+
+        // If we wrote straight into the list file, and the program
+        // crashed halfway through, the list would be left cut off,
+        // and the old one would be gone.
+        // Instead, we write to a temporary file next to it, then
+        // rename that over the real one. Renaming is atomic, so the
+        // file is always either the old list or the new one.
+        let temp_path = path.with_extension("json.tmp");
+        let mut file = File::create(&temp_path)?;
+        file.write_all(contents.as_bytes())?;
+        // sync_all waits until the data is actually on the disk.
+        // Otherwise, a power cut right after the rename could leave
+        // an empty file behind.
+        file.sync_all()?;
+        fs::rename(&temp_path, path)?;
+
+        Ok(())
+    }
+
+    /// Adds a new item and returns its ID.
+    fn add(&mut self, text: String) -> Result<u32, TodoError> {
+        let id = self.next_id;
+        // += 1 would panic (or wrap around to 0, in release builds)
+        // after u32::MAX. checked_add returns None instead, which
+        // we turn into an error.
+        self.next_id = id.checked_add(1).ok_or(TodoError::OutOfIds)?;
+
+        self.items.push(Item { id, text, done: false });
+
+        Ok(id)
+    }
+
+    /// Marks the item with the given ID as done.
+    fn complete(&mut self, id: u32) -> Result<(), TodoError> {
+        // iter_mut gives us mutable references to each item,
+        // and find returns the first one that matches.
+        let item = self
+            .items
+            .iter_mut()
+            .find(|item| item.id == id)
+            .ok_or(TodoError::NotFound(id))?;
+
+        item.done = true;
+
+        Ok(())
+    }
+
+    /// Removes the item with the given ID.
+    fn remove(&mut self, id: u32) -> Result<Item, TodoError> {
+        let index = self
+            .items
+            .iter()
+            .position(|item| item.id == id)
+            .ok_or(TodoError::NotFound(id))?;
+
+        Ok(self.items.remove(index))
+    }
+
+    /// Returns a line of text for each item, like `[x] 0: Learn Rust`.
+    /// If `pending` is true, completed items are left out.
+    fn lines(&self, pending: bool) -> Vec<String> {
+        // filter skips the items that it returns false for, so
+        // completed items won't be included when --pending is passed.
+        self.items
+            .iter()
+            .filter(|item| !(pending && item.done))
+            .map(|item| {
+                let check = if item.done { "x" } else { " " };
+                format!("[{check}] {}: {}", item.id, item.text)
+            })
+            .collect()
+    }
+}
+
+/// Runs a single command, loading and saving the list as needed.
+fn run(cli: Cli) -> Result<(), TodoError> {
+    let path = list_path()?;
+    let mut list = TodoList::load(&path)?;
+
+    match cli.command {
+        Command::Add { text } => {
+            let id = list.add(text)?;
+            list.save(&path)?;
+            println!("Added item {id}.");
+        }
+        Command::List { pending } => {
+            // With --pending, a list where everything is done has
+            // nothing to show either.
+            let lines = list.lines(pending);
+            if lines.is_empty() {
+                println!("Nothing to do!");
+            }
+
+            for line in lines {
+                println!("{line}");
+            }
+        }
+        Command::Done { id } => {
+            list.complete(id)?;
+            list.save(&path)?;
+            println!("Completed item {id}.");
+        }
+        Command::Rm { id } => {
+            let item = list.remove(id)?;
+            list.save(&path)?;
+            println!("Removed \"{}\".", item.text);
+        }
+    }
+
+    Ok(())
+}
+
+fn main() {
+    // parse reads the arguments that were passed to our program.
+    // If they're invalid (or --help was passed), clap prints a
+    // message and exits for us.
+    let cli = Cli::parse();
+
+    if let Err(err) = run(cli) {
+        eprintln!("Error: {err}");
+        // A non-zero exit code tells whoever ran us
+        // that something went wrong.
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a path to a list file in a new temporary directory.
+    /// The directory is deleted when the TempDir is dropped, so
+    /// it has to be kept around for as long as the path is used.
+    fn temp_path() -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("todo").join("todo.json");
+        (dir, path)
+    }
+
+    #[test]
+    fn adds_completes_and_removes_items() {
+        let mut list = TodoList::default();
+
+        assert_eq!(list.add("Learn Rust".into()).unwrap(), 0);
+        assert_eq!(list.add("Write a CLI".into()).unwrap(), 1);
+        list.complete(0).unwrap();
+
+        assert_eq!(list.lines(false), vec!["[x] 0: Learn Rust", "[ ] 1: Write a CLI"]);
+        assert_eq!(list.lines(true), vec!["[ ] 1: Write a CLI"]);
+
+        assert_eq!(list.remove(0).unwrap().text, "Learn Rust");
+        assert_eq!(list.lines(false), vec!["[ ] 1: Write a CLI"]);
+
+        // Once everything is done, there's nothing pending to show.
+        list.complete(1).unwrap();
+        assert!(list.lines(true).is_empty());
+    }
+
+    #[test]
+    fn does_not_reuse_ids() {
+        let mut list = TodoList::default();
+        list.add("first".into()).unwrap();
+        list.remove(0).unwrap();
+
+        assert_eq!(list.add("second".into()).unwrap(), 1);
+    }
+
+    #[test]
+    fn reports_missing_items() {
+        let mut list = TodoList::default();
+
+        assert!(matches!(list.complete(7), Err(TodoError::NotFound(7))));
+        assert!(matches!(list.remove(7), Err(TodoError::NotFound(7))));
+    }
+
+    #[test]
+    fn runs_out_of_ids_without_panicking() {
+        let mut list = TodoList {
+            next_id: u32::MAX - 1,
+            items: Vec::new(),
+        };
+
+        // u32::MAX is only ever stored as next_id, since there's no ID
+        // after it to move on to.
+        assert_eq!(list.add("last".into()).unwrap(), u32::MAX - 1);
+        assert!(matches!(list.add("too many".into()), Err(TodoError::OutOfIds)));
+        assert_eq!(list.items.len(), 1);
+    }
+
+    #[test]
+    fn saves_and_loads_the_list() {
+        let (_dir, path) = temp_path();
+
+        // A file that doesn't exist yet is an empty list.
+        let mut list = TodoList::load(&path).unwrap();
+        assert!(list.items.is_empty());
+
+        list.add("Learn Rust".into()).unwrap();
+        list.add("Write a CLI".into()).unwrap();
+        list.complete(1).unwrap();
+        list.remove(0).unwrap();
+        // save creates the todo/ folder, since it doesn't exist yet.
+        list.save(&path).unwrap();
+
+        // Only the list itself is left, not the temporary file.
+        let files: Vec<_> = fs::read_dir(path.parent().unwrap()).unwrap().collect();
+        assert_eq!(files.len(), 1);
+
+        let mut list = TodoList::load(&path).unwrap();
+        assert_eq!(list.lines(false), vec!["[x] 1: Write a CLI"]);
+        // next_id is saved too, so IDs still aren't reused.
+        assert_eq!(list.add("Ship it".into()).unwrap(), 2);
+    }
+
+    #[test]
+    fn reports_a_corrupted_file() {
+        let (_dir, path) = temp_path();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "not json").unwrap();
+
+        assert!(matches!(TodoList::load(&path), Err(TodoError::Json(_))));
+    }
+}
@@ -0,0 +1,657 @@
+// This is a key-value store that saves everything to a file.
+// Try running `cargo run -- set name Ferris`, then
+// `cargo run -- get name`.
+// Even though the program exits in between, the value
+// is still there because it's stored in `data.kv`.
+//
+// The design is based on Bitcask
+// (https://riak.com/assets/bitcask-intro.pdf).
+// Instead of editing the file in place, we only ever add
+// records to the end of it (this is called an append-only log),
+// and keep an index in memory that remembers where the newest
+// value for each key is.
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// The file that the store is saved in.
+const DATA_FILE: &str = "data.kv";
+
+/// How many bytes of old, overwritten records we'll put up
+/// with before compacting the log.
+const COMPACTION_THRESHOLD: u64 = 1024 * 1024;
+
+/// The size of a record's header, in bytes.
+/// It holds a checksum (4 bytes), the kind of record (1 byte),
+/// the key's length (4 bytes), and the value's length (4 bytes).
+const HEADER_SIZE: u64 = 4 + 1 + 4 + 4;
+
+// Every record in the file looks like this:
+//
+// | checksum | kind | key length | value length | key | value |
+//
+// The checksum is calculated from everything after it.
+// If the program crashes halfway through writing a record,
+// the checksum won't match when we read it back, so we
+// know to throw that record away.
+
+/// What a record in the log does.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RecordKind {
+    /// Sets a key to a value.
+    Set = 0,
+    /// Deletes a key. The value is always empty.
+    Delete = 1,
+}
+
+/// Where a value lives inside the log file.
+#[derive(Debug, Clone, Copy)]
+struct ValuePosition {
+    /// The offset of the start of the value (in bytes).
+    offset: u64,
+    /// The length of the value (in bytes).
+    len: u32,
+    /// The size of the whole record (in bytes).
+    /// This is used to keep track of how much space
+    /// compaction would free up.
+    record_len: u64,
+}
+
+/// A key-value store backed by an append-only log.
+struct KvStore {
+    /// The path to the log file.
+    path: PathBuf,
+    /// The open log file.
+    file: File,
+    /// Maps each key to the position of its newest value.
+    index: HashMap<String, ValuePosition>,
+    /// How many bytes in the log belong to records that
+    /// have been overwritten or deleted.
+    stale_bytes: u64,
+}
+
+/// Calculates a CRC-32 checksum of some bytes.
+///
+/// This is the same checksum that zip files use.
+/// There are crates that do this much faster (like `crc32fast`),
+/// but it only takes a few lines to write ourselves.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+
+    for &byte in bytes {
+        crc ^= byte as u32;
+
+        for _ in 0..8 {
+            // If the lowest bit is set, shift and XOR with the
+            // CRC-32 polynomial, otherwise, just shift.
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB88320 & mask);
+        }
+    }
+
+    !crc
+}
+
+/// Turns a record into the bytes that get written to the log.
+fn encode_record(kind: RecordKind, key: &str, value: &[u8]) -> Vec<u8> {
+    let mut body = Vec::with_capacity(HEADER_SIZE as usize + key.len() + value.len());
+
+    // to_le_bytes turns a number into its bytes in little-endian
+    // order (smallest byte first).
+    // We need to pick an order so that the file can be read
+    // on any computer, and little-endian is the most common.
+    body.push(kind as u8);
+    body.extend_from_slice(&(key.len() as u32).to_le_bytes());
+    body.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    body.extend_from_slice(key.as_bytes());
+    body.extend_from_slice(value);
+
+    let mut record = crc32(&body).to_le_bytes().to_vec();
+    record.extend_from_slice(&body);
+
+    record
+}
+
+/// A record that was read back from the log.
+struct Record {
+    kind: RecordKind,
+    key: String,
+    /// Where the value starts and how long it is.
+    value: ValuePosition,
+}
+
+/// Checks whether `bytes` starts with a complete record whose
+/// checksum matches.
+fn is_complete_record(bytes: &[u8]) -> bool {
+    let Some(header) = bytes.get(..HEADER_SIZE as usize) else {
+        return false;
+    };
+    // The kind has to be Set (0) or Delete (1).
+    if header[4] > 1 {
+        return false;
+    }
+
+    let key_len = u32::from_le_bytes(header[5..9].try_into().unwrap()) as u64;
+    let value_len = u32::from_le_bytes(header[9..13].try_into().unwrap()) as u64;
+    let Some(record) = bytes.get(..(HEADER_SIZE + key_len + value_len) as usize) else {
+        return false;
+    };
+
+    let checksum = u32::from_le_bytes(header[0..4].try_into().unwrap());
+    crc32(&record[4..]) == checksum
+}
+
+/// Reads a single record from the log.
+/// `offset` is where the record starts in the file, and
+/// `file_len` is how long the whole file is.
+///
+/// Returns Ok(None) if there are no more complete records,
+/// which happens at the end of the file, or if the last record
+/// was only partially written.
+/// A broken record that *isn't* the last one is an error, since
+/// that means the file was damaged, not just cut off by a crash.
+fn read_record(reader: &mut impl Read, offset: u64, file_len: u64) -> io::Result<Option<Record>> {
+    let mut header = [0; HEADER_SIZE as usize];
+
+    // read_exact gives an UnexpectedEof error if the file ends
+    // before the buffer is full.
+    // That's exactly what happens after a crash mid-write, so
+    // we treat it as the end of the log instead of an error.
+    match reader.read_exact(&mut header) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err),
+    }
+
+    // try_into converts a slice into a fixed-size array.
+    // It can only fail if the lengths don't match, and we
+    // know that they do.
+    let checksum = u32::from_le_bytes(header[0..4].try_into().unwrap());
+    let key_len = u32::from_le_bytes(header[5..9].try_into().unwrap());
+    let value_len = u32::from_le_bytes(header[9..13].try_into().unwrap());
+
+    // The lengths haven't been checked by the checksum yet, so they
+    // could be anything, up to 4GB each. A record can't be longer
+    // than the rest of the file, though. If it says it is, either
+    // the file ends partway through it (a crash), or the length
+    // itself is damaged.
+    // Checking this first also means that a broken header can't
+    // make us allocate gigabytes of memory below.
+    let record_len = HEADER_SIZE + key_len as u64 + value_len as u64;
+    let remaining = file_len.saturating_sub(offset);
+    if record_len > remaining {
+        // To tell those apart, look for a complete record in the
+        // rest of the file. A torn record is the last thing in the
+        // file, so there won't be one after it. If there is one, the
+        // length is wrong, and truncating here would throw that
+        // record (and every one after it) away.
+        // The rest of the file really exists, so reading it in is
+        // fine, unlike trusting the lengths.
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest)?;
+        if (0..rest.len()).any(|start| is_complete_record(&rest[start..])) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("the record at byte {offset} is longer than the rest of the file"),
+            ));
+        }
+        return Ok(None);
+    }
+
+    // If this record goes right up to the end of the file, and it
+    // turns out to be broken, it's the half-written record from a
+    // crash. Anywhere else, it's damage.
+    let is_last = record_len == remaining;
+    let broken = || {
+        if is_last {
+            Ok(None)
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("the record at byte {offset} is corrupted"),
+            ))
+        }
+    };
+
+    let kind = match header[4] {
+        0 => RecordKind::Set,
+        1 => RecordKind::Delete,
+        _ => return broken(),
+    };
+
+    let mut data = vec![0; key_len as usize + value_len as usize];
+    match reader.read_exact(&mut data) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err),
+    }
+
+    // The checksum covers everything in the record except
+    // for the checksum itself.
+    let mut body = header[4..].to_vec();
+    body.extend_from_slice(&data);
+    if crc32(&body) != checksum {
+        return broken();
+    }
+
+    let Ok(key) = String::from_utf8(data[..key_len as usize].to_vec()) else {
+        return broken();
+    };
+
+    Ok(Some(Record {
+        kind,
+        key,
+        value: ValuePosition {
+            offset: offset + HEADER_SIZE + key_len as u64,
+            len: value_len,
+            record_len,
+        },
+    }))
+}
+
+impl KvStore {
+    /// Opens the store at the given path, creating it if it
+    /// doesn't exist.
+    ///
+    /// This reads through the whole log to rebuild the index.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<KvStore> {
+        let path = path.as_ref().to_path_buf();
+
+        // append means that every write goes to the end of the
+        // file, no matter where we've seeked to for reading.
+        let file = OpenOptions::new().read(true).append(true).create(true).open(&path)?;
+
+        let mut store = KvStore {
+            path,
+            file,
+            index: HashMap::new(),
+            stale_bytes: 0,
+        };
+        store.load()?;
+
+        Ok(store)
+    }
+
+    /// Rebuilds the index by replaying every record in the log.
+    fn load(&mut self) -> io::Result<()> {
+        self.index.clear();
+        self.stale_bytes = 0;
+
+        // Reading one byte at a time from a file is slow, because
+        // every read asks the operating system for more data.
+        // BufReader reads big chunks at once and hands them out
+        // to us piece by piece.
+        self.file.seek(SeekFrom::Start(0))?;
+        let len = self.file.metadata()?.len();
+        let mut reader = BufReader::new(&self.file);
+        let mut offset = 0;
+
+        while let Some(record) = read_record(&mut reader, offset, len)? {
+            offset += record.value.record_len;
+
+            // Later records win, so if the key was already in
+            // the index, the record it pointed to is now stale.
+            let old = match record.kind {
+                RecordKind::Set => self.index.insert(record.key, record.value),
+                RecordKind::Delete => {
+                    // The delete record itself is also useless
+                    // after compaction.
+                    self.stale_bytes += record.value.record_len;
+                    self.index.remove(&record.key)
+                }
+            };
+
+            if let Some(old) = old {
+                self.stale_bytes += old.record_len;
+            }
+        }
+
+        // If the last record was cut off (because of a crash),
+        // remove it so that new records don't get written after
+        // a broken one. read_record only stops early when the
+        // record goes right up to the end of the file, and returns
+        // an error for broken records anywhere else, so this never
+        // throws away a complete record.
+        if offset < len {
+            eprintln!("Warning: discarding {} bytes of incomplete data.", len - offset);
+            self.file.set_len(offset)?;
+        }
+
+        Ok(())
+    }
+
+    /// Adds a record to the end of the log and returns where
+    /// its value was written.
+    fn append(&mut self, kind: RecordKind, key: &str, value: &[u8]) -> io::Result<ValuePosition> {
+        let record = encode_record(kind, key, value);
+        let start = self.file.seek(SeekFrom::End(0))?;
+
+        self.file.write_all(&record)?;
+        // sync_data makes sure the data is actually on the disk,
+        // and not just sitting in a buffer somewhere.
+        // Without it, a power outage could lose a write that
+        // we already told the user had succeeded.
+        self.file.sync_data()?;
+
+        Ok(ValuePosition {
+            offset: start + HEADER_SIZE + key.len() as u64,
+            len: value.len() as u32,
+            record_len: record.len() as u64,
+        })
+    }
+
+    /// Returns the value for a key, or None if it isn't set.
+    pub fn get(&mut self, key: &str) -> io::Result<Option<Vec<u8>>> {
+        let Some(position) = self.index.get(key) else {
+            return Ok(None);
+        };
+
+        // We know exactly where the value is, so we can jump
+        // straight to it instead of reading the whole file.
+        let mut value = vec![0; position.len as usize];
+        self.file.seek(SeekFrom::Start(position.offset))?;
+        self.file.read_exact(&mut value)?;
+
+        Ok(Some(value))
+    }
+
+    /// Sets a key to a value, replacing the old value if there was one.
+    pub fn set(&mut self, key: &str, value: &[u8]) -> io::Result<()> {
+        let position = self.append(RecordKind::Set, key, value)?;
+
+        if let Some(old) = self.index.insert(key.to_string(), position) {
+            self.stale_bytes += old.record_len;
+        }
+
+        self.maybe_compact()
+    }
+
+    /// Deletes a key.
+    /// Returns whether the key existed.
+    pub fn delete(&mut self, key: &str) -> io::Result<bool> {
+        // If there's nothing to delete, don't bother writing
+        // a record for it.
+        if !self.index.contains_key(key) {
+            return Ok(false);
+        }
+
+        let position = self.append(RecordKind::Delete, key, &[])?;
+        if let Some(old) = self.index.remove(key) {
+            self.stale_bytes += old.record_len + position.record_len;
+        }
+
+        self.maybe_compact()?;
+
+        Ok(true)
+    }
+
+    /// Compacts the log if enough of it is stale.
+    fn maybe_compact(&mut self) -> io::Result<()> {
+        if self.stale_bytes >= COMPACTION_THRESHOLD {
+            self.compact()?;
+        }
+
+        Ok(())
+    }
+
+    /// Rewrites the log so that it only contains the newest
+    /// value for each key.
+    pub fn compact(&mut self) -> io::Result<()> {
+        // We write the new log to a separate file first.
+        // If we crash halfway through, the old log is still
+        // there and nothing is lost.
+        let temp_path = self.path.with_extension("kv.compact");
+        let mut temp = File::create(&temp_path)?;
+
+        // Collect the keys first, since we need to borrow
+        // self mutably to read each value.
+        let keys: Vec<String> = self.index.keys().cloned().collect();
+        for key in keys {
+            if let Some(value) = self.get(&key)? {
+                temp.write_all(&encode_record(RecordKind::Set, &key, &value))?;
+            }
+        }
+        temp.sync_all()?;
+
+        // Renaming a file is atomic: anyone looking at the path
+        // will either see the old log or the new one, never
+        // something in between.
+        fs::rename(&temp_path, &self.path)?;
+
+        self.file = OpenOptions::new().read(true).append(true).open(&self.path)?;
+        self.load()
+    }
+}
+
+/// Prints out how to use the program.
+fn print_usage() {
+    eprintln!("Usage:");
+    eprintln!("  get <key>");
+    eprintln!("  set <key> <value>");
+    eprintln!("  rm <key>");
+    eprintln!("  compact");
+}
+
+/// Runs the command given in `args` against the store.
+fn run(args: &[String]) -> io::Result<()> {
+    let mut store = KvStore::open(DATA_FILE)?;
+
+    // Matching on a slice lets us check the command and
+    // pull out its arguments at the same time.
+    match args {
+        [cmd, key] if cmd == "get" => match store.get(key)? {
+            // from_utf8_lossy replaces any invalid characters
+            // instead of failing.
+            Some(value) => println!("{}", String::from_utf8_lossy(&value)),
+            None => println!("{key} is not set."),
+        },
+        [cmd, key, value] if cmd == "set" => {
+            store.set(key, value.as_bytes())?;
+            println!("Set {key}.");
+        }
+        [cmd, key] if cmd == "rm" => {
+            if store.delete(key)? {
+                println!("Removed {key}.");
+            } else {
+                println!("{key} is not set.");
+            }
+        }
+        [cmd] if cmd == "compact" => {
+            let before = store.file.metadata()?.len();
+            store.compact()?;
+            let after = store.file.metadata()?.len();
+            println!("Compacted the log from {before} bytes to {after} bytes.");
+        }
+        _ => print_usage(),
+    }
+
+    Ok(())
+}
+
+fn main() {
+    // The first argument is the name of the program, so skip it.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    if let Err(err) = run(&args) {
+        eprintln!("Error: {err}");
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Opens a store in a new temporary directory.
+    /// The directory is deleted when the TempDir is dropped, so
+    /// it has to be kept around for as long as the store is used.
+    fn temp_store() -> (tempfile::TempDir, PathBuf, KvStore) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(DATA_FILE);
+        let store = KvStore::open(&path).unwrap();
+        (dir, path, store)
+    }
+
+    #[test]
+    fn sets_gets_and_deletes() {
+        let (_dir, _path, mut store) = temp_store();
+
+        store.set("name", b"Ferris").unwrap();
+        store.set("name", b"Corro").unwrap();
+        store.set("empty", b"").unwrap();
+
+        assert_eq!(store.get("name").unwrap(), Some(b"Corro".to_vec()));
+        assert_eq!(store.get("empty").unwrap(), Some(Vec::new()));
+        assert_eq!(store.get("missing").unwrap(), None);
+
+        assert!(store.delete("name").unwrap());
+        assert!(!store.delete("name").unwrap());
+        assert_eq!(store.get("name").unwrap(), None);
+    }
+
+    #[test]
+    fn keeps_values_after_reopening() {
+        let (_dir, path, mut store) = temp_store();
+        store.set("a", b"1").unwrap();
+        store.set("b", b"2").unwrap();
+        store.set("a", b"3").unwrap();
+        store.delete("b").unwrap();
+        drop(store);
+
+        let mut store = KvStore::open(&path).unwrap();
+        assert_eq!(store.get("a").unwrap(), Some(b"3".to_vec()));
+        assert_eq!(store.get("b").unwrap(), None);
+        // The first "a", and both records for "b", are stale.
+        assert!(store.stale_bytes > 0);
+    }
+
+    #[test]
+    fn compaction_keeps_only_the_newest_values() {
+        let (_dir, path, mut store) = temp_store();
+        for i in 0..100 {
+            store.set("counter", i.to_string().as_bytes()).unwrap();
+        }
+        store.set("gone", b"soon").unwrap();
+        store.delete("gone").unwrap();
+
+        let before = fs::metadata(&path).unwrap().len();
+        store.compact().unwrap();
+        let after = fs::metadata(&path).unwrap().len();
+
+        assert!(after < before);
+        assert_eq!(store.stale_bytes, 0);
+        assert_eq!(store.get("counter").unwrap(), Some(b"99".to_vec()));
+        assert_eq!(store.get("gone").unwrap(), None);
+
+        // The compacted log still works after reopening.
+        drop(store);
+        let mut store = KvStore::open(&path).unwrap();
+        assert_eq!(store.get("counter").unwrap(), Some(b"99".to_vec()));
+    }
+
+    #[test]
+    fn discards_a_torn_last_record() {
+        let (_dir, path, mut store) = temp_store();
+        store.set("safe", b"value").unwrap();
+        drop(store);
+        let good_len = fs::metadata(&path).unwrap().len();
+
+        // Half of a record, like a crash in the middle of a write.
+        let record = encode_record(RecordKind::Set, "torn", b"value");
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&record[..record.len() / 2]).unwrap();
+        drop(file);
+
+        let mut store = KvStore::open(&path).unwrap();
+        assert_eq!(store.get("safe").unwrap(), Some(b"value".to_vec()));
+        assert_eq!(store.get("torn").unwrap(), None);
+        assert_eq!(fs::metadata(&path).unwrap().len(), good_len);
+    }
+
+    #[test]
+    fn discards_a_record_torn_partway_through_its_value() {
+        let (_dir, path, mut store) = temp_store();
+        store.set("safe", b"value").unwrap();
+        drop(store);
+        let good_len = fs::metadata(&path).unwrap().len();
+
+        // The whole header and key made it to the disk, but only
+        // part of the value did.
+        let record = encode_record(RecordKind::Set, "torn", b"a long value");
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&record[..record.len() - 4]).unwrap();
+        drop(file);
+
+        let mut store = KvStore::open(&path).unwrap();
+        assert_eq!(store.get("safe").unwrap(), Some(b"value".to_vec()));
+        assert_eq!(store.get("torn").unwrap(), None);
+        assert_eq!(fs::metadata(&path).unwrap().len(), good_len);
+
+        // New records go where the torn one was.
+        store.set("after", b"crash").unwrap();
+        drop(store);
+        let mut store = KvStore::open(&path).unwrap();
+        assert_eq!(store.get("after").unwrap(), Some(b"crash".to_vec()));
+    }
+
+    #[test]
+    fn does_not_allocate_for_huge_lengths() {
+        let (_dir, path, mut store) = temp_store();
+        store.set("safe", b"value").unwrap();
+        drop(store);
+
+        // A header that says the key is 4GB long. It's treated as a
+        // torn record, instead of allocating 4GB to read it into.
+        let mut header = vec![0; HEADER_SIZE as usize];
+        header[5..9].copy_from_slice(&u32::MAX.to_le_bytes());
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&header).unwrap();
+        drop(file);
+
+        let mut store = KvStore::open(&path).unwrap();
+        assert_eq!(store.get("safe").unwrap(), Some(b"value".to_vec()));
+    }
+
+    #[test]
+    fn refuses_to_throw_away_records_after_damage() {
+        let (_dir, path, mut store) = temp_store();
+        store.set("first", b"1").unwrap();
+        store.set("second", b"2").unwrap();
+        drop(store);
+        let len = fs::metadata(&path).unwrap().len();
+
+        // Flip a bit in the first record's value.
+        let mut bytes = fs::read(&path).unwrap();
+        let first_len = encode_record(RecordKind::Set, "first", b"1").len();
+        bytes[first_len - 1] ^= 1;
+        fs::write(&path, bytes).unwrap();
+
+        let err = KvStore::open(&path).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        // The second record is still in the file.
+        assert_eq!(fs::metadata(&path).unwrap().len(), len);
+    }
+
+    #[test]
+    fn refuses_to_throw_away_records_after_a_damaged_length() {
+        let (_dir, path, mut store) = temp_store();
+        store.set("a", b"one").unwrap();
+        store.set("b", b"two").unwrap();
+        store.set("c", b"three").unwrap();
+        drop(store);
+        let len = fs::metadata(&path).unwrap().len();
+
+        // Flip a bit in the first record's key length, so that it
+        // claims to be much longer than the rest of the file.
+        let mut bytes = fs::read(&path).unwrap();
+        bytes[8] ^= 1;
+        fs::write(&path, bytes).unwrap();
+
+        let err = KvStore::open(&path).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        // The other records are still in the file.
+        assert_eq!(fs::metadata(&path).unwrap().len(), len);
+    }
+}
@@ -0,0 +1,55 @@
+// Errors are a lot more useful if they tell you where
+// the problem is.
+// Every error that the lexer or parser creates carries
+// the position in the input that caused it.
+
+use std::fmt;
+
+/// A position in the input text.
+/// Both the line and column start at 1, which is what
+/// text editors show.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    /// The line number.
+    pub line: usize,
+    /// The column number (in characters, not bytes).
+    pub column: usize,
+}
+
+impl Position {
+    /// The position of the first character.
+    pub fn start() -> Position {
+        Position { line: 1, column: 1 }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// An error found while lexing or parsing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// What went wrong.
+    pub message: String,
+    /// Where it went wrong.
+    pub position: Position,
+}
+
+impl ParseError {
+    /// Creates a new error at the given position.
+    pub fn new(message: impl Into<String>, position: Position) -> ParseError {
+        ParseError {
+            message: message.into(),
+            position,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}", self.message, self.position)
+    }
+}
@@ -0,0 +1,440 @@
+// The lexer (also called a tokenizer) is the first step
+// of parsing.
+// It turns a string of characters into a list of tokens,
+// which are the "words" of the language: punctuation like `{`
+// and `,`, strings, numbers, and keywords like `true`.
+// It also throws away anything that doesn't matter, like
+// whitespace.
+//
+// Doing this first means that the parser doesn't need
+// to worry about individual characters at all.
+
+use crate::error::{ParseError, Position};
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// The different kinds of tokens in JSON.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    /// `{`
+    LeftBrace,
+    /// `}`
+    RightBrace,
+    /// `[`
+    LeftBracket,
+    /// `]`
+    RightBracket,
+    /// `:`
+    Colon,
+    /// `,`
+    Comma,
+    /// A string, with its escape sequences already decoded.
+    String(String),
+    /// A number.
+    Number(f64),
+    /// `true` or `false`.
+    Bool(bool),
+    /// `null`
+    Null,
+}
+
+/// A token, along with where it starts in the input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub position: Position,
+}
+
+/// Turns JSON text into tokens.
+pub struct Lexer<'a> {
+    /// The characters that haven't been read yet.
+    /// Peekable lets us look at the next character
+    /// without taking it.
+    chars: Peekable<Chars<'a>>,
+    /// The position of the next character.
+    position: Position,
+}
+
+impl<'a> Lexer<'a> {
+    /// Creates a lexer for the given input.
+    pub fn new(input: &'a str) -> Lexer<'a> {
+        Lexer {
+            chars: input.chars().peekable(),
+            position: Position::start(),
+        }
+    }
+
+    /// Returns the position just after the last character,
+    /// which is where "unexpected end of input" errors point.
+    pub fn end_position(&self) -> Position {
+        self.position
+    }
+
+    /// Takes the next character and moves the position forward.
+    fn next_char(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+
+        if c == '\n' {
+            self.position.line += 1;
+            self.position.column = 1;
+        } else {
+            self.position.column += 1;
+        }
+
+        Some(c)
+    }
+
+    /// Takes the next character, or returns an error if
+    /// the input has ended.
+    fn expect_char(&mut self) -> Result<char, ParseError> {
+        self.next_char()
+            .ok_or_else(|| ParseError::new("unexpected end of input", self.position))
+    }
+
+    /// Skips over any whitespace.
+    fn skip_whitespace(&mut self) {
+        // JSON only allows these four whitespace characters.
+        while let Some(' ' | '\t' | '\n' | '\r') = self.chars.peek() {
+            self.next_char();
+        }
+    }
+
+    /// Reads the next token.
+    /// Returns Ok(None) at the end of the input.
+    pub fn next_token(&mut self) -> Result<Option<Token>, ParseError> {
+        self.skip_whitespace();
+
+        let position = self.position;
+        let Some(&c) = self.chars.peek() else {
+            return Ok(None);
+        };
+
+        let kind = match c {
+            '{' | '}' | '[' | ']' | ':' | ',' => {
+                self.next_char();
+
+                match c {
+                    '{' => TokenKind::LeftBrace,
+                    '}' => TokenKind::RightBrace,
+                    '[' => TokenKind::LeftBracket,
+                    ']' => TokenKind::RightBracket,
+                    ':' => TokenKind::Colon,
+                    _ => TokenKind::Comma,
+                }
+            }
+            '"' => TokenKind::String(self.read_string()?),
+            '-' | '0'..='9' => TokenKind::Number(self.read_number()?),
+            'a'..='z' => self.read_keyword()?,
+            _ => return Err(ParseError::new(format!("unexpected character '{c}'"), position)),
+        };
+
+        Ok(Some(Token { kind, position }))
+    }
+
+    /// Reads `true`, `false`, or `null`.
+    fn read_keyword(&mut self) -> Result<TokenKind, ParseError> {
+        let position = self.position;
+        let mut word = String::new();
+
+        while let Some(&c) = self.chars.peek() {
+            if !c.is_ascii_alphabetic() {
+                break;
+            }
+
+            word.push(c);
+            self.next_char();
+        }
+
+        match word.as_str() {
+            "true" => Ok(TokenKind::Bool(true)),
+            "false" => Ok(TokenKind::Bool(false)),
+            "null" => Ok(TokenKind::Null),
+            _ => Err(ParseError::new(format!("unknown keyword '{word}'"), position)),
+        }
+    }
+
+    /// Reads a number.
+    ///
+    /// JSON numbers are stricter than Rust's: there can't be
+    /// a leading `+`, leading zeros (like `01`), or a trailing `.`.
+    /// So, we check the format ourselves, then let Rust do the
+    /// actual conversion.
+    fn read_number(&mut self) -> Result<f64, ParseError> {
+        let position = self.position;
+        let mut text = String::new();
+
+        if self.chars.peek() == Some(&'-') {
+            text.push('-');
+            self.next_char();
+        }
+
+        // The integer part is either a single 0, or a
+        // non-zero digit followed by any digits.
+        match self.chars.peek() {
+            Some('0') => {
+                text.push('0');
+                self.next_char();
+            }
+            Some('1'..='9') => self.read_digits(&mut text),
+            _ => return Err(ParseError::new("expected a digit", self.position)),
+        }
+
+        if self.chars.peek() == Some(&'.') {
+            text.push('.');
+            self.next_char();
+
+            if !matches!(self.chars.peek(), Some('0'..='9')) {
+                return Err(ParseError::new("expected a digit after '.'", self.position));
+            }
+            self.read_digits(&mut text);
+        }
+
+        if let Some('e' | 'E') = self.chars.peek() {
+            text.push('e');
+            self.next_char();
+
+            if let Some(&sign @ ('+' | '-')) = self.chars.peek() {
+                text.push(sign);
+                self.next_char();
+            }
+
+            if !matches!(self.chars.peek(), Some('0'..='9')) {
+                return Err(ParseError::new("expected a digit in exponent", self.position));
+            }
+            self.read_digits(&mut text);
+        }
+
+        // A number like 01 would stop after the 0, leaving
+        // a 1 behind. Catch that here so that the error message
+        // makes sense.
+        if let Some('0'..='9') = self.chars.peek() {
+            return Err(ParseError::new("numbers can't have leading zeros", position));
+        }
+
+        let number: f64 = text
+            .parse()
+            .map_err(|_| ParseError::new(format!("invalid number '{text}'"), position))?;
+
+        // Numbers too big for an f64 (like 1e999) turn into infinity,
+        // which JSON doesn't have, so we couldn't print it back out.
+        if !number.is_finite() {
+            return Err(ParseError::new(format!("number '{text}' is too big"), position));
+        }
+
+        Ok(number)
+    }
+
+    /// Reads digits until something that isn't a digit is found.
+    fn read_digits(&mut self, text: &mut String) {
+        while let Some(&c @ '0'..='9') = self.chars.peek() {
+            text.push(c);
+            self.next_char();
+        }
+    }
+
+    /// Reads a string, decoding any escape sequences.
+    fn read_string(&mut self) -> Result<String, ParseError> {
+        let start = self.position;
+        // Skip the opening quote.
+        self.next_char();
+
+        let mut string = String::new();
+
+        loop {
+            let position = self.position;
+            let Some(c) = self.next_char() else {
+                return Err(ParseError::new("unterminated string", start));
+            };
+
+            match c {
+                '"' => return Ok(string),
+                '\\' => string.push(self.read_escape(position)?),
+                // Control characters (like newlines) aren't
+                // allowed inside of strings. They need to be
+                // escaped instead.
+                '\u{0}'..='\u{1f}' => {
+                    return Err(ParseError::new("control characters must be escaped", position))
+                }
+                _ => string.push(c),
+            }
+        }
+    }
+
+    /// Reads the part of an escape sequence after the `\`.
+    /// `position` is where the `\` was.
+    fn read_escape(&mut self, position: Position) -> Result<char, ParseError> {
+        let c = self.expect_char()?;
+
+        Ok(match c {
+            '"' => '"',
+            '\\' => '\\',
+            '/' => '/',
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'u' => {
+                let code = self.read_hex()?;
+
+                // Characters outside of the Basic Multilingual Plane
+                // (like most emoji) don't fit in four hex digits.
+                // JSON writes them as two escapes, called a
+                // surrogate pair, which we need to combine.
+                if (0xD800..0xDC00).contains(&code) {
+                    if self.next_char() != Some('\\') || self.next_char() != Some('u') {
+                        return Err(ParseError::new("expected a low surrogate", position));
+                    }
+
+                    let low = self.read_hex()?;
+                    if !(0xDC00..0xE000).contains(&low) {
+                        return Err(ParseError::new("invalid low surrogate", position));
+                    }
+
+                    let combined = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                    // from_u32 returns None if the number isn't a
+                    // valid character, but every surrogate pair
+                    // makes a valid one.
+                    char::from_u32(combined).unwrap()
+                } else {
+                    char::from_u32(code)
+                        .ok_or_else(|| ParseError::new("invalid unicode escape", position))?
+                }
+            }
+            _ => return Err(ParseError::new(format!("invalid escape '\\{c}'"), position)),
+        })
+    }
+
+    /// Reads the four hex digits of a `\u` escape.
+    fn read_hex(&mut self) -> Result<u32, ParseError> {
+        let mut code = 0;
+
+        for _ in 0..4 {
+            let position = self.position;
+            let c = self.expect_char()?;
+            let digit = c
+                .to_digit(16)
+                .ok_or_else(|| ParseError::new(format!("invalid hex digit '{c}'"), position))?;
+
+            code = code * 16 + digit;
+        }
+
+        Ok(code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads every token in the input.
+    fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
+        let mut lexer = Lexer::new(input);
+        let mut tokens = Vec::new();
+
+        while let Some(token) = lexer.next_token()? {
+            tokens.push(token);
+        }
+
+        Ok(tokens)
+    }
+
+    /// Lexes the input and returns just the token kinds.
+    fn kinds(input: &str) -> Vec<TokenKind> {
+        tokenize(input)
+            .unwrap()
+            .into_iter()
+            .map(|token| token.kind)
+            .collect()
+    }
+
+    /// Lexes the input and returns the error.
+    fn error(input: &str) -> ParseError {
+        tokenize(input).unwrap_err()
+    }
+
+    #[test]
+    fn punctuation_and_keywords() {
+        assert_eq!(
+            kinds("{ } [ ] : , true false null"),
+            vec![
+                TokenKind::LeftBrace,
+                TokenKind::RightBrace,
+                TokenKind::LeftBracket,
+                TokenKind::RightBracket,
+                TokenKind::Colon,
+                TokenKind::Comma,
+                TokenKind::Bool(true),
+                TokenKind::Bool(false),
+                TokenKind::Null,
+            ]
+        );
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(
+            kinds("0 -0 12 -3.5 1e3 2.5E-2 7e+1"),
+            vec![
+                TokenKind::Number(0.0),
+                TokenKind::Number(-0.0),
+                TokenKind::Number(12.0),
+                TokenKind::Number(-3.5),
+                TokenKind::Number(1000.0),
+                TokenKind::Number(0.025),
+                TokenKind::Number(70.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_numbers() {
+        assert_eq!(error("01").message, "numbers can't have leading zeros");
+        assert_eq!(error("1.").message, "expected a digit after '.'");
+        assert_eq!(error("1e").message, "expected a digit in exponent");
+        assert_eq!(error("-").message, "expected a digit");
+        assert_eq!(error("1e999").message, "number '1e999' is too big");
+        assert_eq!(error("-1e309").message, "number '-1e309' is too big");
+    }
+
+    #[test]
+    fn string_escapes() {
+        assert_eq!(
+            kinds(r#""a\"b\\c\/d\n\tA""#),
+            vec![TokenKind::String("a\"b\\c/d\n\tA".into())]
+        );
+    }
+
+    #[test]
+    fn surrogate_pairs() {
+        assert_eq!(kinds(r#""\ud83e\udd80""#), vec![TokenKind::String("🦀".into())]);
+        assert_eq!(error(r#""\ud83e""#).message, "expected a low surrogate");
+    }
+
+    #[test]
+    fn invalid_strings() {
+        assert_eq!(error("\"abc").message, "unterminated string");
+        assert_eq!(error("\"a\nb\"").message, "control characters must be escaped");
+        assert_eq!(error(r#""\x""#).message, "invalid escape '\\x'");
+        assert_eq!(error(r#""\u12g4""#).message, "invalid hex digit 'g'");
+    }
+
+    #[test]
+    fn unknown_input() {
+        assert_eq!(error("nul").message, "unknown keyword 'nul'");
+        assert_eq!(error("@").message, "unexpected character '@'");
+    }
+
+    #[test]
+    fn positions() {
+        let tokens = tokenize("{\n  \"a\": 1\n}").unwrap();
+        let positions: Vec<_> = tokens.iter().map(|token| token.position.to_string()).collect();
+
+        assert_eq!(positions, vec!["1:1", "2:3", "2:6", "2:8", "3:1"]);
+    }
+
+    #[test]
+    fn error_positions() {
+        assert_eq!(error("[1,\n  @]").position, Position { line: 2, column: 3 });
+    }
+}
@@ -0,0 +1,56 @@
+// This is a JSON parser written from scratch.
+// Run it with a file (`cargo run -- data.json`), or
+// type some JSON in and press Ctrl+D when you're done.
+// It'll print the JSON back out nicely formatted,
+// or show you exactly where the problem is.
+//
+// Try running `cargo test` to run the tests at the bottom
+// of lexer.rs and parser.rs.
+
+// These lines tell Rust about the other files in src/.
+// Each one becomes a module, which we can access with
+// `crate::name` (or just `name` from this file).
+mod error;
+mod lexer;
+mod parser;
+mod value;
+
+use std::io::Read;
+
+/// Prints an error along with the line that caused it,
+/// and an arrow pointing at the exact column.
+fn print_error(input: &str, err: &error::ParseError) {
+    eprintln!("Error: {err}");
+
+    // lines() doesn't include the last line if it's empty,
+    // so fall back to an empty string.
+    let line = input.lines().nth(err.position.line - 1).unwrap_or("");
+    let padding = " ".repeat(err.position.column - 1);
+
+    eprintln!("  {line}");
+    eprintln!("  {padding}^");
+}
+
+fn main() {
+    let mut input = String::new();
+
+    // If a file name was passed, read that.
+    // Otherwise, read everything from standard input.
+    let result = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(path).map(|contents| input = contents),
+        None => std::io::stdin().read_to_string(&mut input).map(|_| ()),
+    };
+
+    if let Err(err) = result {
+        eprintln!("Error: could not read input: {err}");
+        std::process::exit(1);
+    }
+
+    match parser::parse(&input) {
+        Ok(value) => println!("{}", value.pretty(2)),
+        Err(err) => {
+            print_error(&input, &err);
+            std::process::exit(1);
+        }
+    }
+}
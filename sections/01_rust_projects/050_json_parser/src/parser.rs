@@ -0,0 +1,296 @@
+// The parser turns tokens into a tree of values.
+// It's called a recursive descent parser, because there's
+// one function for each part of the grammar, and they call each
+// other (recursively) to parse values inside of values.
+//
+// For JSON, the grammar looks like this:
+//
+// value  = object | array | STRING | NUMBER | BOOL | NULL
+// object = "{" (STRING ":" value ("," STRING ":" value)*)? "}"
+// array  = "[" (value ("," value)*)? "]"
+//
+// parse_value, parse_object, and parse_array below each
+// handle one of these lines.
+
+use crate::error::{ParseError, Position};
+use crate::lexer::{Lexer, Token, TokenKind};
+use crate::value::Value;
+
+/// How many arrays and objects can be inside each other.
+/// Each level is another function call, so without a limit,
+/// input like `[[[[...` could overflow the stack and crash.
+const MAX_DEPTH: usize = 128;
+
+/// Parses a JSON document into a Value.
+pub fn parse(input: &str) -> Result<Value, ParseError> {
+    let mut lexer = Lexer::new(input);
+    let mut tokens = Vec::new();
+
+    while let Some(token) = lexer.next_token()? {
+        tokens.push(token);
+    }
+
+    // We need the end position for errors about the input
+    // ending too early.
+    let end = lexer.end_position();
+    let mut parser = Parser { tokens, index: 0, end, depth: 0 };
+
+    let value = parser.parse_value()?;
+
+    // A valid document is exactly one value.
+    // Anything after it (like `1 2`) is an error.
+    if let Some(token) = parser.peek() {
+        return Err(ParseError::new("expected end of input", token.position));
+    }
+
+    Ok(value)
+}
+
+/// Keeps track of where we are in the list of tokens.
+struct Parser {
+    tokens: Vec<Token>,
+    /// The index of the next token to read.
+    index: usize,
+    /// The position just after the end of the input.
+    end: Position,
+    /// How many arrays and objects we're inside of.
+    depth: usize,
+}
+
+impl Parser {
+    /// Returns the next token without taking it.
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index)
+    }
+
+    /// Takes the next token, or returns an error if there
+    /// aren't any left.
+    fn next(&mut self) -> Result<Token, ParseError> {
+        let token = self
+            .tokens
+            .get(self.index)
+            .cloned()
+            .ok_or_else(|| ParseError::new("unexpected end of input", self.end))?;
+
+        self.index += 1;
+
+        Ok(token)
+    }
+
+    /// Takes the next token and checks that it's the expected kind.
+    /// `what` is used in the error message.
+    fn expect(&mut self, kind: TokenKind, what: &str) -> Result<Token, ParseError> {
+        let token = self.next()?;
+
+        if token.kind == kind {
+            Ok(token)
+        } else {
+            Err(ParseError::new(format!("expected {what}"), token.position))
+        }
+    }
+
+    /// Parses any value.
+    fn parse_value(&mut self) -> Result<Value, ParseError> {
+        let token = self.next()?;
+
+        match token.kind {
+            TokenKind::Null => Ok(Value::Null),
+            TokenKind::Bool(b) => Ok(Value::Bool(b)),
+            TokenKind::Number(n) => Ok(Value::Number(n)),
+            TokenKind::String(s) => Ok(Value::String(s)),
+            TokenKind::LeftBracket | TokenKind::LeftBrace => {
+                if self.depth == MAX_DEPTH {
+                    return Err(ParseError::new("too deeply nested", token.position));
+                }
+
+                self.depth += 1;
+                let value = if token.kind == TokenKind::LeftBracket {
+                    self.parse_array()
+                } else {
+                    self.parse_object()
+                };
+                self.depth -= 1;
+
+                value
+            }
+            _ => Err(ParseError::new("expected a value", token.position)),
+        }
+    }
+
+    /// Parses the rest of an array, after the `[`.
+    fn parse_array(&mut self) -> Result<Value, ParseError> {
+        let mut items = Vec::new();
+
+        // An empty array.
+        if let Some(Token { kind: TokenKind::RightBracket, .. }) = self.peek() {
+            self.index += 1;
+            return Ok(Value::Array(items));
+        }
+
+        loop {
+            // This is the recursive part.
+            // An array can contain any value, including
+            // other arrays.
+            items.push(self.parse_value()?);
+
+            let token = self.next()?;
+            match token.kind {
+                TokenKind::Comma => continue,
+                TokenKind::RightBracket => return Ok(Value::Array(items)),
+                _ => return Err(ParseError::new("expected ',' or ']'", token.position)),
+            }
+        }
+    }
+
+    /// Parses the rest of an object, after the `{`.
+    fn parse_object(&mut self) -> Result<Value, ParseError> {
+        let mut entries = Vec::new();
+
+        // An empty object.
+        if let Some(Token { kind: TokenKind::RightBrace, .. }) = self.peek() {
+            self.index += 1;
+            return Ok(Value::Object(entries));
+        }
+
+        loop {
+            let token = self.next()?;
+            let TokenKind::String(key) = token.kind else {
+                return Err(ParseError::new("expected a string key", token.position));
+            };
+
+            self.expect(TokenKind::Colon, "':'")?;
+            entries.push((key, self.parse_value()?));
+
+            let token = self.next()?;
+            match token.kind {
+                TokenKind::Comma => continue,
+                TokenKind::RightBrace => return Ok(Value::Object(entries)),
+                _ => return Err(ParseError::new("expected ',' or '}'", token.position)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses the input and returns the error message and
+    /// position as a string, like "expected a value at 1:2".
+    fn error(input: &str) -> String {
+        parse(input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn scalars() {
+        assert_eq!(parse("null"), Ok(Value::Null));
+        assert_eq!(parse("true"), Ok(Value::Bool(true)));
+        assert_eq!(parse(" -1.5 "), Ok(Value::Number(-1.5)));
+        assert_eq!(parse(r#""hi""#), Ok(Value::String("hi".into())));
+    }
+
+    #[test]
+    fn empty_containers() {
+        assert_eq!(parse("[]"), Ok(Value::Array(vec![])));
+        assert_eq!(parse("{ }"), Ok(Value::Object(vec![])));
+    }
+
+    #[test]
+    fn nested_values() {
+        let value = parse(r#"{"a": [1, {"b": null}], "c": "d"}"#).unwrap();
+
+        assert_eq!(
+            value,
+            Value::Object(vec![
+                (
+                    "a".into(),
+                    Value::Array(vec![
+                        Value::Number(1.0),
+                        Value::Object(vec![("b".into(), Value::Null)]),
+                    ])
+                ),
+                ("c".into(), Value::String("d".into())),
+            ])
+        );
+    }
+
+    #[test]
+    fn object_keys_keep_their_order() {
+        let Ok(Value::Object(entries)) = parse(r#"{"z": 1, "a": 2, "m": 3}"#) else {
+            panic!("expected an object");
+        };
+        let keys: Vec<_> = entries.iter().map(|(key, _)| key.as_str()).collect();
+
+        assert_eq!(keys, vec!["z", "a", "m"]);
+    }
+
+    #[test]
+    fn deeply_nested_arrays() {
+        let input = format!("{}{}", "[".repeat(100), "]".repeat(100));
+
+        assert!(parse(&input).is_ok());
+    }
+
+    #[test]
+    fn too_deeply_nested() {
+        let input = format!("{}{}", "[".repeat(128), "]".repeat(128));
+        assert!(parse(&input).is_ok());
+
+        let input = format!("{}{}", "[{\"a\":".repeat(100), "1}]".repeat(100));
+        assert_eq!(error(&input), "too deeply nested at 1:385");
+
+        // Far too deep to parse recursively, if there were no limit.
+        let input = "[".repeat(1_000_000);
+        assert_eq!(error(&input), "too deeply nested at 1:129");
+    }
+
+    #[test]
+    fn syntax_errors() {
+        assert_eq!(error(""), "unexpected end of input at 1:1");
+        assert_eq!(error("[1 2]"), "expected ',' or ']' at 1:4");
+        assert_eq!(error("[1,]"), "expected a value at 1:4");
+        assert_eq!(error(r#"{"a" 1}"#), "expected ':' at 1:6");
+        assert_eq!(error("{1: 2}"), "expected a string key at 1:2");
+        assert_eq!(error(r#"{"a": 1,}"#), "expected a string key at 1:9");
+        assert_eq!(error(r#"{"a": 1 "b": 2}"#), "expected ',' or '}' at 1:9");
+        assert_eq!(error("1 2"), "expected end of input at 1:3");
+    }
+
+    #[test]
+    fn unexpected_end_points_at_the_end() {
+        assert_eq!(error("[1,\n 2"), "unexpected end of input at 2:3");
+    }
+
+    #[test]
+    fn lexer_errors_are_passed_through() {
+        assert_eq!(error("[1, @]"), "unexpected character '@' at 1:5");
+    }
+
+    #[test]
+    fn compact_round_trip() {
+        let input = r#"{"name":"Ferris","tags":["crab","rust"],"age":8,"ok":true,"x":null}"#;
+        let value = parse(input).unwrap();
+
+        assert_eq!(value.to_string(), input);
+        assert_eq!(parse(&value.to_string()), Ok(value));
+    }
+
+    #[test]
+    fn escapes_round_trip() {
+        let value = Value::String("quote \" slash \\ newline \n bell \u{7}".into());
+
+        assert_eq!(value.to_string(), r#""quote \" slash \\ newline \n bell \u0007""#);
+        assert_eq!(parse(&value.to_string()), Ok(value));
+    }
+
+    #[test]
+    fn pretty_printing() {
+        let value = parse(r#"{"a": [1, 2], "b": {}, "c": []}"#).unwrap();
+
+        assert_eq!(
+            value.pretty(2),
+            "{\n  \"a\": [\n    1,\n    2\n  ],\n  \"b\": {},\n  \"c\": []\n}"
+        );
+        assert_eq!(parse(&value.pretty(4)), Ok(value));
+    }
+}
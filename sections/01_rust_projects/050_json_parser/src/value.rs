@@ -0,0 +1,138 @@
+// This is what our parser produces: a tree of values.
+// Each variant of the enum is one of JSON's types, and arrays
+// and objects hold more values inside of them, which is how
+// we can represent JSON that's nested as deep as we want.
+
+use std::fmt::{self, Write};
+
+/// A JSON value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    /// An object's keys and values.
+    /// We use a Vec instead of a HashMap so that the keys
+    /// stay in the same order that they were written in.
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Formats the value over multiple lines, indented
+    /// with the given number of spaces per level.
+    pub fn pretty(&self, indent: usize) -> String {
+        let mut out = String::new();
+        // Writing to a String can't fail, so it's safe to unwrap.
+        self.write_pretty(&mut out, indent, 0).unwrap();
+        out
+    }
+
+    /// Writes the value in pretty form.
+    /// `depth` is how many levels deep we currently are.
+    fn write_pretty(&self, out: &mut String, indent: usize, depth: usize) -> fmt::Result {
+        // " ".repeat(n) creates a String with n spaces.
+        let inner = " ".repeat(indent * (depth + 1));
+        let outer = " ".repeat(indent * depth);
+
+        match self {
+            Value::Array(items) if !items.is_empty() => {
+                out.push_str("[\n");
+
+                for (i, item) in items.iter().enumerate() {
+                    out.push_str(&inner);
+                    item.write_pretty(out, indent, depth + 1)?;
+
+                    if i + 1 < items.len() {
+                        out.push(',');
+                    }
+                    out.push('\n');
+                }
+
+                write!(out, "{outer}]")
+            }
+            Value::Object(entries) if !entries.is_empty() => {
+                out.push_str("{\n");
+
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    out.push_str(&inner);
+                    write_string(out, key)?;
+                    out.push_str(": ");
+                    value.write_pretty(out, indent, depth + 1)?;
+
+                    if i + 1 < entries.len() {
+                        out.push(',');
+                    }
+                    out.push('\n');
+                }
+
+                write!(out, "{outer}}}")
+            }
+            // Everything else (including empty arrays and objects)
+            // looks the same as it does in compact form.
+            _ => write!(out, "{self}"),
+        }
+    }
+}
+
+/// Writes a string with quotes around it, escaping
+/// any characters that need it.
+fn write_string(out: &mut impl Write, string: &str) -> fmt::Result {
+    out.write_char('"')?;
+
+    for c in string.chars() {
+        match c {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '\n' => out.write_str("\\n")?,
+            '\r' => out.write_str("\\r")?,
+            '\t' => out.write_str("\\t")?,
+            // Any other control characters use \u escapes.
+            // The :04x means to print the number in hex,
+            // padded with zeros to four digits.
+            '\u{0}'..='\u{1f}' => write!(out, "\\u{:04x}", c as u32)?,
+            _ => out.write_char(c)?,
+        }
+    }
+
+    out.write_char('"')
+}
+
+// Display prints the value as compact JSON,
+// with no extra whitespace.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => write!(f, "null"),
+            Value::Bool(b) => write!(f, "{b}"),
+            Value::Number(n) => write!(f, "{n}"),
+            Value::String(s) => write_string(f, s),
+            Value::Array(items) => {
+                write!(f, "[")?;
+
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+
+                write!(f, "]")
+            }
+            Value::Object(entries) => {
+                write!(f, "{{")?;
+
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{value}")?;
+                }
+
+                write!(f, "}}")
+            }
+        }
+    }
+}
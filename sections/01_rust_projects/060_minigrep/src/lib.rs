@@ -0,0 +1,420 @@
+// This is the library half of our grep clone.
+// All of the actual work happens here, and main.rs just
+// passes the command-line arguments in.
+// Splitting it up like this means that our tests (at the
+// bottom of this file and in the tests/ folder) can call
+// these functions directly.
+
+use regex::{Regex, RegexBuilder};
+use std::error::Error;
+use std::fs;
+use std::io::{self, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// The escape code that starts highlighted (bold red) text.
+const HIGHLIGHT_START: &str = "\x1b[1;31m";
+/// The escape code that goes back to normal text.
+const HIGHLIGHT_END: &str = "\x1b[0m";
+
+/// The options that the program was run with.
+#[derive(Debug, PartialEq)]
+pub struct Config {
+    /// What to search for.
+    pub query: String,
+    /// The files and directories to search in.
+    pub paths: Vec<PathBuf>,
+    /// Whether uppercase and lowercase letters should match each other.
+    pub ignore_case: bool,
+    /// Whether the query is a regular expression instead of plain text.
+    pub regex: bool,
+    /// Whether matches should be highlighted with color.
+    pub color: bool,
+}
+
+impl Config {
+    /// Builds a Config from the program's arguments
+    /// (not including the program name).
+    ///
+    /// This takes in an iterator instead of a Vec so that
+    /// we can pass `std::env::args()` in directly, and tests
+    /// can pass in whatever they want.
+    pub fn build(args: impl Iterator<Item = String>) -> Result<Config, String> {
+        let mut query = None;
+        let mut paths = Vec::new();
+        let mut ignore_case = false;
+        let mut regex = false;
+        let mut color = true;
+        // Set once we've seen `--`.
+        let mut only_positional = false;
+
+        for arg in args {
+            // Everything after `--` is the query or a path, even if
+            // it starts with a dash. That's how to search for
+            // something like `-foo`.
+            let is_flag = !only_positional && arg.starts_with('-') && arg.len() > 1;
+
+            if !is_flag {
+                // The first argument that isn't a flag is the query,
+                // and the rest are paths.
+                if query.is_none() {
+                    query = Some(arg);
+                } else {
+                    paths.push(PathBuf::from(arg));
+                }
+                continue;
+            }
+
+            match arg.as_str() {
+                "--" => only_positional = true,
+                "-i" | "--ignore-case" => ignore_case = true,
+                "-e" | "--regex" => regex = true,
+                "--no-color" => color = false,
+                _ => return Err(format!("unknown flag '{arg}'")),
+            }
+        }
+
+        let Some(query) = query else {
+            return Err("missing the search query".into());
+        };
+
+        // Search the current directory if no paths were given.
+        if paths.is_empty() {
+            paths.push(PathBuf::from("."));
+        }
+
+        Ok(Config {
+            query,
+            paths,
+            ignore_case,
+            regex,
+            color,
+        })
+    }
+}
+
+/// A line that contains at least one match.
+#[derive(Debug, PartialEq)]
+pub struct Match<'a> {
+    /// The line number (starting at 1).
+    pub line_number: usize,
+    /// The text of the line.
+    pub line: &'a str,
+    /// Where each match is in the line, in bytes.
+    pub ranges: Vec<Range<usize>>,
+}
+
+/// Turns the query into a Regex that we can search with.
+///
+/// Even when the query isn't a regular expression, we still
+/// use a Regex to search.
+/// regex::escape turns characters that have special meanings
+/// (like `.` and `*`) into plain characters, so `1.5` only
+/// matches "1.5" and not "125".
+pub fn build_pattern(config: &Config) -> Result<Regex, regex::Error> {
+    let pattern = if config.regex {
+        config.query.clone()
+    } else {
+        regex::escape(&config.query)
+    };
+
+    RegexBuilder::new(&pattern)
+        .case_insensitive(config.ignore_case)
+        .build()
+}
+
+// The 'a here says that the Matches we return point into
+// `contents`, so they can't outlive it.
+// This lets us return pieces of the text without copying them.
+
+/// Finds every line in `contents` that matches the pattern.
+pub fn search<'a>(pattern: &Regex, contents: &'a str) -> Vec<Match<'a>> {
+    contents
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let ranges: Vec<_> = pattern.find_iter(line).map(|m| m.range()).collect();
+
+            // filter_map drops any Nones, so lines without
+            // matches won't be in the result.
+            if ranges.is_empty() {
+                None
+            } else {
+                Some(Match {
+                    line_number: i + 1,
+                    line,
+                    ranges,
+                })
+            }
+        })
+        .collect()
+}
+
+/// Wraps each match in the line with color codes.
+pub fn highlight(line: &str, ranges: &[Range<usize>]) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut last = 0;
+
+    for range in ranges {
+        out.push_str(&line[last..range.start]);
+        out.push_str(HIGHLIGHT_START);
+        out.push_str(&line[range.clone()]);
+        out.push_str(HIGHLIGHT_END);
+        last = range.end;
+    }
+    out.push_str(&line[last..]);
+
+    out
+}
+
+/// Returns every file under `path`.
+/// If `path` is a file, it's returned by itself, and if it's
+/// a directory, every file inside it (and inside any directories
+/// inside it) is returned.
+///
+/// Folders that can't be read are reported on stderr and skipped,
+/// so one of them doesn't stop us from finding everything else.
+/// `had_error` is set to true if that happens.
+pub fn collect_files(path: &Path, had_error: &mut bool) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+
+    if path.is_dir() {
+        add_dir(path, &mut files, had_error);
+    } else {
+        files.push(path.to_path_buf());
+    }
+
+    files
+}
+
+/// Adds every file inside `dir` to `files`.
+/// Anything that can't be read is reported on stderr and skipped.
+fn add_dir(dir: &Path, files: &mut Vec<PathBuf>, had_error: &mut bool) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) => {
+            eprintln!("{}: {err}", dir.display());
+            *had_error = true;
+            return;
+        }
+    };
+
+    // Each entry is a Result too, since reading the next one
+    // can fail partway through the folder.
+    let mut entries: Vec<_> = entries
+        .filter_map(|entry| match entry {
+            Ok(entry) => Some(entry),
+            Err(err) => {
+                eprintln!("{}: {err}", dir.display());
+                *had_error = true;
+                None
+            }
+        })
+        .collect();
+    // read_dir doesn't return files in any particular
+    // order, so sort them to keep our output consistent.
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        // Skip hidden files and folders (like .git).
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+
+        // Unlike path.is_dir(), file_type() doesn't follow symlinks.
+        // A link to a folder that contains it would have us going
+        // round in circles forever, so links are skipped.
+        let file_type = match entry.file_type() {
+            Ok(file_type) => file_type,
+            Err(err) => {
+                eprintln!("{}: {err}", entry.path().display());
+                *had_error = true;
+                continue;
+            }
+        };
+        if file_type.is_dir() {
+            // This is where the recursion happens:
+            // for a directory, we call ourselves again.
+            add_dir(&entry.path(), files, had_error);
+        } else if file_type.is_file() {
+            files.push(entry.path());
+        }
+    }
+}
+
+/// What happened during a search.
+#[derive(Debug, PartialEq)]
+pub struct Outcome {
+    /// Whether any line matched.
+    pub found: bool,
+    /// Whether any file or folder couldn't be read.
+    pub had_error: bool,
+}
+
+/// Searches every file and writes the matching lines to `out`.
+///
+/// Files and folders that can't be read (because they don't exist,
+/// or we don't have permission) are reported on stderr, and the
+/// search carries on with everything else.
+/// The returned Outcome says whether that happened, so that main
+/// can still exit with an error afterwards.
+pub fn run(config: &Config, out: &mut impl Write) -> Result<Outcome, Box<dyn Error>> {
+    let pattern = build_pattern(config)?;
+    let mut found = false;
+    let mut had_error = false;
+
+    for path in &config.paths {
+        for file in collect_files(path, &mut had_error) {
+            // Files that aren't text (like images) can't be read
+            // into a String, so we skip them instead of stopping.
+            let contents = match fs::read_to_string(&file) {
+                Ok(contents) => contents,
+                Err(err) if err.kind() == io::ErrorKind::InvalidData => continue,
+                Err(err) => {
+                    eprintln!("{}: {err}", file.display());
+                    had_error = true;
+                    continue;
+                }
+            };
+
+            for m in search(&pattern, &contents) {
+                found = true;
+
+                let line = if config.color {
+                    highlight(m.line, &m.ranges)
+                } else {
+                    m.line.to_string()
+                };
+
+                writeln!(out, "{}:{}:{line}", file.display(), m.line_number)?;
+            }
+        }
+    }
+
+    Ok(Outcome { found, had_error })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a Config from a list of string arguments.
+    fn config(args: &[&str]) -> Result<Config, String> {
+        Config::build(args.iter().map(|arg| arg.to_string()))
+    }
+
+    /// Builds a pattern for a query with the given flags.
+    fn pattern(query: &str, ignore_case: bool, regex: bool) -> Regex {
+        build_pattern(&Config {
+            query: query.into(),
+            paths: vec![],
+            ignore_case,
+            regex,
+            color: false,
+        })
+        .unwrap()
+    }
+
+    const POEM: &str = "\
+I'm nobody! Who are you?
+Are you nobody, too?
+Then there's a pair of us - don't tell!";
+
+    #[test]
+    fn parses_flags_and_paths() {
+        let config = config(&["-i", "nobody", "a.txt", "--regex", "b", "--no-color"]).unwrap();
+
+        assert_eq!(config.query, "nobody");
+        assert_eq!(config.paths, vec![PathBuf::from("a.txt"), PathBuf::from("b")]);
+        assert!(config.ignore_case);
+        assert!(config.regex);
+        assert!(!config.color);
+    }
+
+    #[test]
+    fn defaults_to_the_current_directory() {
+        assert_eq!(config(&["query"]).unwrap().paths, vec![PathBuf::from(".")]);
+    }
+
+    #[test]
+    fn everything_after_a_double_dash_is_positional() {
+        let config = config(&["-i", "--", "-foo", "--regex"]).unwrap();
+
+        assert_eq!(config.query, "-foo");
+        assert_eq!(config.paths, vec![PathBuf::from("--regex")]);
+        assert!(config.ignore_case);
+        assert!(!config.regex);
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert_eq!(config(&[]), Err("missing the search query".into()));
+        assert_eq!(config(&["-x", "query"]), Err("unknown flag '-x'".into()));
+    }
+
+    #[test]
+    fn case_sensitive() {
+        let matches = search(&pattern("Are", false, false), POEM);
+        let lines: Vec<_> = matches.iter().map(|m| m.line_number).collect();
+
+        assert_eq!(lines, vec![2]);
+    }
+
+    #[test]
+    fn case_insensitive() {
+        let matches = search(&pattern("are", true, false), POEM);
+        let lines: Vec<_> = matches.iter().map(|m| m.line_number).collect();
+
+        assert_eq!(lines, vec![1, 2]);
+    }
+
+    #[test]
+    fn plain_text_is_escaped() {
+        // Without escaping, the `?` would make the `u` optional.
+        assert_eq!(search(&pattern("you?", false, false), POEM).len(), 1);
+        assert_eq!(search(&pattern("you?", false, true), POEM).len(), 2);
+    }
+
+    #[test]
+    fn regex_matching() {
+        let matches = search(&pattern(r"\bno\w+", false, true), POEM);
+
+        assert_eq!(matches[0].ranges, vec![4..10]);
+        assert_eq!(matches[1].ranges, vec![8..14]);
+    }
+
+    #[test]
+    fn finds_every_match_in_a_line() {
+        let matches = search(&pattern("o", false, false), "foo");
+
+        assert_eq!(matches[0].ranges, vec![1..2, 2..3]);
+    }
+
+    #[test]
+    fn skips_folders_that_cannot_be_read() {
+        let temp = tempfile::tempdir().unwrap();
+        let file = temp.path().join("file.txt");
+        fs::write(&file, "text").unwrap();
+
+        // read_dir fails on something that isn't a folder, the same
+        // way it does on one we don't have permission to read.
+        // The error is reported, and nothing is added.
+        let mut files = Vec::new();
+        let mut had_error = false;
+        add_dir(&file, &mut files, &mut had_error);
+        assert!(files.is_empty());
+        assert!(had_error);
+
+        let mut had_error = false;
+        assert_eq!(collect_files(temp.path(), &mut had_error), vec![file]);
+        assert!(!had_error);
+    }
+
+    #[test]
+    fn highlights_matches() {
+        assert_eq!(
+            highlight("a cat sat", &[2..5, 6..9]),
+            format!("a {HIGHLIGHT_START}cat{HIGHLIGHT_END} {HIGHLIGHT_START}sat{HIGHLIGHT_END}")
+        );
+    }
+}
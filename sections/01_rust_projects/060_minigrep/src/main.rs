@@ -0,0 +1,43 @@
+// This is a grep clone.
+// Try running `cargo run -- -i fn src` to find every
+// function in the project.
+// Most of the code is in lib.rs, and this file just
+// hands our arguments over to it.
+
+// Our library is called project (from Cargo.toml),
+// so that's what we import from.
+use project::Config;
+use std::io::IsTerminal;
+
+fn main() {
+    let mut config = match Config::build(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            eprintln!("Usage: minigrep [-i|--ignore-case] [-e|--regex] [--no-color] [--] <query> [paths...]");
+            std::process::exit(2);
+        }
+    };
+
+    // Color codes look like garbage if our output is being
+    // written to a file, so only use them when printing
+    // straight to a terminal.
+    let stdout = std::io::stdout();
+    if !stdout.is_terminal() {
+        config.color = false;
+    }
+
+    // Like the real grep, exit with 0 if something matched,
+    // 1 if nothing did, and 2 if there was an error.
+    // A file that couldn't be read is an error too, even though
+    // the rest of the search carried on.
+    match project::run(&config, &mut stdout.lock()) {
+        Ok(outcome) if outcome.had_error => std::process::exit(2),
+        Ok(outcome) if outcome.found => {}
+        Ok(_) => std::process::exit(1),
+        Err(err) => {
+            eprintln!("Error: {err}");
+            std::process::exit(2);
+        }
+    }
+}
@@ -0,0 +1,217 @@
+// These are integration tests.
+// Every file in the tests/ folder is compiled as its own
+// crate, so it can only use the public parts of our library,
+// just like any other program would.
+// This is a good way to check that everything works together.
+
+use project::Config;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+/// Creates a fresh temporary directory with a few files to search
+/// through. It's deleted when the TempDir is dropped, at the end
+/// of the test.
+fn setup() -> TempDir {
+    let temp = tempfile::tempdir().unwrap();
+    let dir = temp.path();
+
+    fs::create_dir_all(dir.join("nested/deeper")).unwrap();
+    fs::create_dir_all(dir.join(".hidden")).unwrap();
+
+    fs::write(dir.join("top.txt"), "Rust is fast\nand safe\n").unwrap();
+    fs::write(dir.join("nested/poem.txt"), "Rusty nails\nno match here\n").unwrap();
+    fs::write(dir.join("nested/deeper/notes.txt"), "learn rust\n").unwrap();
+    fs::write(dir.join(".hidden/secret.txt"), "Rust\n").unwrap();
+    // Invalid UTF-8, which should be skipped.
+    fs::write(dir.join("binary.bin"), [0xff, 0xfe, b'R']).unwrap();
+
+    temp
+}
+
+/// Runs the search and returns each line of output,
+/// with the temporary directory removed from the paths.
+fn run(dir: &Path, args: &[&str]) -> (bool, Vec<String>) {
+    // --no-color goes first, since anything after a `--` in args
+    // would be taken as a path.
+    let mut args: Vec<String> = std::iter::once("--no-color")
+        .chain(args.iter().copied())
+        .map(|arg| arg.to_string())
+        .collect();
+    args.push(dir.display().to_string());
+
+    let config = Config::build(args.into_iter()).unwrap();
+    let mut out = Vec::new();
+    let outcome = project::run(&config, &mut out).unwrap();
+    assert!(!outcome.had_error);
+
+    let prefix = format!("{}/", dir.display());
+    let lines = String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .map(|line| line.replace(&prefix, ""))
+        .collect();
+
+    (outcome.found, lines)
+}
+
+#[test]
+fn searches_directories_recursively() {
+    let temp = setup();
+    let dir = temp.path();
+    let (found, lines) = run(dir, &["Rust"]);
+
+    assert!(found);
+    assert_eq!(lines, vec!["nested/poem.txt:1:Rusty nails", "top.txt:1:Rust is fast"]);
+}
+
+#[test]
+fn ignore_case_finds_more() {
+    let temp = setup();
+    let dir = temp.path();
+    let (_, lines) = run(dir, &["-i", "rust"]);
+
+    assert_eq!(
+        lines,
+        vec![
+            "nested/deeper/notes.txt:1:learn rust",
+            "nested/poem.txt:1:Rusty nails",
+            "top.txt:1:Rust is fast",
+        ]
+    );
+}
+
+#[test]
+fn regex_mode() {
+    let temp = setup();
+    let dir = temp.path();
+    let (_, lines) = run(dir, &["--regex", r"^\w+ (is|nails)"]);
+
+    assert_eq!(lines, vec!["nested/poem.txt:1:Rusty nails", "top.txt:1:Rust is fast"]);
+}
+
+#[test]
+fn no_matches() {
+    let temp = setup();
+    let dir = temp.path();
+    let (found, lines) = run(dir, &["Python"]);
+
+    assert!(!found);
+    assert!(lines.is_empty());
+}
+
+#[test]
+fn binary_exit_codes() {
+    let temp = setup();
+    let dir = temp.path();
+    // Cargo sets this variable to the path of our compiled
+    // binary when running integration tests.
+    let binary = env!("CARGO_BIN_EXE_project");
+
+    let status = Command::new(binary).arg("safe").arg(dir).status().unwrap();
+    assert_eq!(status.code(), Some(0));
+
+    let status = Command::new(binary).arg("Python").arg(dir).status().unwrap();
+    assert_eq!(status.code(), Some(1));
+
+    let output = Command::new(binary).arg("--bogus").output().unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown flag '--bogus'"));
+}
+
+#[test]
+fn output_is_not_colored_when_piped() {
+    let temp = setup();
+    let dir = temp.path();
+    let output = Command::new(env!("CARGO_BIN_EXE_project"))
+        .arg("safe")
+        .arg(dir)
+        .output()
+        .unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.ends_with("top.txt:2:and safe\n"));
+    assert!(!stdout.contains('\x1b'));
+}
+
+#[test]
+fn reports_unreadable_files_and_keeps_going() {
+    let temp = setup();
+    let dir = temp.path();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_project"))
+        .arg("safe")
+        .arg(dir.join("missing.txt"))
+        .arg(dir)
+        .output()
+        .unwrap();
+
+    // The search still finishes, but the exit code says that
+    // something went wrong.
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("missing.txt"));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("top.txt:2:and safe"));
+}
+
+// Symlinks are created differently on Windows, and need
+// special permissions there.
+#[cfg(unix)]
+#[test]
+fn skips_symlinks() {
+    let temp = setup();
+    let dir = temp.path();
+    // A link to the folder that contains it. Following it would
+    // never end.
+    std::os::unix::fs::symlink(dir, dir.join("nested/loop")).unwrap();
+    std::os::unix::fs::symlink(dir.join("top.txt"), dir.join("link.txt")).unwrap();
+
+    let (_, lines) = run(dir, &["Rust"]);
+
+    assert_eq!(lines, vec!["nested/poem.txt:1:Rusty nails", "top.txt:1:Rust is fast"]);
+}
+
+// Permissions work differently on Windows.
+#[cfg(unix)]
+#[test]
+fn reports_unreadable_folders_and_keeps_going() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp = setup();
+    let dir = temp.path();
+    let locked = dir.join("nested");
+    fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+
+    // Some users (like root) can read a folder anyway, in which
+    // case there's nothing to test.
+    if fs::read_dir(&locked).is_ok() {
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+        return;
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_project"))
+        .arg("Rust")
+        .arg(dir)
+        .output()
+        .unwrap();
+
+    // Put the permissions back, so that the TempDir can be deleted.
+    fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("nested"));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("top.txt:1:Rust is fast"));
+}
+
+#[test]
+fn searches_for_a_query_that_starts_with_a_dash() {
+    let temp = setup();
+    let dir = temp.path();
+    fs::write(dir.join("flags.txt"), "pass -foo to it
+").unwrap();
+
+    let (found, lines) = run(dir, &["--", "-foo"]);
+
+    assert!(found);
+    assert_eq!(lines, vec!["flags.txt:1:pass -foo to it"]);
+}
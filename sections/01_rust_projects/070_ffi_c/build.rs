@@ -0,0 +1,18 @@
+// This is a build script.
+// If a package has a build.rs, Cargo compiles and runs it before
+// compiling the rest of the package.
+// Here, we use it to compile the C library in csrc/ and tell
+// Cargo to link it into our program.
+
+fn main() {
+    // cc compiles histogram.c into a static library called
+    // libhistogram.a, and prints the instructions that tell Cargo
+    // to link it in.
+    cc::Build::new().file("csrc/histogram.c").warnings(true).compile("histogram");
+
+    // Only rerun this script when the C code changes.
+    // Without these lines, Cargo would rerun it whenever any file
+    // in the package changes.
+    println!("cargo:rerun-if-changed=csrc/histogram.c");
+    println!("cargo:rerun-if-changed=csrc/histogram.h");
+}
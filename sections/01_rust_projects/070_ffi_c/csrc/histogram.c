@@ -0,0 +1,214 @@
+#include "histogram.h"
+
+#include <math.h>
+#include <stdarg.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+struct Histogram {
+    double min;
+    double max;
+    size_t buckets;
+    size_t total;
+    /* An array of `buckets` counts. */
+    size_t *counts;
+};
+
+double stats_mean(const double *values, size_t len) {
+    double sum = 0.0;
+    size_t i;
+
+    if (len == 0) {
+        return 0.0;
+    }
+
+    for (i = 0; i < len; i++) {
+        sum += values[i];
+    }
+
+    return sum / (double)len;
+}
+
+Histogram *histogram_new(double min, double max, size_t buckets) {
+    Histogram *histogram;
+
+    if (!(min < max) || buckets == 0) {
+        return NULL;
+    }
+
+    /* The span has to fit in a double too. Otherwise, something like
+     * -1e308..1e308 would make histogram_add divide inf by inf, and
+     * turning the NaN that gives into a size_t is undefined. */
+    if (!isfinite(max - min)) {
+        return NULL;
+    }
+
+    histogram = malloc(sizeof(*histogram));
+    if (histogram == NULL) {
+        return NULL;
+    }
+
+    /* calloc sets every count to 0. */
+    histogram->counts = calloc(buckets, sizeof(size_t));
+    if (histogram->counts == NULL) {
+        free(histogram);
+        return NULL;
+    }
+
+    histogram->min = min;
+    histogram->max = max;
+    histogram->buckets = buckets;
+    histogram->total = 0;
+
+    return histogram;
+}
+
+void histogram_free(Histogram *histogram) {
+    if (histogram == NULL) {
+        return;
+    }
+
+    free(histogram->counts);
+    free(histogram);
+}
+
+int histogram_add(Histogram *histogram, double value) {
+    size_t bucket;
+
+    /* Written this way around so that NaN (which isn't >= or <=
+     * anything) is rejected too. */
+    if (!(value >= histogram->min && value <= histogram->max)) {
+        return 0;
+    }
+
+    bucket = (size_t)((value - histogram->min) / (histogram->max - histogram->min) * (double)histogram->buckets);
+
+    /* The maximum value itself goes in the last bucket. */
+    if (bucket >= histogram->buckets) {
+        bucket = histogram->buckets - 1;
+    }
+
+    histogram->counts[bucket]++;
+    histogram->total++;
+
+    return 1;
+}
+
+size_t histogram_buckets(const Histogram *histogram) {
+    return histogram->buckets;
+}
+
+size_t histogram_count(const Histogram *histogram, size_t bucket) {
+    if (bucket >= histogram->buckets) {
+        return 0;
+    }
+
+    return histogram->counts[bucket];
+}
+
+size_t histogram_total(const Histogram *histogram) {
+    return histogram->total;
+}
+
+/* A string that grows as text is added to it. */
+typedef struct {
+    char *data;
+    size_t len;
+    size_t cap;
+    /* Set if an allocation failed. */
+    int failed;
+} Buffer;
+
+/* Adds formatted text to the end of a buffer, like printf. */
+static void buffer_printf(Buffer *buffer, const char *format, ...) {
+    va_list args;
+    int needed;
+
+    if (buffer->failed) {
+        return;
+    }
+
+    /* Find out how long the text will be. */
+    va_start(args, format);
+    needed = vsnprintf(NULL, 0, format, args);
+    va_end(args);
+
+    if (needed < 0) {
+        buffer->failed = 1;
+        return;
+    }
+
+    /* Make room for it, plus the null terminator. */
+    if (buffer->len + (size_t)needed + 1 > buffer->cap) {
+        size_t new_cap = (buffer->len + (size_t)needed + 1) * 2;
+        char *new_data = realloc(buffer->data, new_cap);
+
+        if (new_data == NULL) {
+            buffer->failed = 1;
+            return;
+        }
+
+        buffer->data = new_data;
+        buffer->cap = new_cap;
+    }
+
+    va_start(args, format);
+    vsnprintf(buffer->data + buffer->len, buffer->cap - buffer->len, format, args);
+    va_end(args);
+
+    buffer->len += (size_t)needed;
+}
+
+char *histogram_render(const Histogram *histogram, size_t width) {
+    Buffer buffer = {NULL, 0, 0, 0};
+    double step = (histogram->max - histogram->min) / (double)histogram->buckets;
+    size_t largest = 0;
+    size_t i, j;
+
+    for (i = 0; i < histogram->buckets; i++) {
+        if (histogram->counts[i] > largest) {
+            largest = histogram->counts[i];
+        }
+    }
+
+    /* Start with an empty string, in case there are no lines. */
+    buffer_printf(&buffer, "%s", "");
+
+    for (i = 0; i < histogram->buckets; i++) {
+        size_t count = histogram->counts[i];
+        size_t bar = largest == 0 ? 0 : count * width / largest;
+        char *label;
+
+        buffer_printf(&buffer, "%8.2f - %8.2f | ", histogram->min + step * (double)i,
+                      histogram->min + step * (double)(i + 1));
+
+        for (j = 0; j < width; j++) {
+            buffer_printf(&buffer, "%c", j < bar ? '#' : ' ');
+        }
+
+        /* Ask the Rust side for the label. We own the string it
+         * gives us, but it was allocated by Rust, so it has to be
+         * given back to Rust to be freed, not passed to free(). */
+        label = rust_format_count(count);
+        if (label != NULL) {
+            buffer_printf(&buffer, " %s\n", label);
+        } else {
+            /* Passing NULL to %s is undefined, so fall back to just
+             * the number. */
+            buffer_printf(&buffer, " %zu\n", count);
+        }
+        rust_string_free(label);
+    }
+
+    if (buffer.failed) {
+        free(buffer.data);
+        return NULL;
+    }
+
+    return buffer.data;
+}
+
+void histogram_string_free(char *string) {
+    free(string);
+}
@@ -0,0 +1,68 @@
+/*
+ * A small C library for counting numbers into buckets (a histogram).
+ *
+ * Ownership rules:
+ * - A Histogram created with histogram_new must be freed with
+ *   histogram_free, exactly once.
+ * - A string returned by histogram_render must be freed with
+ *   histogram_string_free.
+ * - Every other pointer argument is only borrowed for the length of
+ *   the call.
+ */
+
+#ifndef HISTOGRAM_H
+#define HISTOGRAM_H
+
+#include <stddef.h>
+
+/* The fields are hidden in histogram.c, so users can only
+ * work with a pointer to one. */
+typedef struct Histogram Histogram;
+
+/* Returns the average of `len` values, or 0 if len is 0. */
+double stats_mean(const double *values, size_t len);
+
+/* Creates a histogram with `buckets` equal-width buckets covering
+ * min..=max. Returns NULL if min >= max, max - min isn't finite,
+ * buckets is 0, or memory couldn't be allocated. */
+Histogram *histogram_new(double min, double max, size_t buckets);
+
+/* Frees a histogram. Does nothing if `histogram` is NULL. */
+void histogram_free(Histogram *histogram);
+
+/* Adds a value. Returns 1 if it was counted, or 0 if it was
+ * outside the histogram's range (or NaN). */
+int histogram_add(Histogram *histogram, double value);
+
+/* Returns the number of buckets. */
+size_t histogram_buckets(const Histogram *histogram);
+
+/* Returns how many values are in a bucket, or 0 if the bucket
+ * doesn't exist. */
+size_t histogram_count(const Histogram *histogram, size_t bucket);
+
+/* Returns how many values have been added. */
+size_t histogram_total(const Histogram *histogram);
+
+/* Draws the histogram as text, one line per bucket, with bars up to
+ * `width` characters long. Returns NULL if memory couldn't be
+ * allocated. The string must be freed with histogram_string_free. */
+char *histogram_render(const Histogram *histogram, size_t width);
+
+/* Frees a string returned by histogram_render. */
+void histogram_string_free(char *string);
+
+/*
+ * These functions aren't written in C. The program using this
+ * library has to provide them (our Rust program does).
+ */
+
+/* Returns a label for a count, like "3 values", or NULL if it
+ * couldn't make one. The string is owned by the caller, and must be
+ * given back with rust_string_free. */
+char *rust_format_count(size_t count);
+
+/* Frees a string returned by rust_format_count. */
+void rust_string_free(char *string);
+
+#endif
@@ -0,0 +1,79 @@
+// These functions are written in Rust, but called from C.
+// histogram.c declares them (in histogram.h) without defining
+// them, and the linker connects those calls to these functions
+// when it builds our program.
+
+use std::ffi::{c_char, CString};
+use std::ptr;
+
+// #[no_mangle] keeps the function's name exactly as written.
+// Normally, Rust changes names behind the scenes (so that two
+// functions called `new` in different modules don't clash), and
+// the C code wouldn't be able to find them.
+// `extern "C"` makes the function use C's calling convention.
+
+/// Returns a label for a count, like "3 values", or NULL if it
+/// couldn't be turned into a C string. histogram.c checks for that.
+///
+/// The string is allocated by Rust, so ownership passes to the
+/// caller, and it must be given back to `rust_string_free`.
+/// Passing it to C's free() would be a bug: Rust and C don't
+/// have to use the same allocator.
+#[no_mangle]
+pub extern "C" fn rust_format_count(count: usize) -> *mut c_char {
+    let label = if count == 1 { "1 value".to_string() } else { format!("{count} values") };
+
+    // A CString is a null-terminated string, like C uses.
+    // It can only fail if the text contains a null byte.
+    match CString::new(label) {
+        // into_raw gives up ownership: Rust won't free the string
+        // when this function returns.
+        Ok(label) => label.into_raw(),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Frees a string returned by `rust_format_count`.
+///
+/// # Safety
+///
+/// `string` must be NULL, or a pointer returned by
+/// `rust_format_count` that hasn't been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn rust_string_free(string: *mut c_char) {
+    if string.is_null() {
+        return;
+    }
+
+    // from_raw takes back ownership of a string from into_raw.
+    // When the CString is dropped at the end of this function,
+    // Rust frees it.
+    // SAFETY: the caller promised that string came from into_raw
+    // and hasn't been freed.
+    drop(unsafe { CString::from_raw(string) });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    #[test]
+    fn labels_round_trip() {
+        for (count, expected) in [(0, "0 values"), (1, "1 value"), (42, "42 values")] {
+            let label = rust_format_count(count);
+
+            // SAFETY: label came from rust_format_count, so it's a
+            // valid null-terminated string until we free it.
+            let text = unsafe { CStr::from_ptr(label) }.to_str().unwrap().to_string();
+            unsafe { rust_string_free(label) };
+
+            assert_eq!(text, expected);
+        }
+    }
+
+    #[test]
+    fn freeing_null_does_nothing() {
+        unsafe { rust_string_free(ptr::null_mut()) };
+    }
+}
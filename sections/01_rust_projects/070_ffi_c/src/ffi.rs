@@ -0,0 +1,43 @@
+// These are the raw declarations of the C functions in
+// csrc/histogram.h, translated into Rust.
+// Every one of them is unsafe to call: Rust can't check what C
+// code does, so it's up to us to follow the rules in the header.
+// The rest of the program uses the safe wrappers in histogram.rs
+// instead.
+
+use std::ffi::c_char;
+use std::marker::{PhantomData, PhantomPinned};
+
+/// The C library's Histogram.
+///
+/// Its fields are hidden in histogram.c, so we don't know what's
+/// inside or how big it is.
+/// This is the recommended way to represent a type like that: a
+/// struct that can't be created, moved out of, or sent to other
+/// threads, which we only ever use through a pointer.
+#[repr(C)]
+pub struct Histogram {
+    _data: [u8; 0],
+    _marker: PhantomData<(*mut u8, PhantomPinned)>,
+}
+
+// `extern "C"` means that these functions use the C calling
+// convention: the rules C uses for passing arguments and
+// return values.
+// The types need to match the C types exactly:
+// - size_t is usize.
+// - double is f64.
+// - int is i32 (std::ffi::c_int, to be exact).
+// - char * is *mut c_char, and const char * is *const c_char.
+extern "C" {
+    pub fn stats_mean(values: *const f64, len: usize) -> f64;
+
+    pub fn histogram_new(min: f64, max: f64, buckets: usize) -> *mut Histogram;
+    pub fn histogram_free(histogram: *mut Histogram);
+    pub fn histogram_add(histogram: *mut Histogram, value: f64) -> i32;
+    pub fn histogram_buckets(histogram: *const Histogram) -> usize;
+    pub fn histogram_count(histogram: *const Histogram, bucket: usize) -> usize;
+    pub fn histogram_total(histogram: *const Histogram) -> usize;
+    pub fn histogram_render(histogram: *const Histogram, width: usize) -> *mut c_char;
+    pub fn histogram_string_free(string: *mut c_char);
+}
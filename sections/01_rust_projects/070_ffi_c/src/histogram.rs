@@ -0,0 +1,211 @@
+// Safe wrappers around the C library.
+// Each function here checks or guarantees everything that the C
+// function needs, so that calling it can never go wrong, no matter
+// what the caller passes in.
+// The rest of the program never has to write `unsafe`.
+
+use crate::ffi;
+use std::ffi::CStr;
+use std::ptr::NonNull;
+
+/// Returns the average of some numbers, or None if there aren't any.
+pub fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+
+    // SAFETY: a slice's pointer and length always describe valid,
+    // initialized f64s, and the C function only reads them while
+    // it's running.
+    Some(unsafe { ffi::stats_mean(values.as_ptr(), values.len()) })
+}
+
+/// A histogram, stored in memory owned by the C library.
+///
+/// The pointer is always valid: it comes from histogram_new, and
+/// is only freed when this struct is dropped.
+/// Since we own it, nothing else can free it or change it behind
+/// our backs.
+pub struct Histogram {
+    raw: NonNull<ffi::Histogram>,
+}
+
+impl Histogram {
+    /// Creates a histogram with `buckets` equal-width buckets
+    /// covering `min..=max`.
+    ///
+    /// Returns None if `min` isn't less than `max`, either of them
+    /// (or the distance between them) isn't finite, or `buckets` is 0.
+    pub fn new(min: f64, max: f64, buckets: usize) -> Option<Histogram> {
+        // histogram_new checks these too, but an infinite range would
+        // lead to undefined behavior in C if it ever got through, so
+        // it's worth checking on this side as well.
+        if !min.is_finite() || !max.is_finite() || !(max - min).is_finite() {
+            return None;
+        }
+
+        // SAFETY: histogram_new accepts any arguments, and returns
+        // NULL if they're invalid.
+        let raw = unsafe { ffi::histogram_new(min, max, buckets) };
+
+        // NonNull::new turns NULL into None, so we never hold onto
+        // a null pointer.
+        NonNull::new(raw).map(|raw| Histogram { raw })
+    }
+
+    /// Adds a value.
+    /// Returns false if it was outside the histogram's range.
+    pub fn add(&mut self, value: f64) -> bool {
+        // SAFETY: raw is valid (see the struct), and we have &mut self,
+        // so nothing else is using the histogram.
+        unsafe { ffi::histogram_add(self.raw.as_ptr(), value) != 0 }
+    }
+
+    /// Returns the number of buckets.
+    pub fn buckets(&self) -> usize {
+        // SAFETY: raw is valid, and this only reads from it.
+        unsafe { ffi::histogram_buckets(self.raw.as_ptr()) }
+    }
+
+    /// Returns how many values are in a bucket, or None if
+    /// the bucket doesn't exist.
+    pub fn count(&self, bucket: usize) -> Option<usize> {
+        // The C function returns 0 for a bucket that doesn't exist,
+        // which looks the same as an empty bucket.
+        // Checking here lets us tell the two apart.
+        if bucket >= self.buckets() {
+            return None;
+        }
+
+        // SAFETY: raw is valid, and this only reads from it.
+        Some(unsafe { ffi::histogram_count(self.raw.as_ptr(), bucket) })
+    }
+
+    /// Returns how many values have been added.
+    pub fn total(&self) -> usize {
+        // SAFETY: raw is valid, and this only reads from it.
+        unsafe { ffi::histogram_total(self.raw.as_ptr()) }
+    }
+
+    /// Draws the histogram as text, with bars up to `width`
+    /// characters long.
+    pub fn render(&self, width: usize) -> String {
+        // SAFETY: raw is valid, and this only reads from it.
+        let raw = unsafe { ffi::histogram_render(self.raw.as_ptr(), width) };
+
+        // The C library only returns NULL if it ran out of memory.
+        if raw.is_null() {
+            panic!("the C library ran out of memory while rendering");
+        }
+
+        // SAFETY: histogram_render returns a null-terminated string,
+        // which stays valid until we free it below.
+        // to_string_lossy reads it, and to_string copies it into
+        // memory that Rust owns.
+        let text = unsafe { CStr::from_ptr(raw) }.to_string_lossy().to_string();
+
+        // The C library allocated the string, so the C library has
+        // to free it.
+        // SAFETY: raw came from histogram_render, we've finished
+        // copying it, and we never use it again.
+        unsafe { ffi::histogram_string_free(raw) };
+
+        text
+    }
+}
+
+impl Drop for Histogram {
+    fn drop(&mut self) {
+        // SAFETY: raw came from histogram_new, and since this
+        // struct is going away, it'll never be used again.
+        unsafe { ffi::histogram_free(self.raw.as_ptr()) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mean_of_values() {
+        assert_eq!(mean(&[1.0, 2.0, 3.0, 6.0]), Some(3.0));
+        assert_eq!(mean(&[]), None);
+    }
+
+    #[test]
+    fn invalid_histograms() {
+        assert!(Histogram::new(10.0, 0.0, 5).is_none());
+        assert!(Histogram::new(0.0, 0.0, 5).is_none());
+        assert!(Histogram::new(0.0, 10.0, 0).is_none());
+        assert!(Histogram::new(f64::NAN, 10.0, 5).is_none());
+    }
+
+    #[test]
+    fn rejects_ranges_that_are_not_finite() {
+        assert!(Histogram::new(f64::NEG_INFINITY, 0.0, 5).is_none());
+        assert!(Histogram::new(0.0, f64::INFINITY, 5).is_none());
+        // Both ends are finite, but the distance between them isn't.
+        assert!(Histogram::new(-1e308, 1e308, 5).is_none());
+        assert!(Histogram::new(f64::MIN, f64::MAX, 5).is_none());
+
+        // The biggest range that does fit still works.
+        let mut histogram = Histogram::new(-8e307, 8e307, 4).unwrap();
+        assert!(histogram.add(-8e307));
+        assert!(histogram.add(0.0));
+        assert!(histogram.add(8e307));
+        assert_eq!(histogram.count(0), Some(1));
+        assert_eq!(histogram.count(2), Some(1));
+        assert_eq!(histogram.count(3), Some(1));
+    }
+
+    #[test]
+    fn counts_values_into_buckets() {
+        let mut histogram = Histogram::new(0.0, 10.0, 5).unwrap();
+
+        for value in [0.0, 1.9, 2.0, 5.5, 9.9, 10.0] {
+            assert!(histogram.add(value));
+        }
+
+        assert_eq!(histogram.buckets(), 5);
+        assert_eq!(histogram.total(), 6);
+        assert_eq!(histogram.count(0), Some(2));
+        assert_eq!(histogram.count(1), Some(1));
+        assert_eq!(histogram.count(2), Some(1));
+        assert_eq!(histogram.count(3), Some(0));
+        // 10.0 is the maximum, so it goes in the last bucket.
+        assert_eq!(histogram.count(4), Some(2));
+        assert_eq!(histogram.count(5), None);
+    }
+
+    #[test]
+    fn rejects_values_outside_the_range() {
+        let mut histogram = Histogram::new(0.0, 10.0, 5).unwrap();
+
+        assert!(!histogram.add(-0.1));
+        assert!(!histogram.add(10.1));
+        assert!(!histogram.add(f64::NAN));
+        assert_eq!(histogram.total(), 0);
+    }
+
+    #[test]
+    fn renders_with_labels_from_rust() {
+        let mut histogram = Histogram::new(0.0, 4.0, 2).unwrap();
+        histogram.add(1.0);
+        histogram.add(3.0);
+        histogram.add(3.5);
+
+        let text = histogram.render(4);
+        let lines: Vec<&str> = text.lines().collect();
+
+        // The labels come from rust_format_count, which the C
+        // code calls while rendering.
+        assert_eq!(lines, ["    0.00 -     2.00 | ##   1 value", "    2.00 -     4.00 | #### 2 values"]);
+    }
+
+    #[test]
+    fn renders_an_empty_histogram() {
+        let histogram = Histogram::new(0.0, 1.0, 1).unwrap();
+
+        assert_eq!(histogram.render(3), "    0.00 -     1.00 |     0 values\n");
+    }
+}
@@ -0,0 +1,134 @@
+// This program draws a histogram of some numbers, using a small
+// library written in C (in csrc/).
+// Run it with a file of numbers (`cargo run -- numbers.txt`),
+// or without one to use some example exam scores.
+//
+// build.rs compiles the C code, ffi.rs declares its functions,
+// histogram.rs wraps them in a safe Rust interface, and
+// callbacks.rs has the Rust functions that the C code calls.
+//
+// Try running `cargo test` to run the tests in histogram.rs,
+// callbacks.rs, and at the bottom of this file.
+
+mod callbacks;
+mod ffi;
+mod histogram;
+
+use histogram::Histogram;
+use std::{env, fs, process};
+
+/// Scores to use if no file was given.
+const EXAMPLE_SCORES: [f64; 24] = [
+    72.0, 85.5, 91.0, 64.0, 78.5, 88.0, 95.5, 59.0, 70.0, 82.0, 76.5, 68.0, 99.0, 81.0, 73.5, 87.0, 90.5, 45.0, 79.0,
+    84.0, 66.5, 92.0, 77.0, 100.0,
+];
+
+/// Reads whitespace-separated numbers from a file.
+fn read_numbers(path: &str) -> Result<Vec<f64>, String> {
+    let text = fs::read_to_string(path).map_err(|err| format!("could not read {path}: {err}"))?;
+    parse_numbers(&text)
+}
+
+/// Parses whitespace-separated numbers.
+/// "inf" and "NaN" parse as f64s, but they can't go in a histogram,
+/// so they're rejected along with anything else that isn't a number.
+fn parse_numbers(text: &str) -> Result<Vec<f64>, String> {
+    text.split_whitespace()
+        .map(|word| match word.parse::<f64>() {
+            Ok(number) if number.is_finite() => Ok(number),
+            _ => Err(format!("{word:?} is not a number")),
+        })
+        .collect()
+}
+
+/// Returns the range the histogram should cover: from the smallest
+/// number to the largest.
+fn range(numbers: &[f64]) -> (f64, f64) {
+    let min = numbers.iter().copied().fold(f64::INFINITY, f64::min);
+    let mut max = numbers.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if min == max {
+        // The histogram needs a range that isn't empty. Adding 1.0
+        // wouldn't do for big numbers: 1e17 + 1.0 is still 1e17, since
+        // f64s that big can't hold the difference. So widen it by an
+        // amount that grows with the number.
+        let width = min.abs().max(1.0);
+        max = min + width;
+
+        // Near f64::MAX, that would go past the biggest f64 and give
+        // infinity, so widen it downwards instead.
+        if !max.is_finite() {
+            return (min - width, min);
+        }
+    }
+    (min, max)
+}
+
+fn main() {
+    let numbers = match env::args().nth(1) {
+        Some(path) => read_numbers(&path).unwrap_or_else(|err| {
+            eprintln!("Error: {err}");
+            process::exit(1);
+        }),
+        None => EXAMPLE_SCORES.to_vec(),
+    };
+
+    // mean is calculated by the C library.
+    let Some(mean) = histogram::mean(&numbers) else {
+        eprintln!("Error: there are no numbers to draw.");
+        process::exit(1);
+    };
+
+    // The histogram itself lives in memory allocated by C.
+    // Histogram frees it for us when it goes out of scope.
+    let (min, max) = range(&numbers);
+    let Some(mut histogram) = Histogram::new(min, max, 6) else {
+        eprintln!("Error: can't draw a histogram from {min} to {max}.");
+        process::exit(1);
+    };
+    for &number in &numbers {
+        histogram.add(number);
+    }
+
+    println!("{} numbers, with an average of {mean:.2}:\n", histogram.total());
+
+    // While rendering, the C code calls back into Rust
+    // (rust_format_count) for each bucket's label.
+    print!("{}", histogram.render(30));
+
+    // max_by_key returns the last of any ties.
+    let busiest = (0..histogram.buckets()).max_by_key(|&bucket| histogram.count(bucket)).unwrap();
+    println!("\nThe busiest bucket is number {}.", busiest + 1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_numbers_that_are_not_finite() {
+        assert_eq!(parse_numbers("1 2.5\n-3"), Ok(vec![1.0, 2.5, -3.0]));
+        assert!(parse_numbers("1 inf").is_err());
+        assert!(parse_numbers("NaN").is_err());
+        assert!(parse_numbers("-infinity").is_err());
+        assert!(parse_numbers("1e999").is_err());
+    }
+
+    #[test]
+    fn widens_empty_ranges() {
+        assert_eq!(range(&[3.0, 1.0, 2.0]), (1.0, 3.0));
+        assert_eq!(range(&[5.0, 5.0]), (5.0, 10.0));
+        assert_eq!(range(&[0.0]), (0.0, 1.0));
+
+        for number in [1e17, -1e17, 1e300, f64::MAX, f64::MIN] {
+            let (min, max) = range(&[number, number]);
+            assert!(Histogram::new(min, max, 6).is_some(), "{number}");
+        }
+    }
+
+    #[test]
+    fn rejects_extreme_ranges() {
+        let numbers = parse_numbers("-1e308 0 1e308").unwrap();
+        let (min, max) = range(&numbers);
+        assert!(Histogram::new(min, max, 6).is_none());
+    }
+}
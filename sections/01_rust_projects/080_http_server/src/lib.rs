@@ -0,0 +1,21 @@
+// A small HTTP/1.1 server, built on nothing but std::net.
+//
+// The pieces, in the order that a request goes through them:
+// * server.rs accepts connections, and hands them to the thread pool.
+// * pool.rs runs each connection on one of a fixed number of threads.
+// * request.rs reads the request from the connection.
+// * router.rs picks a handler based on the method and path.
+// * static_files.rs serves files from a folder.
+// * response.rs writes the response back.
+
+pub mod pool;
+pub mod request;
+pub mod response;
+pub mod router;
+pub mod server;
+pub mod static_files;
+
+pub use request::Request;
+pub use response::Response;
+pub use router::{Params, Router};
+pub use server::Server;
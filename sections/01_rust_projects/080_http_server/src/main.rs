@@ -0,0 +1,42 @@
+// Start the server with `cargo run`, then open http://127.0.0.1:7878
+// in your browser, or try it with curl:
+//     curl -v http://127.0.0.1:7878/hello/ferris
+//     curl -d "some text" http://127.0.0.1:7878/echo
+
+use project::{static_files, Request, Response, Router, Server};
+use std::path::PathBuf;
+
+const ADDRESS: &str = "127.0.0.1:7878";
+
+fn main() {
+    // CARGO_MANIFEST_DIR is the folder with Cargo.toml in it, so the
+    // files are found no matter where the server is started from.
+    let public = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("public");
+
+    let router = Router::new()
+        .get("/hello/:name", |_, params| Response::text(format!("Hello, {}!\n", params["name"])))
+        .post("/echo", echo)
+        .fallback(move |request, _| static_files::serve(&public, request));
+
+    let server = match Server::bind(ADDRESS, router, 4) {
+        Ok(server) => server,
+        Err(err) => {
+            eprintln!("Could not listen on {ADDRESS}: {err}");
+            std::process::exit(1);
+        }
+    };
+
+    println!("Listening on http://{ADDRESS}");
+    server.run();
+}
+
+/// Sends the request body back, in capitals if the query is `loud`.
+fn echo(request: &Request, _: &project::Params) -> Response {
+    let body = String::from_utf8_lossy(&request.body);
+
+    if request.query.as_deref() == Some("loud") {
+        Response::text(body.to_uppercase())
+    } else {
+        Response::text(body)
+    }
+}
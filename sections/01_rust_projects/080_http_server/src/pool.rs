@@ -0,0 +1,90 @@
+// A thread pool: a fixed number of threads that take jobs from a
+// channel.
+//
+// Starting a new thread for every connection would work, but anyone
+// could open thousands of connections and make us start thousands of
+// threads. A pool puts a limit on that.
+
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+pub struct ThreadPool {
+    /// An Option, so that Drop can close the channel by dropping it.
+    sender: Option<Sender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl ThreadPool {
+    /// Starts a pool with `size` threads.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0, "a thread pool needs at least one thread");
+
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        let workers = (0..size)
+            .map(|id| {
+                let receiver = Arc::clone(&receiver);
+                thread::Builder::new()
+                    .name(format!("worker-{id}"))
+                    .spawn(move || work(&receiver))
+                    .expect("failed to start a worker thread")
+            })
+            .collect();
+
+        ThreadPool {
+            sender: Some(sender),
+            workers,
+        }
+    }
+
+    /// Runs a job on one of the pool's threads, as soon as one is free.
+    pub fn execute<F>(&self, job: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        if let Some(sender) = &self.sender {
+            // This only fails if every worker has stopped, which can't
+            // happen while the pool is alive (jobs that panic are
+            // caught in `work`).
+            let _ = sender.send(Box::new(job));
+        }
+    }
+}
+
+fn work(receiver: &Mutex<Receiver<Job>>) {
+    loop {
+        // The lock is released at the end of this statement, before
+        // the job runs, so other workers can take jobs in the meantime.
+        let job = receiver.lock().unwrap().recv();
+
+        let Ok(job) = job else {
+            // The pool was dropped.
+            break;
+        };
+
+        // A bug in one handler shouldn't take a worker down with it.
+        // catch_unwind stops a panic here, so the worker can carry on
+        // with the next job. The panic message is still printed.
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(job));
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        // Closing the channel tells the workers to stop once the jobs
+        // that are left are done.
+        drop(self.sender.take());
+
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
@@ -0,0 +1,278 @@
+// Reading an HTTP request from a connection.
+//
+// A request looks like this (every line ends with \r\n):
+//
+//     POST /echo?loud HTTP/1.1
+//     Host: localhost:7878
+//     Content-Length: 5
+//
+//     hello
+//
+// The first line is the request line: the method, the target (path
+// and query), and the version. Then come the headers, one per line,
+// then an empty line, and then the body, if there is one.
+
+use std::fmt;
+use std::io::{self, BufRead, Read};
+
+/// The longest that the request line or a header can be.
+const MAX_LINE: usize = 8 * 1024;
+/// The most headers that a request can have.
+const MAX_HEADERS: usize = 100;
+/// The biggest body that a request can have.
+const MAX_BODY: usize = 1024 * 1024;
+
+/// An HTTP request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    /// The path, like `/hello/world`. It doesn't include the query.
+    pub path: String,
+    /// Everything after the `?`, if there was one.
+    pub query: Option<String>,
+    /// The HTTP version, like `HTTP/1.1`.
+    pub version: String,
+    /// The headers. Their names are lowercase, since header names
+    /// aren't case-sensitive.
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+/// What can go wrong while reading a request.
+#[derive(Debug)]
+pub enum RequestError {
+    /// The connection failed, or timed out.
+    Io(io::Error),
+    /// The request isn't valid HTTP.
+    Malformed(&'static str),
+    /// A line, or the headers, or the body, is too big.
+    TooLarge,
+    /// The request uses a version of HTTP that we don't support.
+    UnsupportedVersion,
+}
+
+impl fmt::Display for RequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestError::Io(err) => write!(f, "connection error: {err}"),
+            RequestError::Malformed(reason) => write!(f, "malformed request: {reason}"),
+            RequestError::TooLarge => write!(f, "request too large"),
+            RequestError::UnsupportedVersion => write!(f, "unsupported HTTP version"),
+        }
+    }
+}
+
+impl From<io::Error> for RequestError {
+    fn from(err: io::Error) -> Self {
+        RequestError::Io(err)
+    }
+}
+
+impl Request {
+    /// Reads a request from a connection.
+    ///
+    /// Returns Ok(None) if the connection was closed before a new
+    /// request started, which is how a keep-alive connection normally
+    /// ends.
+    pub fn read(reader: &mut impl BufRead) -> Result<Option<Request>, RequestError> {
+        let Some(request_line) = read_line(reader)? else {
+            return Ok(None);
+        };
+
+        // The request line has exactly three parts, separated by spaces.
+        let mut parts = request_line.split(' ');
+        let (Some(method), Some(target), Some(version), None) = (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(RequestError::Malformed("the request line should be `METHOD /path HTTP/1.1`"));
+        };
+
+        if method.is_empty() || !method.bytes().all(|byte| byte.is_ascii_uppercase()) {
+            return Err(RequestError::Malformed("invalid method"));
+        }
+        if !target.starts_with('/') {
+            return Err(RequestError::Malformed("the path should start with /"));
+        }
+        if version != "HTTP/1.1" && version != "HTTP/1.0" {
+            return Err(RequestError::UnsupportedVersion);
+        }
+
+        let (path, query) = match target.split_once('?') {
+            Some((path, query)) => (path, Some(query.to_string())),
+            None => (target, None),
+        };
+
+        let mut request = Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            query,
+            version: version.to_string(),
+            headers: Vec::new(),
+            body: Vec::new(),
+        };
+
+        // Headers continue until an empty line.
+        loop {
+            let Some(line) = read_line(reader)? else {
+                return Err(RequestError::Malformed("the connection closed in the middle of the headers"));
+            };
+            if line.is_empty() {
+                break;
+            }
+
+            if request.headers.len() == MAX_HEADERS {
+                return Err(RequestError::TooLarge);
+            }
+
+            let Some((name, value)) = line.split_once(':') else {
+                return Err(RequestError::Malformed("a header is missing its `:`"));
+            };
+            // No spaces are allowed between the name and the colon.
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                return Err(RequestError::Malformed("invalid header name"));
+            }
+
+            request.headers.push((name.to_ascii_lowercase(), value.trim().to_string()));
+        }
+
+        // We don't support chunked request bodies (only responses use
+        // them here), so a body needs a Content-Length.
+        if request.header("transfer-encoding").is_some() {
+            return Err(RequestError::Malformed("chunked request bodies aren't supported"));
+        }
+
+        if let Some(length) = request.header("content-length") {
+            let length: usize = length
+                .parse()
+                .map_err(|_| RequestError::Malformed("invalid Content-Length"))?;
+            if length > MAX_BODY {
+                return Err(RequestError::TooLarge);
+            }
+
+            request.body = vec![0; length];
+            reader.read_exact(&mut request.body)?;
+        }
+
+        Ok(Some(request))
+    }
+
+    /// Returns the value of a header. `name` should be lowercase.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns true if the client wants to keep the connection open
+    /// for another request after this one.
+    pub fn keep_alive(&self) -> bool {
+        let connection = self.header("connection").map(str::to_ascii_lowercase);
+
+        // HTTP/1.1 keeps connections open unless it's told not to.
+        // HTTP/1.0 closes them unless it's told not to.
+        match self.version.as_str() {
+            "HTTP/1.1" => connection.as_deref() != Some("close"),
+            _ => connection.as_deref() == Some("keep-alive"),
+        }
+    }
+}
+
+/// Reads one line, without the \r\n at the end.
+/// Returns Ok(None) if the connection is closed before anything is read.
+fn read_line(reader: &mut impl BufRead) -> Result<Option<String>, RequestError> {
+    let mut line = Vec::new();
+
+    // take() stops a client from sending a never-ending line and using
+    // up all of our memory.
+    let read = reader.take(MAX_LINE as u64 + 1).read_until(b'\n', &mut line)?;
+    if read == 0 {
+        return Ok(None);
+    }
+    if line.len() > MAX_LINE {
+        return Err(RequestError::TooLarge);
+    }
+
+    // Lines should end in \r\n, but being forgiving about a plain \n is
+    // common (and harmless).
+    let Some(line) = line.strip_suffix(b"\n") else {
+        return Err(RequestError::Malformed("the connection closed in the middle of a line"));
+    };
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+
+    String::from_utf8(line.to_vec())
+        .map(Some)
+        .map_err(|_| RequestError::Malformed("the request isn't valid UTF-8"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<Option<Request>, RequestError> {
+        Request::read(&mut text.as_bytes())
+    }
+
+    #[test]
+    fn parses_a_request() {
+        let request = parse("POST /echo?loud HTTP/1.1\r\nHost: localhost\r\nContent-Length: 5\r\n\r\nhello")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/echo");
+        assert_eq!(request.query.as_deref(), Some("loud"));
+        assert_eq!(request.header("host"), Some("localhost"));
+        assert_eq!(request.body, b"hello");
+        assert!(request.keep_alive());
+    }
+
+    #[test]
+    fn reads_requests_one_after_another() {
+        let mut input = "GET /a HTTP/1.1\r\n\r\nGET /b HTTP/1.1\r\n\r\n".as_bytes();
+
+        assert_eq!(Request::read(&mut input).unwrap().unwrap().path, "/a");
+        assert_eq!(Request::read(&mut input).unwrap().unwrap().path, "/b");
+        assert!(Request::read(&mut input).unwrap().is_none());
+    }
+
+    #[test]
+    fn keep_alive() {
+        let request = |version: &str, connection: &str| {
+            parse(&format!("GET / {version}\r\nConnection: {connection}\r\n\r\n")).unwrap().unwrap()
+        };
+
+        assert!(!request("HTTP/1.1", "close").keep_alive());
+        assert!(!request("HTTP/1.0", "whatever").keep_alive());
+        assert!(request("HTTP/1.0", "Keep-Alive").keep_alive());
+    }
+
+    #[test]
+    fn rejects_bad_requests() {
+        assert!(matches!(parse("GET /\r\n\r\n"), Err(RequestError::Malformed(_))));
+        assert!(matches!(parse("get / HTTP/1.1\r\n\r\n"), Err(RequestError::Malformed(_))));
+        assert!(matches!(parse("GET nope HTTP/1.1\r\n\r\n"), Err(RequestError::Malformed(_))));
+        assert!(matches!(parse("GET / HTTP/2\r\n\r\n"), Err(RequestError::UnsupportedVersion)));
+        assert!(matches!(parse("GET / HTTP/1.1\r\nNo colon\r\n\r\n"), Err(RequestError::Malformed(_))));
+        assert!(matches!(parse("GET / HTTP/1.1\r\nHost: x\r\n"), Err(RequestError::Malformed(_))));
+        assert!(matches!(
+            parse("POST / HTTP/1.1\r\nContent-Length: lots\r\n\r\n"),
+            Err(RequestError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_huge_requests() {
+        let long_path = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_LINE));
+        assert!(matches!(parse(&long_path), Err(RequestError::TooLarge)));
+
+        let huge_body = format!("POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n", MAX_BODY + 1);
+        assert!(matches!(parse(&huge_body), Err(RequestError::TooLarge)));
+    }
+
+    #[test]
+    fn short_body_is_an_error() {
+        let error = parse("POST / HTTP/1.1\r\nContent-Length: 10\r\n\r\nhi").unwrap_err();
+
+        assert!(matches!(error, RequestError::Io(err) if err.kind() == io::ErrorKind::UnexpectedEof));
+    }
+}
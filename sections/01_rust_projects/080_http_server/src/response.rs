@@ -0,0 +1,160 @@
+// Writing an HTTP response.
+//
+// A response looks a lot like a request, but the first line is the
+// status line: the version, a status code, and a short description.
+//
+//     HTTP/1.1 200 OK
+//     Content-Type: text/plain; charset=utf-8
+//     Content-Length: 12
+//
+//     Hello there!
+
+use std::io::{self, Write};
+
+/// An HTTP response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// Creates a response with a status code, and no headers or body.
+    pub fn new(status: u16) -> Self {
+        Response {
+            status,
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    /// A 200 OK response with a plain text body.
+    pub fn text(body: impl Into<String>) -> Self {
+        Response::new(200)
+            .with_header("Content-Type", "text/plain; charset=utf-8")
+            .with_body(body.into())
+    }
+
+    /// A 200 OK response with an HTML body.
+    pub fn html(body: impl Into<String>) -> Self {
+        Response::new(200)
+            .with_header("Content-Type", "text/html; charset=utf-8")
+            .with_body(body.into())
+    }
+
+    /// An error response, with the status's description as the body.
+    pub fn error(status: u16) -> Self {
+        let mut response = Response::text(format!("{status} {}\n", reason(status)));
+        response.status = status;
+        response
+    }
+
+    /// Adds a header.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sets the body.
+    pub fn with_body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Returns the value of a header, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Writes the response to a connection.
+    ///
+    /// `keep_alive` says whether the connection will stay open for
+    /// another request, which the client needs to know.
+    /// `include_body` is false for HEAD requests, which get the headers
+    /// (including Content-Length) but no body.
+    pub fn write_to(&self, writer: &mut impl Write, keep_alive: bool, include_body: bool) -> io::Result<()> {
+        write!(writer, "HTTP/1.1 {} {}\r\n", self.status, reason(self.status))?;
+
+        for (name, value) in &self.headers {
+            write!(writer, "{name}: {value}\r\n")?;
+        }
+
+        // Content-Length tells the client where the body ends. Without
+        // it, the only way to know would be for us to close the
+        // connection, and keep-alive wouldn't work.
+        write!(writer, "Content-Length: {}\r\n", self.body.len())?;
+        write!(writer, "Connection: {}\r\n", if keep_alive { "keep-alive" } else { "close" })?;
+        write!(writer, "\r\n")?;
+
+        if include_body {
+            writer.write_all(&self.body)?;
+        }
+
+        writer.flush()
+    }
+}
+
+/// The standard description for a status code.
+pub fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        301 => "Moved Permanently",
+        304 => "Not Modified",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        413 => "Content Too Large",
+        500 => "Internal Server Error",
+        505 => "HTTP Version Not Supported",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_a_response() {
+        let mut output = Vec::new();
+        Response::text("hi").write_to(&mut output, true, true).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "HTTP/1.1 200 OK\r\n\
+            Content-Type: text/plain; charset=utf-8\r\n\
+            Content-Length: 2\r\n\
+            Connection: keep-alive\r\n\
+            \r\n\
+            hi"
+        );
+    }
+
+    #[test]
+    fn head_responses_have_no_body() {
+        let mut output = Vec::new();
+        Response::text("hi").write_to(&mut output, false, false).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.contains("Content-Length: 2\r\n"));
+        assert!(output.contains("Connection: close\r\n"));
+        assert!(output.ends_with("\r\n\r\n"));
+    }
+
+    #[test]
+    fn errors() {
+        let response = Response::error(404);
+
+        assert_eq!(response.status, 404);
+        assert_eq!(response.body, b"404 Not Found\n");
+        assert_eq!(response.header("content-type"), Some("text/plain; charset=utf-8"));
+    }
+}
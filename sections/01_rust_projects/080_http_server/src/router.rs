@@ -0,0 +1,200 @@
+// Deciding which handler runs for each request.
+//
+// Routes are patterns like `/hello/:name`. A segment starting with `:`
+// matches anything, and whatever it matched is passed to the handler
+// as a parameter.
+
+use std::collections::HashMap;
+
+use crate::request::Request;
+use crate::response::Response;
+
+/// The parameters that a route's pattern matched, like `name` in
+/// `/hello/:name`.
+pub type Params = HashMap<String, String>;
+
+/// A function that handles a request.
+///
+/// It has to be Send + Sync, since every thread in the pool calls
+/// handlers from the same Router.
+pub type Handler = Box<dyn Fn(&Request, &Params) -> Response + Send + Sync>;
+
+struct Route {
+    method: String,
+    /// The pattern, split into segments.
+    segments: Vec<String>,
+    handler: Handler,
+}
+
+/// A list of routes, and a fallback for requests that don't match any
+/// of them.
+pub struct Router {
+    routes: Vec<Route>,
+    fallback: Option<Handler>,
+}
+
+impl Router {
+    pub fn new() -> Self {
+        Router {
+            routes: Vec::new(),
+            fallback: None,
+        }
+    }
+
+    /// Adds a route. Routes are tried in the order that they're added.
+    pub fn route<F>(mut self, method: &str, pattern: &str, handler: F) -> Self
+    where
+        F: Fn(&Request, &Params) -> Response + Send + Sync + 'static,
+    {
+        self.routes.push(Route {
+            method: method.to_string(),
+            segments: segments(pattern).map(str::to_string).collect(),
+            handler: Box::new(handler),
+        });
+        self
+    }
+
+    /// Adds a GET route (which also handles HEAD requests).
+    pub fn get<F>(self, pattern: &str, handler: F) -> Self
+    where
+        F: Fn(&Request, &Params) -> Response + Send + Sync + 'static,
+    {
+        self.route("GET", pattern, handler)
+    }
+
+    /// Adds a POST route.
+    pub fn post<F>(self, pattern: &str, handler: F) -> Self
+    where
+        F: Fn(&Request, &Params) -> Response + Send + Sync + 'static,
+    {
+        self.route("POST", pattern, handler)
+    }
+
+    /// Sets what happens when no route matches. This is a good place
+    /// for serving static files. Without one, the response is a 404.
+    pub fn fallback<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&Request, &Params) -> Response + Send + Sync + 'static,
+    {
+        self.fallback = Some(Box::new(handler));
+        self
+    }
+
+    /// Finds the right handler for a request, and runs it.
+    pub fn handle(&self, request: &Request) -> Response {
+        // HEAD is the same as GET, without the body (which the server
+        // leaves out when it writes the response).
+        let method = if request.method == "HEAD" { "GET" } else { &request.method };
+
+        let mut path_matched = false;
+        for route in &self.routes {
+            let Some(params) = match_path(&route.segments, &request.path) else {
+                continue;
+            };
+
+            if route.method == method {
+                return (route.handler)(request, &params);
+            }
+            path_matched = true;
+        }
+
+        match &self.fallback {
+            Some(fallback) => fallback(request, &Params::new()),
+            // The path exists, but not with this method.
+            None if path_matched => Response::error(405),
+            None => Response::error(404),
+        }
+    }
+}
+
+impl Default for Router {
+    fn default() -> Self {
+        Router::new()
+    }
+}
+
+/// Splits a path into segments, ignoring empty ones, so that `/a//b/`
+/// is the same as `/a/b`.
+fn segments(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|segment| !segment.is_empty())
+}
+
+/// Checks whether a path matches a pattern, and returns the parameters
+/// if it does.
+fn match_path(pattern: &[String], path: &str) -> Option<Params> {
+    let mut params = Params::new();
+    let mut path = segments(path);
+
+    for expected in pattern {
+        let actual = path.next()?;
+
+        match expected.strip_prefix(':') {
+            Some(name) => {
+                params.insert(name.to_string(), actual.to_string());
+            }
+            None if expected == actual => {}
+            None => return None,
+        }
+    }
+
+    // The path must not have any segments left over.
+    match path.next() {
+        Some(_) => None,
+        None => Some(params),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: &str, path: &str) -> Request {
+        Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            query: None,
+            version: "HTTP/1.1".to_string(),
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    fn router() -> Router {
+        Router::new()
+            .get("/", |_, _| Response::text("home"))
+            .get("/hello/:name", |_, params| Response::text(format!("hello {}", params["name"])))
+            .post("/hello/:name", |_, _| Response::text("posted"))
+    }
+
+    #[test]
+    fn matches_paths() {
+        let pattern: Vec<String> = vec!["users".into(), ":id".into()];
+
+        assert_eq!(match_path(&pattern, "/users/7").unwrap()["id"], "7");
+        assert!(match_path(&pattern, "/users").is_none());
+        assert!(match_path(&pattern, "/users/7/posts").is_none());
+        assert!(match_path(&pattern, "/posts/7").is_none());
+        assert!(match_path(&[], "/").is_some());
+    }
+
+    #[test]
+    fn routes_requests() {
+        let router = router();
+
+        assert_eq!(router.handle(&request("GET", "/")).body, b"home");
+        assert_eq!(router.handle(&request("GET", "/hello/ferris")).body, b"hello ferris");
+        assert_eq!(router.handle(&request("POST", "/hello/ferris")).body, b"posted");
+        assert_eq!(router.handle(&request("HEAD", "/hello/ferris")).body, b"hello ferris");
+    }
+
+    #[test]
+    fn missing_routes() {
+        let router = router();
+
+        assert_eq!(router.handle(&request("GET", "/nope")).status, 404);
+        assert_eq!(router.handle(&request("DELETE", "/hello/ferris")).status, 405);
+
+        let router = router.fallback(|_, _| Response::text("fallback"));
+        assert_eq!(router.handle(&request("GET", "/nope")).body, b"fallback");
+    }
+}
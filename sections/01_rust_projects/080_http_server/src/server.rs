@@ -0,0 +1,118 @@
+// Accepting connections, and answering the requests on them.
+
+use std::io::{self, BufReader, BufWriter};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::pool::ThreadPool;
+use crate::request::{Request, RequestError};
+use crate::response::Response;
+use crate::router::Router;
+
+/// How long a keep-alive connection can sit idle before we close it.
+/// Without a limit, idle connections would tie up our threads forever.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The most requests that one connection can make, so that a single
+/// busy client can't keep a thread to itself.
+const MAX_REQUESTS_PER_CONNECTION: usize = 100;
+
+pub struct Server {
+    listener: TcpListener,
+    router: Arc<Router>,
+    threads: usize,
+}
+
+impl Server {
+    /// Starts listening on an address, like "127.0.0.1:7878".
+    /// Port 0 picks any free port, which is handy for tests.
+    pub fn bind(address: impl ToSocketAddrs, router: Router, threads: usize) -> io::Result<Self> {
+        Ok(Server {
+            listener: TcpListener::bind(address)?,
+            // Every connection needs the router, so it's shared with an
+            // Arc instead of being copied.
+            router: Arc::new(router),
+            threads,
+        })
+    }
+
+    /// The address that the server is listening on.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Accepts connections forever.
+    pub fn run(self) {
+        let pool = ThreadPool::new(self.threads);
+
+        // incoming() waits for the next connection, every time it's called.
+        for stream in self.listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                // Accepting can fail for reasons that only affect one
+                // connection (like the client giving up), so carry on.
+                Err(err) => {
+                    eprintln!("Failed to accept a connection: {err}");
+                    continue;
+                }
+            };
+
+            let router = Arc::clone(&self.router);
+            pool.execute(move || {
+                if let Err(err) = handle_connection(stream, &router) {
+                    eprintln!("Connection error: {err}");
+                }
+            });
+        }
+    }
+}
+
+/// Answers requests on a connection until it's closed.
+fn handle_connection(stream: TcpStream, router: &Router) -> io::Result<()> {
+    // If no data arrives for this long, reads fail with a WouldBlock or
+    // TimedOut error.
+    stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+
+    // try_clone gives us a second handle to the same connection, so
+    // that one can be wrapped in a BufReader and the other in a
+    // BufWriter.
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = BufWriter::new(stream);
+
+    for handled in 1..=MAX_REQUESTS_PER_CONNECTION {
+        let request = match Request::read(&mut reader) {
+            Ok(Some(request)) => request,
+            // The client closed the connection.
+            Ok(None) => return Ok(()),
+            // The client went quiet, so we close the connection.
+            Err(RequestError::Io(err))
+                if matches!(err.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) =>
+            {
+                return Ok(());
+            }
+            Err(RequestError::Io(err)) => return Err(err),
+            // After a bad request, we can't tell where the next one
+            // would start, so we answer and close the connection.
+            Err(err) => {
+                let status = match err {
+                    RequestError::TooLarge => 413,
+                    RequestError::UnsupportedVersion => 505,
+                    _ => 400,
+                };
+                return Response::error(status).write_to(&mut writer, false, true);
+            }
+        };
+
+        let keep_alive = request.keep_alive() && handled < MAX_REQUESTS_PER_CONNECTION;
+        let response = router.handle(&request);
+
+        response.write_to(&mut writer, keep_alive, request.method != "HEAD")?;
+
+        if !keep_alive {
+            break;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+// Serving files from a folder.
+
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use crate::request::Request;
+use crate::response::Response;
+
+/// Serves the files inside `root`. A request for `/css/site.css` gets
+/// `root/css/site.css`, and a request for a folder gets the
+/// `index.html` inside it.
+pub fn serve(root: &Path, request: &Request) -> Response {
+    if request.method != "GET" && request.method != "HEAD" {
+        return Response::error(405).with_header("Allow", "GET, HEAD");
+    }
+
+    let Some(mut path) = resolve(root, &request.path) else {
+        return Response::error(404);
+    };
+
+    if path.is_dir() {
+        path.push("index.html");
+    }
+
+    match fs::read(&path) {
+        Ok(contents) => Response::new(200)
+            .with_header("Content-Type", content_type(&path))
+            .with_body(contents),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Response::error(404),
+        Err(err) if err.kind() == io::ErrorKind::PermissionDenied => Response::error(403),
+        Err(err) => {
+            eprintln!("Could not read {}: {err}", path.display());
+            Response::error(500)
+        }
+    }
+}
+
+/// Turns a request path into a file path inside `root`.
+///
+/// Returns None for paths that try to leave `root`. Without this check,
+/// a request for `/../../etc/passwd` would happily serve a file from
+/// anywhere on the computer!
+fn resolve(root: &Path, request_path: &str) -> Option<PathBuf> {
+    let mut path = root.to_path_buf();
+
+    // Path::components splits a path into its parts, and tells us
+    // what each one is. Only plain names are allowed: no `..`, and no
+    // absolute paths (which would replace root when pushed).
+    for component in Path::new(request_path.trim_start_matches('/')).components() {
+        match component {
+            Component::Normal(name) => path.push(name),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+
+    Some(path)
+}
+
+/// Guesses a file's Content-Type from its extension, so that the
+/// browser knows what to do with it.
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("js") => "text/javascript; charset=utf-8",
+        Some("json") => "application/json",
+        Some("txt") => "text/plain; charset=utf-8",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("svg") => "image/svg+xml",
+        Some("ico") => "image/x-icon",
+        // "Some bytes, I don't know what they are."
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_paths() {
+        let root = Path::new("public");
+
+        assert_eq!(resolve(root, "/"), Some(PathBuf::from("public")));
+        assert_eq!(resolve(root, "/css/site.css"), Some(PathBuf::from("public/css/site.css")));
+        assert_eq!(resolve(root, "/./a.txt"), Some(PathBuf::from("public/a.txt")));
+    }
+
+    #[test]
+    fn blocks_escaping_the_root() {
+        let root = Path::new("public");
+
+        assert_eq!(resolve(root, "/../secret.txt"), None);
+        assert_eq!(resolve(root, "/css/../../secret.txt"), None);
+    }
+
+    #[test]
+    fn content_types() {
+        assert_eq!(content_type(Path::new("index.html")), "text/html; charset=utf-8");
+        assert_eq!(content_type(Path::new("data.bin")), "application/octet-stream");
+        assert_eq!(content_type(Path::new("README")), "application/octet-stream");
+    }
+}
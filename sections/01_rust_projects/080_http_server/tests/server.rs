@@ -0,0 +1,209 @@
+// These tests start a real server on a random port, and talk to it
+// over TCP, just like a browser would.
+
+use project::{static_files, Response, Router, Server};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::path::PathBuf;
+use std::thread;
+
+/// Starts a server in the background, and returns its address.
+fn start() -> SocketAddr {
+    let public = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("public");
+    let router = Router::new()
+        .get("/hello/:name", |_, params| Response::text(format!("Hello, {}!", params["name"])))
+        .post("/echo", |request, _| Response::text(String::from_utf8_lossy(&request.body)))
+        .get("/panic", |_, _| panic!("this handler is broken"))
+        .fallback(move |request, _| static_files::serve(&public, request));
+
+    // Port 0 means "any free port", so tests running at the same time
+    // don't get in each other's way.
+    let server = Server::bind("127.0.0.1:0", router, 2).unwrap();
+    let address = server.local_addr().unwrap();
+
+    // The server runs forever, and is stopped when the test ends.
+    thread::spawn(move || server.run());
+
+    address
+}
+
+/// A parsed response.
+struct Reply {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Reply {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn text(&self) -> &str {
+        std::str::from_utf8(&self.body).unwrap()
+    }
+}
+
+/// Reads one response from a connection.
+/// `has_body` is false for responses to HEAD requests.
+fn read_reply(reader: &mut impl BufRead, has_body: bool) -> Reply {
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    let status = line.split(' ').nth(1).unwrap().parse().unwrap();
+
+    let mut headers = Vec::new();
+    loop {
+        line.clear();
+        reader.read_line(&mut line).unwrap();
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let (name, value) = line.split_once(": ").unwrap();
+        headers.push((name.to_string(), value.to_string()));
+    }
+
+    let mut reply = Reply {
+        status,
+        headers,
+        body: Vec::new(),
+    };
+
+    if has_body {
+        let length = reply.header("Content-Length").unwrap().parse().unwrap();
+        reply.body = vec![0; length];
+        reader.read_exact(&mut reply.body).unwrap();
+    }
+
+    reply
+}
+
+/// Sends a raw request on a new connection, and reads the response.
+fn send(address: SocketAddr, request: &str) -> Reply {
+    let mut stream = TcpStream::connect(address).unwrap();
+    stream.write_all(request.as_bytes()).unwrap();
+
+    read_reply(&mut BufReader::new(stream), true)
+}
+
+#[test]
+fn routes() {
+    let address = start();
+
+    let reply = send(address, "GET /hello/ferris HTTP/1.1\r\nHost: test\r\n\r\n");
+    assert_eq!(reply.status, 200);
+    assert_eq!(reply.text(), "Hello, ferris!");
+
+    let reply = send(address, "POST /echo HTTP/1.1\r\nContent-Length: 11\r\n\r\nhello there");
+    assert_eq!(reply.text(), "hello there");
+}
+
+#[test]
+fn keep_alive() {
+    let address = start();
+    let stream = TcpStream::connect(address).unwrap();
+    let mut writer = stream.try_clone().unwrap();
+    let mut reader = BufReader::new(stream);
+
+    // Three requests on one connection.
+    for name in ["a", "b", "c"] {
+        write!(writer, "GET /hello/{name} HTTP/1.1\r\n\r\n").unwrap();
+        let reply = read_reply(&mut reader, true);
+
+        assert_eq!(reply.text(), format!("Hello, {name}!"));
+        assert_eq!(reply.header("Connection"), Some("keep-alive"));
+    }
+
+    // Asking to close gets one last response, and then the server
+    // closes the connection.
+    write!(writer, "GET /hello/d HTTP/1.1\r\nConnection: close\r\n\r\n").unwrap();
+    assert_eq!(read_reply(&mut reader, true).header("Connection"), Some("close"));
+
+    let mut rest = Vec::new();
+    reader.read_to_end(&mut rest).unwrap();
+    assert!(rest.is_empty());
+}
+
+#[test]
+fn pipelined_requests() {
+    // Clients are allowed to send several requests without waiting
+    // for the responses. The BufReader might read more than one request
+    // at once, and has to keep the rest for next time.
+    let address = start();
+    let mut stream = TcpStream::connect(address).unwrap();
+    stream
+        .write_all(b"GET /hello/one HTTP/1.1\r\n\r\nGET /hello/two HTTP/1.1\r\nConnection: close\r\n\r\n")
+        .unwrap();
+
+    let mut reader = BufReader::new(stream);
+    assert_eq!(read_reply(&mut reader, true).text(), "Hello, one!");
+    assert_eq!(read_reply(&mut reader, true).text(), "Hello, two!");
+}
+
+#[test]
+fn static_files() {
+    let address = start();
+
+    let reply = send(address, "GET / HTTP/1.1\r\n\r\n");
+    assert_eq!(reply.status, 200);
+    assert_eq!(reply.header("Content-Type"), Some("text/html; charset=utf-8"));
+    assert!(reply.text().contains("<h1>Hello from Rust!</h1>"));
+
+    let reply = send(address, "GET /style.css HTTP/1.1\r\n\r\n");
+    assert_eq!(reply.header("Content-Type"), Some("text/css; charset=utf-8"));
+
+    assert_eq!(send(address, "GET /missing.html HTTP/1.1\r\n\r\n").status, 404);
+    assert_eq!(send(address, "GET /../Cargo.toml HTTP/1.1\r\n\r\n").status, 404);
+    assert_eq!(send(address, "DELETE /style.css HTTP/1.1\r\n\r\n").status, 405);
+}
+
+#[test]
+fn head_requests() {
+    let address = start();
+    let mut stream = TcpStream::connect(address).unwrap();
+    stream.write_all(b"HEAD /hello/ferris HTTP/1.1\r\n\r\n").unwrap();
+
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let reply = read_reply(&mut reader, false);
+
+    // The headers describe the body that a GET would get.
+    assert_eq!(reply.status, 200);
+    assert_eq!(reply.header("Content-Length"), Some("14"));
+
+    // The connection is still usable, which proves that no body was sent.
+    stream.write_all(b"GET /hello/again HTTP/1.1\r\n\r\n").unwrap();
+    assert_eq!(read_reply(&mut reader, true).text(), "Hello, again!");
+}
+
+#[test]
+fn bad_requests() {
+    let address = start();
+
+    assert_eq!(send(address, "NONSENSE\r\n\r\n").status, 400);
+    assert_eq!(send(address, "GET / HTTP/3\r\n\r\n").status, 505);
+
+    let reply = send(address, "POST /echo HTTP/1.1\r\nContent-Length: 999999999\r\n\r\n");
+    assert_eq!(reply.status, 413);
+    assert_eq!(reply.header("Connection"), Some("close"));
+}
+
+#[test]
+fn survives_a_panicking_handler() {
+    let address = start();
+
+    // The panicking handler never answers, so the connection is closed
+    // without a response.
+    let mut stream = TcpStream::connect(address).unwrap();
+    stream.write_all(b"GET /panic HTTP/1.1\r\n\r\n").unwrap();
+    let mut rest = Vec::new();
+    stream.read_to_end(&mut rest).unwrap();
+    assert!(rest.is_empty());
+
+    // Both workers are still there.
+    for _ in 0..4 {
+        assert_eq!(send(address, "GET /hello/still-here HTTP/1.1\r\n\r\n").status, 200);
+    }
+}
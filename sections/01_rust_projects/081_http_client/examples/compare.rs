@@ -0,0 +1,30 @@
+// Fetches the same page with our client and with reqwest, and checks
+// that they agree. Run it with:
+//     cargo run --example compare -- http://example.com
+
+use std::time::Instant;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let url = std::env::args().nth(1).unwrap_or_else(|| "http://example.com".to_string());
+
+    let start = Instant::now();
+    let ours = project::get(&url)?;
+    println!("Ours:    {} in {:?}, {} bytes", ours.status, start.elapsed(), ours.body.len());
+
+    // reqwest does everything that our client does (and a lot more,
+    // like connection pooling, HTTPS, and compression), but the steps
+    // are the same: connect, write the request, read the response.
+    let start = Instant::now();
+    let theirs = reqwest::blocking::get(&url)?;
+    let status = theirs.status().as_u16();
+    let body = theirs.bytes()?;
+    println!("reqwest: {} in {:?}, {} bytes", status, start.elapsed(), body.len());
+
+    if ours.status == status && ours.body == body {
+        println!("Both clients got the same response.");
+    } else {
+        println!("The responses are different!");
+    }
+
+    Ok(())
+}
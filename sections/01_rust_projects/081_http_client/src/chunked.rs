@@ -0,0 +1,134 @@
+// Decoding a body sent with `Transfer-Encoding: chunked`.
+//
+// When a server doesn't know how big a response will be before it
+// starts sending it, it can't send a Content-Length. Instead, it sends
+// the body in chunks, each one starting with its size (in hexadecimal),
+// and ends with a chunk of size 0:
+//
+//     7\r\n
+//     Mozilla\r\n
+//     11\r\n
+//     Developer Network\r\n
+//     0\r\n
+//     \r\n
+//
+// That's "Mozilla" (7 bytes) and "Developer Network" (0x11 = 17 bytes).
+
+use std::io::{BufRead, Read};
+
+use crate::Error;
+
+/// The biggest body that we're willing to read, so that a server can't
+/// use up all of our memory.
+const MAX_BODY: usize = 64 * 1024 * 1024;
+
+/// Reads a chunked body, and returns it all joined together.
+pub fn read_chunked(reader: &mut impl BufRead) -> Result<Vec<u8>, Error> {
+    let mut body = Vec::new();
+
+    loop {
+        let line = read_line(reader)?;
+
+        // A chunk's size can be followed by extensions, like
+        // `1a;name=value`. Nobody really uses them, so we skip them.
+        let size = line.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size, 16)
+            .map_err(|_| Error::InvalidResponse(format!("invalid chunk size `{line}`")))?;
+
+        if size == 0 {
+            break;
+        }
+
+        // The size comes from the server, so it could be anything.
+        // Writing `body.len() + size > MAX_BODY` would overflow for a
+        // huge size, so subtract instead (body.len() is never more than
+        // MAX_BODY, so this can't go below 0).
+        if size > MAX_BODY - body.len() {
+            return Err(Error::InvalidResponse("the body is too large".to_string()));
+        }
+
+        // Read exactly `size` bytes onto the end of the body.
+        let start = body.len();
+        body.resize(start + size, 0);
+        reader.read_exact(&mut body[start..])?;
+
+        // Every chunk is followed by \r\n.
+        if !read_line(reader)?.is_empty() {
+            return Err(Error::InvalidResponse("a chunk is longer than its size".to_string()));
+        }
+    }
+
+    // After the last chunk, there can be trailers (headers that come
+    // after the body), and then an empty line. We don't use trailers,
+    // so they're skipped.
+    while !read_line(reader)?.is_empty() {}
+
+    Ok(body)
+}
+
+/// Reads a line, without the \r\n.
+pub(crate) fn read_line(reader: &mut impl BufRead) -> Result<String, Error> {
+    let mut line = Vec::new();
+
+    // take() stops a server from sending a never-ending line.
+    reader.take(8 * 1024).read_until(b'\n', &mut line)?;
+
+    let Some(line) = line.strip_suffix(b"\n") else {
+        return Err(Error::InvalidResponse("the response ended in the middle of a line".to_string()));
+    };
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+
+    String::from_utf8(line.to_vec()).map_err(|_| Error::InvalidResponse("a line isn't valid UTF-8".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(input: &str) -> Result<Vec<u8>, Error> {
+        read_chunked(&mut input.as_bytes())
+    }
+
+    #[test]
+    fn decodes_chunks() {
+        let body = decode("7\r\nMozilla\r\n11\r\nDeveloper Network\r\n0\r\n\r\n").unwrap();
+
+        assert_eq!(body, b"MozillaDeveloper Network");
+    }
+
+    #[test]
+    fn skips_extensions_and_trailers() {
+        let body = decode("5;note=hi\r\nhello\r\n0\r\nExpires: never\r\n\r\n").unwrap();
+
+        assert_eq!(body, b"hello");
+    }
+
+    #[test]
+    fn leaves_the_next_response_alone() {
+        let mut input = "2\r\nhi\r\n0\r\n\r\nHTTP/1.1 200 OK".as_bytes();
+
+        assert_eq!(read_chunked(&mut input).unwrap(), b"hi");
+        assert_eq!(input, b"HTTP/1.1 200 OK");
+    }
+
+    #[test]
+    fn rejects_bad_chunks() {
+        // Not hexadecimal.
+        assert!(decode("zz\r\nhello\r\n0\r\n\r\n").is_err());
+        // Longer than its size.
+        assert!(decode("2\r\nhello\r\n0\r\n\r\n").is_err());
+        // Cut off before the last chunk.
+        assert!(decode("5\r\nhello\r\n").is_err());
+        assert!(decode("5\r\nhel").is_err());
+    }
+
+    #[test]
+    fn rejects_huge_chunks() {
+        // Adding this to the body's length would overflow.
+        assert!(matches!(
+            decode("1\r\na\r\nffffffffffffffff\r\nhello\r\n0\r\n\r\n"),
+            Err(Error::InvalidResponse(_))
+        ));
+        assert!(decode("4000001\r\nhello\r\n0\r\n\r\n").is_err());
+    }
+}
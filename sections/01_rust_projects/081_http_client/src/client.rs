@@ -0,0 +1,145 @@
+// Putting it all together: connecting, sending the request, reading the
+// response, and following redirects.
+
+use std::io::{BufReader, BufWriter};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use crate::request::write_get;
+use crate::response::Response;
+use crate::url::Url;
+use crate::Error;
+
+pub struct Client {
+    timeout: Duration,
+    max_redirects: usize,
+    headers: Vec<(String, String)>,
+}
+
+impl Client {
+    pub fn new() -> Self {
+        Client {
+            timeout: Duration::from_secs(30),
+            max_redirects: 5,
+            headers: Vec::new(),
+        }
+    }
+
+    /// How long to wait for the server to connect, or to send more of
+    /// the response, before giving up.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// How many redirects to follow. 0 means that redirects are
+    /// returned like any other response.
+    pub fn with_max_redirects(mut self, max_redirects: usize) -> Self {
+        self.max_redirects = max_redirects;
+        self
+    }
+
+    /// Adds a header to every request.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sends a GET request, following any redirects.
+    pub fn get(&self, url: &str) -> Result<Response, Error> {
+        let mut url = Url::parse(url)?;
+        let mut redirects = 0;
+
+        loop {
+            let response = self.send(&url)?;
+
+            // 3xx responses with a Location header tell us to look
+            // somewhere else. (304 Not Modified is the exception, and
+            // doesn't have a Location.)
+            let location = match response.status {
+                301 | 302 | 303 | 307 | 308 if self.max_redirects > 0 => response.header("location"),
+                _ => None,
+            };
+
+            let Some(location) = location else {
+                return Ok(response);
+            };
+
+            if redirects == self.max_redirects {
+                return Err(Error::TooManyRedirects);
+            }
+
+            redirects += 1;
+            url = url.join(location)?;
+        }
+    }
+
+    /// Sends one request, without following redirects.
+    fn send(&self, url: &Url) -> Result<Response, Error> {
+        let stream = self.connect(url)?;
+
+        // Like in the HTTP server project, try_clone gives us a second
+        // handle to the connection for reading.
+        let mut writer = BufWriter::new(stream.try_clone()?);
+        write_get(&mut writer, url, &self.headers)?;
+
+        let mut reader = BufReader::new(stream);
+        Response::read(&mut reader, true)
+    }
+
+    fn connect(&self, url: &Url) -> Result<TcpStream, Error> {
+        // A host name like "example.com" has to be looked up (with DNS)
+        // to find its IP addresses. There can be several (for example,
+        // an IPv4 and an IPv6 one), so we try each until one works.
+        let mut last_error = None;
+
+        for address in (url.host.as_str(), url.port).to_socket_addrs()? {
+            match TcpStream::connect_timeout(&address, self.timeout) {
+                Ok(stream) => {
+                    stream.set_read_timeout(Some(self.timeout))?;
+                    stream.set_write_timeout(Some(self.timeout))?;
+                    return Ok(stream);
+                }
+                Err(err) => last_error = Some(err),
+            }
+        }
+
+        Err(match last_error {
+            Some(err) => Error::Io(err),
+            None => Error::InvalidUrl(format!("`{}` has no addresses", url.host)),
+        })
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Client::new()
+    }
+}
+
+/// Sends a GET request with the default settings.
+pub fn get(url: &str) -> Result<Response, Error> {
+    Client::new().get(url)
+}
+
+/// Fetches a page, and returns its body as text.
+///
+/// This does the same thing as this async function written with reqwest:
+///
+/// ```ignore
+/// async fn get_text(url: &str) -> reqwest::Result<String> {
+///     reqwest::get(url).await?.error_for_status()?.text().await
+/// }
+/// ```
+pub fn get_text(url: &str) -> Result<String, Error> {
+    let response = get(url)?;
+
+    if !response.is_success() {
+        return Err(Error::InvalidResponse(format!(
+            "the server answered with {} {}",
+            response.status, response.reason
+        )));
+    }
+
+    Ok(response.text())
+}
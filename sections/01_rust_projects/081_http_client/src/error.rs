@@ -0,0 +1,37 @@
+// Everything that can go wrong while making a request.
+
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The URL couldn't be parsed, or isn't one that we support.
+    InvalidUrl(String),
+    /// Connecting failed, or the connection broke or timed out.
+    Io(io::Error),
+    /// The server sent something that isn't valid HTTP.
+    InvalidResponse(String),
+    /// The server kept redirecting us, probably in a loop.
+    TooManyRedirects,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidUrl(reason) => write!(f, "invalid URL: {reason}"),
+            Error::Io(err) => write!(f, "connection error: {err}"),
+            Error::InvalidResponse(reason) => write!(f, "invalid response: {reason}"),
+            Error::TooManyRedirects => write!(f, "too many redirects"),
+        }
+    }
+}
+
+// This lets our Error be used with `?` in functions that return
+// `Result<_, Box<dyn std::error::Error>>`.
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
@@ -0,0 +1,21 @@
+// A small HTTP/1.1 client, built on nothing but std::net.
+//
+// The pieces, in the order that a request goes through them:
+// * url.rs splits the URL into a host, a port, and a path.
+// * client.rs connects to the server, and follows redirects.
+// * request.rs writes the request.
+// * response.rs reads the status line and headers, and works out where
+//   the body ends.
+// * chunked.rs decodes bodies sent with `Transfer-Encoding: chunked`.
+
+pub mod chunked;
+pub mod client;
+pub mod error;
+pub mod request;
+pub mod response;
+pub mod url;
+
+pub use client::{get, get_text, Client};
+pub use error::Error;
+pub use response::Response;
+pub use url::Url;
@@ -0,0 +1,30 @@
+// Fetch a page with `cargo run -- http://example.com`.
+// It prints the status and headers, then the body, a bit like
+// `curl -i`.
+
+use std::io::Write;
+
+fn main() {
+    let Some(url) = std::env::args().nth(1) else {
+        eprintln!("Usage: cargo run -- <url>");
+        std::process::exit(2);
+    };
+
+    let response = match project::get(&url) {
+        Ok(response) => response,
+        Err(err) => {
+            eprintln!("Could not fetch {url}: {err}");
+            std::process::exit(1);
+        }
+    };
+
+    println!("{} {}", response.status, response.reason);
+    for (name, value) in &response.headers {
+        println!("{name}: {value}");
+    }
+    println!();
+
+    // The body might not be text (it could be an image), so the bytes
+    // are written as they are, instead of going through a String.
+    let _ = std::io::stdout().write_all(&response.body);
+}
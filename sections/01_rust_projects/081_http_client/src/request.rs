@@ -0,0 +1,73 @@
+// Writing a GET request.
+//
+// A request is just a few lines of text:
+//
+//     GET /docs/index.html HTTP/1.1\r\n
+//     Host: example.com\r\n
+//     User-Agent: project/0.1\r\n
+//     Accept-Encoding: identity\r\n
+//     Connection: close\r\n
+//     \r\n
+
+use std::io::{self, Write};
+
+use crate::url::Url;
+
+/// The name that we give ourselves in the User-Agent header.
+/// Cargo fills these in from Cargo.toml.
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// Writes a GET request for `url`, with some extra headers.
+pub fn write_get(writer: &mut impl Write, url: &Url, headers: &[(String, String)]) -> io::Result<()> {
+    write!(writer, "GET {} HTTP/1.1\r\n", url.target)?;
+
+    // Host is the only header that HTTP/1.1 requires. One server (one
+    // IP address) can host lots of websites, and this is how it knows
+    // which one we want.
+    write!(writer, "Host: {}\r\n", url.host_header())?;
+    write!(writer, "User-Agent: {USER_AGENT}\r\n")?;
+
+    // "Please don't compress the body." Otherwise, we might get a gzip
+    // file back, which we'd have to decompress.
+    write!(writer, "Accept-Encoding: identity\r\n")?;
+
+    // We only make one request per connection, so we ask the server to
+    // close the connection after answering it, instead of keeping it
+    // open for the next request.
+    write!(writer, "Connection: close\r\n")?;
+
+    for (name, value) in headers {
+        write!(writer, "{name}: {value}\r\n")?;
+    }
+
+    // An empty line marks the end of the headers. A GET request has no
+    // body, so that's the end of the request.
+    write!(writer, "\r\n")?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_a_request() {
+        let url = Url::parse("http://example.com:8080/search?q=rust").unwrap();
+        let mut request = Vec::new();
+
+        write_get(&mut request, &url, &[("Accept".to_string(), "text/html".to_string())]).unwrap();
+
+        assert_eq!(
+            String::from_utf8(request).unwrap(),
+            format!(
+                "GET /search?q=rust HTTP/1.1\r\n\
+                 Host: example.com:8080\r\n\
+                 User-Agent: {USER_AGENT}\r\n\
+                 Accept-Encoding: identity\r\n\
+                 Connection: close\r\n\
+                 Accept: text/html\r\n\
+                 \r\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,243 @@
+// Reading a response.
+//
+//     HTTP/1.1 200 OK\r\n
+//     Content-Type: text/html\r\n
+//     Content-Length: 5\r\n
+//     \r\n
+//     hello
+//
+// The tricky part is the body. There are three ways that a server can
+// tell us where it ends:
+// * A Content-Length header, which says how many bytes it is.
+// * `Transfer-Encoding: chunked`, which sends it in pieces (see
+//   chunked.rs).
+// * Neither, in which case the body is everything until the server
+//   closes the connection.
+
+use std::io::{BufRead, Read};
+
+use crate::chunked::{read_chunked, read_line};
+use crate::Error;
+
+/// The most headers that we'll accept in one response.
+const MAX_HEADERS: usize = 100;
+
+/// The biggest body that we're willing to read.
+const MAX_BODY: u64 = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub status: u16,
+    /// The text after the status code, like "OK" or "Not Found".
+    pub reason: String,
+    /// The header names are stored in lowercase, since they aren't
+    /// case-sensitive.
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// Reads a response from a connection.
+    ///
+    /// `has_body` should be false for responses to HEAD requests, which
+    /// have the same headers as a GET would, but no body.
+    pub fn read(reader: &mut impl BufRead, has_body: bool) -> Result<Response, Error> {
+        loop {
+            let (status, reason) = read_status_line(reader)?;
+            let headers = read_headers(reader)?;
+
+            // 1xx responses (like "100 Continue") are only progress
+            // updates, and the real response comes after them.
+            if (100..200).contains(&status) {
+                continue;
+            }
+
+            let mut response = Response {
+                status,
+                reason,
+                headers,
+                body: Vec::new(),
+            };
+
+            // 204 No Content and 304 Not Modified never have a body,
+            // even if they have a Content-Length.
+            if has_body && status != 204 && status != 304 {
+                response.body = response.read_body(reader)?;
+            }
+
+            return Ok(response);
+        }
+    }
+
+    /// Looks up a header. Names aren't case-sensitive.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The body as text. Anything that isn't valid UTF-8 is replaced
+    /// with �.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    /// Whether the status is 2xx, which means that the request worked.
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    fn read_body(&self, reader: &mut impl BufRead) -> Result<Vec<u8>, Error> {
+        // Transfer-Encoding is a list, like `gzip, chunked`, and chunked
+        // always comes last. We asked for no compression, so we only
+        // expect to see `chunked` on its own.
+        if let Some(encoding) = self.header("transfer-encoding") {
+            let last = encoding.rsplit(',').next().unwrap_or_default().trim();
+            if !last.eq_ignore_ascii_case("chunked") {
+                return Err(Error::InvalidResponse(format!("unsupported Transfer-Encoding `{encoding}`")));
+            }
+
+            return read_chunked(reader);
+        }
+
+        if let Some(length) = self.header("content-length") {
+            let length: u64 = length
+                .parse()
+                .map_err(|_| Error::InvalidResponse(format!("invalid Content-Length `{length}`")))?;
+            if length > MAX_BODY {
+                return Err(Error::InvalidResponse("the body is too large".to_string()));
+            }
+
+            let mut body = vec![0; length as usize];
+            reader.read_exact(&mut body)?;
+            return Ok(body);
+        }
+
+        // No length, so the body goes on until the connection closes.
+        // One more byte than the limit is read, so we can tell whether
+        // the body went over it.
+        let mut body = Vec::new();
+        reader.take(MAX_BODY + 1).read_to_end(&mut body)?;
+        if body.len() as u64 > MAX_BODY {
+            return Err(Error::InvalidResponse("the body is too large".to_string()));
+        }
+
+        Ok(body)
+    }
+}
+
+/// Reads a line like `HTTP/1.1 404 Not Found`.
+fn read_status_line(reader: &mut impl BufRead) -> Result<(u16, String), Error> {
+    let line = read_line(reader)?;
+
+    // The reason can contain spaces (or be empty), so we only split
+    // off the first two parts.
+    let mut parts = line.splitn(3, ' ');
+    let (Some(version), Some(status)) = (parts.next(), parts.next()) else {
+        return Err(Error::InvalidResponse(format!("invalid status line `{line}`")));
+    };
+
+    if !version.starts_with("HTTP/1.") {
+        return Err(Error::InvalidResponse(format!("unsupported version `{version}`")));
+    }
+
+    let status = status
+        .parse()
+        .ok()
+        .filter(|status| (100..1000).contains(status))
+        .ok_or_else(|| Error::InvalidResponse(format!("invalid status code `{status}`")))?;
+
+    Ok((status, parts.next().unwrap_or_default().to_string()))
+}
+
+/// Reads `Name: value` lines, up to the empty line after them.
+fn read_headers(reader: &mut impl BufRead) -> Result<Vec<(String, String)>, Error> {
+    let mut headers = Vec::new();
+
+    loop {
+        let line = read_line(reader)?;
+        if line.is_empty() {
+            return Ok(headers);
+        }
+
+        if headers.len() == MAX_HEADERS {
+            return Err(Error::InvalidResponse("too many headers".to_string()));
+        }
+
+        let Some((name, value)) = line.split_once(':') else {
+            return Err(Error::InvalidResponse(format!("invalid header `{line}`")));
+        };
+
+        headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(input: &str) -> Result<Response, Error> {
+        Response::read(&mut input.as_bytes(), true)
+    }
+
+    #[test]
+    fn reads_content_length() {
+        let response = read("HTTP/1.1 200 OK\r\nContent-Length: 5\r\nX-Extra: a: b\r\n\r\nhello, and more").unwrap();
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.reason, "OK");
+        assert_eq!(response.header("X-EXTRA"), Some("a: b"));
+        assert_eq!(response.body, b"hello");
+    }
+
+    #[test]
+    fn reads_chunked() {
+        let response =
+            read("HTTP/1.1 404 Not Found\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nabc\r\n1\r\nd\r\n0\r\n\r\n").unwrap();
+
+        assert_eq!(response.status, 404);
+        assert_eq!(response.reason, "Not Found");
+        assert_eq!(response.body, b"abcd");
+    }
+
+    #[test]
+    fn reads_until_closed() {
+        let response = read("HTTP/1.0 200 OK\r\n\r\nall of\r\nthis").unwrap();
+
+        assert_eq!(response.body, b"all of\r\nthis");
+    }
+
+    #[test]
+    fn skips_bodies_that_dont_exist() {
+        let response = read("HTTP/1.1 304 Not Modified\r\nContent-Length: 5\r\n\r\n").unwrap();
+        assert!(response.body.is_empty());
+
+        let mut input = "HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\n".as_bytes();
+        let response = Response::read(&mut input, false).unwrap();
+        assert!(response.body.is_empty());
+    }
+
+    #[test]
+    fn skips_informational_responses() {
+        let response = read("HTTP/1.1 100 Continue\r\n\r\nHTTP/1.1 201 Created\r\nContent-Length: 0\r\n\r\n").unwrap();
+
+        assert_eq!(response.status, 201);
+    }
+
+    #[test]
+    fn rejects_bad_responses() {
+        for bad in [
+            "",
+            "HTTP/1.1\r\n\r\n",
+            "HTTP/2 200 OK\r\n\r\n",
+            "HTTP/1.1 abc OK\r\n\r\n",
+            "HTTP/1.1 200 OK\r\nno colon\r\n\r\n",
+            "HTTP/1.1 200 OK\r\nContent-Length: lots\r\n\r\n",
+            "HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\nshort",
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: gzip\r\n\r\n",
+        ] {
+            assert!(read(bad).is_err(), "{bad:?}");
+        }
+    }
+}
@@ -0,0 +1,158 @@
+// Splitting a URL into the parts that we need to make a request.
+//
+//     http://example.com:8080/docs/index.html?lang=en
+//     ^^^^   ^^^^^^^^^^^ ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+//    scheme      host    port         target
+
+use std::fmt;
+
+use crate::Error;
+
+/// The parts of an http:// URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Url {
+    pub host: String,
+    pub port: u16,
+    /// The path and query, like `/docs/index.html?lang=en`.
+    /// This is what goes in the request line.
+    pub target: String,
+}
+
+impl Url {
+    /// Parses a URL. Only http:// URLs are supported, since https://
+    /// would need TLS (encryption), which is a whole project of its own.
+    pub fn parse(url: &str) -> Result<Url, Error> {
+        let Some(rest) = url.strip_prefix("http://") else {
+            return Err(if url.starts_with("https://") {
+                Error::InvalidUrl("https:// isn't supported, only http://".to_string())
+            } else {
+                Error::InvalidUrl(format!("`{url}` should start with http://"))
+            });
+        };
+
+        // The authority (host and port) goes up to the first /, ?, or #.
+        let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+        let (authority, target) = rest.split_at(end);
+
+        // Everything after a # is only for the browser, and is never sent.
+        let target = target.split('#').next().unwrap_or_default();
+        let target = match target {
+            "" => "/".to_string(),
+            target if target.starts_with('?') => format!("/{target}"),
+            target => target.to_string(),
+        };
+
+        // user:password@host isn't supported.
+        if authority.contains('@') {
+            return Err(Error::InvalidUrl("usernames and passwords in URLs aren't supported".to_string()));
+        }
+
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => {
+                let port = port
+                    .parse()
+                    .map_err(|_| Error::InvalidUrl(format!("invalid port `{port}`")))?;
+                (host, port)
+            }
+            // 80 is the standard port for HTTP.
+            None => (authority, 80),
+        };
+
+        if host.is_empty() {
+            return Err(Error::InvalidUrl(format!("`{url}` has no host")));
+        }
+
+        Ok(Url {
+            host: host.to_string(),
+            port,
+            target,
+        })
+    }
+
+    /// Works out where a redirect's Location header points, which can
+    /// be a full URL, or a path on the same server.
+    pub fn join(&self, location: &str) -> Result<Url, Error> {
+        if location.starts_with("http://") || location.starts_with("https://") {
+            return Url::parse(location);
+        }
+
+        if !location.starts_with('/') {
+            // Relative paths like `../other` are allowed too, but are
+            // rare enough that we don't bother.
+            return Err(Error::InvalidUrl(format!("unsupported redirect to `{location}`")));
+        }
+
+        Ok(Url {
+            host: self.host.clone(),
+            port: self.port,
+            target: location.to_string(),
+        })
+    }
+
+    /// The value for the Host header. The port is left out when it's 80.
+    pub fn host_header(&self) -> String {
+        if self.port == 80 {
+            self.host.clone()
+        } else {
+            format!("{}:{}", self.host, self.port)
+        }
+    }
+}
+
+impl fmt::Display for Url {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "http://{}{}", self.host_header(), self.target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(host: &str, port: u16, target: &str) -> Url {
+        Url {
+            host: host.to_string(),
+            port,
+            target: target.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_urls() {
+        assert_eq!(Url::parse("http://example.com").unwrap(), url("example.com", 80, "/"));
+        assert_eq!(
+            Url::parse("http://localhost:8080/a/b?c=d#top").unwrap(),
+            url("localhost", 8080, "/a/b?c=d")
+        );
+        assert_eq!(Url::parse("http://example.com?q=1").unwrap(), url("example.com", 80, "/?q=1"));
+    }
+
+    #[test]
+    fn rejects_bad_urls() {
+        for bad in [
+            "example.com",
+            "https://example.com",
+            "http://",
+            "http://:80/",
+            "http://host:port/",
+            "http://user@host/",
+        ] {
+            assert!(Url::parse(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn joins_redirects() {
+        let base = url("example.com", 8080, "/old");
+
+        assert_eq!(base.join("/new").unwrap(), url("example.com", 8080, "/new"));
+        assert_eq!(base.join("http://other.com/x").unwrap(), url("other.com", 80, "/x"));
+        assert!(base.join("relative").is_err());
+    }
+
+    #[test]
+    fn displays() {
+        assert_eq!(url("a.com", 80, "/x").to_string(), "http://a.com/x");
+        assert_eq!(url("a.com", 81, "/x").to_string(), "http://a.com:81/x");
+    }
+}
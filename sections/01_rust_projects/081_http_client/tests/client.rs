@@ -0,0 +1,136 @@
+// These tests start a tiny server on a random port, which sends back
+// hand-written responses, so we can check every way that a body can be
+// sent.
+
+use project::{get, get_text, Client, Error};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::thread;
+
+/// Starts the server in the background, and returns its address.
+fn start() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            thread::spawn(move || answer(stream.unwrap()));
+        }
+    });
+
+    address
+}
+
+/// Reads a request, and writes a response based on its path.
+fn answer(mut stream: TcpStream) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+    let mut request = String::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        request.push_str(&line);
+        if line == "\r\n" || line.is_empty() {
+            break;
+        }
+    }
+
+    let target = request.split(' ').nth(1).unwrap_or_default();
+    let path = target.split('?').next().unwrap_or_default();
+    let response = match path {
+        "/length" => "HTTP/1.1 200 OK\r\nContent-Length: 12\r\n\r\nHello, world".to_string(),
+        "/chunked" => "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
+                       7\r\nHello, \r\n5;ext=1\r\nworld\r\n0\r\nTrailer: yes\r\n\r\n"
+            .to_string(),
+        // No length at all, so the body ends when we close the
+        // connection (when `stream` is dropped).
+        "/close" => "HTTP/1.1 200 OK\r\nConnection: close\r\n\r\nHello, world".to_string(),
+        "/redirect" => "HTTP/1.1 302 Found\r\nLocation: /length\r\nContent-Length: 0\r\n\r\n".to_string(),
+        "/loop" => "HTTP/1.1 301 Moved Permanently\r\nLocation: /loop\r\nContent-Length: 0\r\n\r\n".to_string(),
+        // Sends the request back, so the tests can look at it.
+        "/echo" => format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{request}", request.len()),
+        "/garbage" => "HELLO THERE\r\n\r\n".to_string(),
+        _ => "HTTP/1.1 404 Not Found\r\nContent-Length: 9\r\n\r\nNot Found".to_string(),
+    };
+
+    stream.write_all(response.as_bytes()).unwrap();
+}
+
+#[test]
+fn reads_every_kind_of_body() {
+    let address = start();
+
+    for path in ["/length", "/chunked", "/close"] {
+        let response = get(&format!("http://{address}{path}")).unwrap();
+
+        assert_eq!(response.status, 200, "{path}");
+        assert_eq!(response.text(), "Hello, world", "{path}");
+    }
+}
+
+#[test]
+fn sends_a_valid_request() {
+    let address = start();
+
+    let client = Client::new().with_header("X-Test", "yes");
+    let request = client.get(&format!("http://{address}/echo?a=b")).unwrap().text();
+
+    assert!(request.starts_with("GET /echo?a=b HTTP/1.1\r\n"));
+    assert!(request.contains(&format!("\r\nHost: {address}\r\n")));
+    assert!(request.contains("\r\nConnection: close\r\n"));
+    assert!(request.contains("\r\nX-Test: yes\r\n"));
+    assert!(request.ends_with("\r\n\r\n"));
+}
+
+#[test]
+fn follows_redirects() {
+    let address = start();
+
+    let response = get(&format!("http://{address}/redirect")).unwrap();
+    assert_eq!(response.text(), "Hello, world");
+
+    let response = Client::new()
+        .with_max_redirects(0)
+        .get(&format!("http://{address}/redirect"))
+        .unwrap();
+    assert_eq!(response.status, 302);
+    assert_eq!(response.header("Location"), Some("/length"));
+
+    assert!(matches!(get(&format!("http://{address}/loop")), Err(Error::TooManyRedirects)));
+}
+
+#[test]
+fn get_text_checks_the_status() {
+    let address = start();
+
+    assert_eq!(get_text(&format!("http://{address}/chunked")).unwrap(), "Hello, world");
+    assert!(get_text(&format!("http://{address}/missing")).is_err());
+}
+
+#[test]
+fn reports_errors() {
+    let address = start();
+
+    assert!(matches!(get(&format!("http://{address}/garbage")), Err(Error::InvalidResponse(_))));
+    assert!(matches!(get("https://example.com"), Err(Error::InvalidUrl(_))));
+
+    // Nothing is listening on this port, since the listener is dropped
+    // straight away.
+    let unused = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    assert!(matches!(get(&format!("http://{unused}/")), Err(Error::Io(_))));
+}
+
+#[test]
+fn agrees_with_reqwest() {
+    let address = start();
+
+    for path in ["/length", "/chunked", "/close", "/redirect", "/missing"] {
+        let url = format!("http://{address}{path}");
+
+        let ours = get(&url).unwrap();
+        let theirs = reqwest::blocking::get(&url).unwrap();
+
+        assert_eq!(ours.status, theirs.status().as_u16(), "{path}");
+        assert_eq!(ours.body, theirs.bytes().unwrap(), "{path}");
+    }
+}
@@ -0,0 +1,10 @@
+-- Migrations run in order, and each one only runs once. sqlx keeps track
+-- of which ones have already run in a table called _sqlx_migrations.
+CREATE TABLE users (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    -- UNIQUE makes the database reject a second user with the same email,
+    -- even if two requests try to create them at the same time.
+    email TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
@@ -0,0 +1,3 @@
+-- Once a migration has run somewhere, it should never be changed. To
+-- change the table, we add a new migration instead.
+ALTER TABLE users ADD COLUMN bio TEXT NOT NULL DEFAULT '';
@@ -0,0 +1,24 @@
+// Connecting to the database.
+
+use std::str::FromStr;
+
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+
+/// Connects to a SQLite database, like "sqlite://users.db", creating it
+/// if it doesn't exist, and runs any migrations that haven't been run
+/// yet.
+pub async fn connect(url: &str) -> Result<SqlitePool, sqlx::Error> {
+    let options = SqliteConnectOptions::from_str(url)?.create_if_missing(true);
+
+    // A pool keeps a few connections open, and hands them out to
+    // requests as they need them. Opening a new connection for every
+    // request would be slow.
+    let pool = SqlitePoolOptions::new().max_connections(5).connect_with(options).await?;
+
+    // migrate!() finds the files in the migrations folder when we
+    // compile, and builds them into the program. So the program doesn't
+    // need the folder to be there when it runs.
+    sqlx::migrate!().run(&pool).await?;
+
+    Ok(pool)
+}
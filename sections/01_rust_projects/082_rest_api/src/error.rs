@@ -0,0 +1,79 @@
+// Everything that can go wrong in a request, and the response that
+// each one turns into.
+//
+// Handlers return Result<_, ApiError>, so they can use `?` on database
+// calls, and axum turns the error into a response with IntoResponse.
+
+use axum::extract::rejection::{JsonRejection, PathRejection, QueryRejection};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde_json::json;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ApiError {
+    /// There's no user with the ID in the path.
+    #[error("user not found")]
+    NotFound,
+    /// The request body is valid JSON, but something in it isn't
+    /// allowed, like an empty name.
+    #[error("{0}")]
+    Validation(String),
+    /// Another user already has this email.
+    #[error("a user with this email already exists")]
+    EmailTaken,
+    /// The request body isn't JSON, or doesn't have the right fields.
+    /// axum's message for this already says what went wrong.
+    #[error(transparent)]
+    Json(#[from] JsonRejection),
+    /// The ID in the path isn't a number, like /users/abc.
+    #[error(transparent)]
+    Path(#[from] PathRejection),
+    /// The query string has the wrong types, like ?limit=lots.
+    #[error(transparent)]
+    Query(#[from] QueryRejection),
+    /// Something went wrong in the database. This is our fault, not the
+    /// client's.
+    #[error("database error")]
+    Database(#[source] sqlx::Error),
+}
+
+// Not a #[from], because some database errors mean something more
+// specific.
+impl From<sqlx::Error> for ApiError {
+    fn from(err: sqlx::Error) -> Self {
+        match &err {
+            // fetch_one returns this when the query found nothing.
+            sqlx::Error::RowNotFound => ApiError::NotFound,
+            // The only UNIQUE column is the email.
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => ApiError::EmailTaken,
+            _ => ApiError::Database(err),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            ApiError::NotFound => StatusCode::NOT_FOUND,
+            ApiError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::EmailTaken => StatusCode::CONFLICT,
+            // This is 400 for invalid JSON, and 422 for missing fields.
+            ApiError::Json(rejection) => rejection.status(),
+            ApiError::Path(rejection) => rejection.status(),
+            ApiError::Query(rejection) => rejection.status(),
+            ApiError::Database(err) => {
+                // The details could give away how the database is set
+                // up, so they're only printed here, and the client just
+                // gets "database error".
+                eprintln!("Database error: {err}");
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+
+        // Every error has the same shape, so clients only need to
+        // handle one: {"error": "user not found"}
+        (status, Json(json!({ "error": self.to_string() }))).into_response()
+    }
+}
@@ -0,0 +1,25 @@
+// A JSON API for creating, reading, updating, and deleting (CRUD)
+// users, with axum for the HTTP side and sqlx for the database.
+//
+// * db.rs connects to the database, and runs the migrations.
+// * users.rs has the handlers for each endpoint.
+// * error.rs turns errors into responses.
+
+pub mod db;
+pub mod error;
+pub mod users;
+
+use axum::routing::get;
+use axum::Router;
+use sqlx::SqlitePool;
+
+pub use error::ApiError;
+
+/// Builds the router. The pool is the "state" that's shared with every
+/// handler, which gets it with the State extractor.
+pub fn app(pool: SqlitePool) -> Router {
+    Router::new()
+        .route("/users", get(users::list).post(users::create))
+        .route("/users/{id}", get(users::get).patch(users::update).delete(users::delete))
+        .with_state(pool)
+}
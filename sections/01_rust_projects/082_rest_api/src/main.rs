@@ -0,0 +1,36 @@
+// Start the API with `cargo run`, then try it with curl:
+//     curl -i -H "Content-Type: application/json" \
+//         -d '{"name": "Ferris", "email": "ferris@example.com"}' http://127.0.0.1:3000/users
+//     curl http://127.0.0.1:3000/users
+//     curl -X PATCH -H "Content-Type: application/json" -d '{"bio": "Crab"}' http://127.0.0.1:3000/users/1
+//     curl -X DELETE http://127.0.0.1:3000/users/1
+
+const ADDRESS: &str = "127.0.0.1:3000";
+
+#[tokio::main]
+async fn main() {
+    // The database can be changed with the DATABASE_URL environment
+    // variable. By default, it's a file called users.db.
+    let url = std::env::var("DATABASE_URL").unwrap_or_else(|_| "sqlite://users.db".to_string());
+
+    let pool = match project::db::connect(&url).await {
+        Ok(pool) => pool,
+        Err(err) => {
+            eprintln!("Could not open the database at {url}: {err}");
+            std::process::exit(1);
+        }
+    };
+
+    let listener = match tokio::net::TcpListener::bind(ADDRESS).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            eprintln!("Could not listen on {ADDRESS}: {err}");
+            std::process::exit(1);
+        }
+    };
+
+    println!("Listening on http://{ADDRESS}");
+    if let Err(err) = axum::serve(listener, project::app(pool)).await {
+        eprintln!("Server error: {err}");
+    }
+}
@@ -0,0 +1,243 @@
+// The /users endpoints.
+//
+//     GET    /users          List users (?limit=20&offset=40 for more).
+//     POST   /users          Create a user.
+//     GET    /users/{id}     Get one user.
+//     PATCH  /users/{id}     Change some of a user's fields.
+//     DELETE /users/{id}     Delete a user.
+//
+// Each handler is an async function. Its arguments are "extractors",
+// which pull what the handler needs out of the request (like the ID in
+// the path, or the JSON body), and its return value is turned into the
+// response.
+
+use axum::extract::{FromRequest, FromRequestParts, Path, Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+use crate::error::ApiError;
+
+/// A user, as stored in the database and sent back to clients.
+///
+/// FromRow lets sqlx build one from a row, by matching the column names
+/// to the field names.
+#[derive(Debug, Serialize, FromRow)]
+pub struct User {
+    pub id: i64,
+    pub name: String,
+    pub email: String,
+    pub bio: String,
+    /// When the user was created, like "2024-05-01 12:30:00" (in UTC).
+    pub created_at: String,
+}
+
+/// The body of a POST /users request.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NewUser {
+    pub name: String,
+    pub email: String,
+    #[serde(default)]
+    pub bio: String,
+}
+
+/// The body of a PATCH /users/{id} request. Fields that are left out
+/// aren't changed.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UserUpdate {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub bio: Option<String>,
+}
+
+/// The ?limit=...&offset=... query for listing users.
+#[derive(Debug, Deserialize)]
+pub struct Page {
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub offset: i64,
+}
+
+fn default_limit() -> i64 {
+    20
+}
+
+/// Works just like axum's Json extractor, except that when the body
+/// isn't valid, the rejection is turned into an ApiError. That way,
+/// bad JSON gets the same {"error": ...} response as every other error.
+#[derive(FromRequest)]
+#[from_request(via(Json), rejection(ApiError))]
+pub struct ApiJson<T>(pub T);
+
+/// The same, for axum's Path extractor, so that /users/abc gets a JSON
+/// error too. Path and Query only look at the URL, not the body, which
+/// is why these derive FromRequestParts instead.
+#[derive(FromRequestParts)]
+#[from_request(via(Path), rejection(ApiError))]
+pub struct ApiPath<T>(pub T);
+
+/// The same, for axum's Query extractor.
+#[derive(FromRequestParts)]
+#[from_request(via(Query), rejection(ApiError))]
+pub struct ApiQuery<T>(pub T);
+
+/// Every column, in the order that User's fields are in.
+const COLUMNS: &str = "id, name, email, bio, created_at";
+
+pub async fn list(State(pool): State<SqlitePool>, ApiQuery(page): ApiQuery<Page>) -> Result<Json<Vec<User>>, ApiError> {
+    // Nobody gets to ask for a million users at once.
+    let limit = page.limit.clamp(1, 100);
+    let offset = page.offset.max(0);
+
+    // The ?s are filled in by bind(), in order. Never build a query by
+    // pasting values into the SQL with format!: a name like
+    // `'; DROP TABLE users; --` would be run as SQL! This is called SQL
+    // injection.
+    let users = sqlx::query_as::<_, User>(&format!("SELECT {COLUMNS} FROM users ORDER BY id LIMIT ? OFFSET ?"))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&pool)
+        .await?;
+
+    Ok(Json(users))
+}
+
+pub async fn create(
+    State(pool): State<SqlitePool>,
+    ApiJson(user): ApiJson<NewUser>,
+) -> Result<impl IntoResponse, ApiError> {
+    let name = validate_name(&user.name)?;
+    let email = validate_email(&user.email)?;
+
+    // RETURNING gives us back the new row, including the ID and
+    // created_at that the database filled in.
+    let user = sqlx::query_as::<_, User>(&format!(
+        "INSERT INTO users (name, email, bio) VALUES (?, ?, ?) RETURNING {COLUMNS}"
+    ))
+    .bind(name)
+    .bind(email)
+    .bind(user.bio)
+    .fetch_one(&pool)
+    .await?;
+
+    // 201 Created, with a Location header saying where the new user
+    // can be found.
+    let location = format!("/users/{}", user.id);
+    Ok((StatusCode::CREATED, [(header::LOCATION, location)], Json(user)))
+}
+
+pub async fn get(State(pool): State<SqlitePool>, ApiPath(id): ApiPath<i64>) -> Result<Json<User>, ApiError> {
+    let user = sqlx::query_as::<_, User>(&format!("SELECT {COLUMNS} FROM users WHERE id = ?"))
+        .bind(id)
+        .fetch_optional(&pool)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    Ok(Json(user))
+}
+
+pub async fn update(
+    State(pool): State<SqlitePool>,
+    ApiPath(id): ApiPath<i64>,
+    ApiJson(update): ApiJson<UserUpdate>,
+) -> Result<Json<User>, ApiError> {
+    let name = update.name.as_deref().map(validate_name).transpose()?;
+    let email = update.email.as_deref().map(validate_email).transpose()?;
+
+    // COALESCE(?, name) is the new name if one was given (the ? isn't
+    // NULL), or else the name that's already there.
+    let user = sqlx::query_as::<_, User>(&format!(
+        "UPDATE users SET name = COALESCE(?, name), email = COALESCE(?, email), bio = COALESCE(?, bio)
+         WHERE id = ? RETURNING {COLUMNS}"
+    ))
+    .bind(name)
+    .bind(email)
+    .bind(update.bio)
+    .bind(id)
+    .fetch_optional(&pool)
+    .await?
+    .ok_or(ApiError::NotFound)?;
+
+    Ok(Json(user))
+}
+
+pub async fn delete(State(pool): State<SqlitePool>, ApiPath(id): ApiPath<i64>) -> Result<StatusCode, ApiError> {
+    let result = sqlx::query("DELETE FROM users WHERE id = ?")
+        .bind(id)
+        .execute(&pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(ApiError::NotFound);
+    }
+
+    // 204 No Content: it worked, and there's nothing to send back.
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Trims a name, and checks that it's a sensible length.
+fn validate_name(name: &str) -> Result<String, ApiError> {
+    let name = name.trim();
+
+    if name.is_empty() {
+        return Err(ApiError::Validation("name can't be empty".to_string()));
+    }
+    if name.chars().count() > 100 {
+        return Err(ApiError::Validation("name can't be longer than 100 characters".to_string()));
+    }
+
+    Ok(name.to_string())
+}
+
+/// Trims and lowercases an email, and checks that it looks like one.
+///
+/// Lowercasing means that `Ferris@Example.com` and `ferris@example.com`
+/// count as the same email. Properly checking an email is famously hard,
+/// so we only check the basics: something, then an @, then a domain.
+fn validate_email(email: &str) -> Result<String, ApiError> {
+    let email = email.trim().to_lowercase();
+
+    let valid = match email.split_once('@') {
+        Some((user, domain)) => {
+            !user.is_empty()
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !domain.contains('@')
+                && !email.contains(char::is_whitespace)
+        }
+        None => false,
+    };
+
+    if !valid {
+        return Err(ApiError::Validation(format!("`{email}` isn't a valid email")));
+    }
+
+    Ok(email)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_names() {
+        assert_eq!(validate_name("  Ferris ").unwrap(), "Ferris");
+        assert!(validate_name("   ").is_err());
+        assert!(validate_name(&"a".repeat(101)).is_err());
+    }
+
+    #[test]
+    fn validates_emails() {
+        assert_eq!(validate_email(" Ferris@Example.com ").unwrap(), "ferris@example.com");
+
+        for bad in ["", "ferris", "@example.com", "ferris@", "ferris@com", "ferris@.com", "a@b@c.com", "a b@c.com"] {
+            assert!(validate_email(bad).is_err(), "{bad}");
+        }
+    }
+}
@@ -0,0 +1,243 @@
+// These tests send requests straight to the router, with a fresh
+// database for every test, so they can't get in each other's way.
+
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use axum::Router;
+use http_body_util::BodyExt;
+use serde_json::{json, Value};
+use tempfile::TempDir;
+use tower::ServiceExt;
+
+/// Builds the app with an empty database. The database is deleted when
+/// the TempDir is dropped, so keep it around until the test ends.
+async fn app() -> (Router, TempDir) {
+    let dir = tempfile::tempdir().unwrap();
+    let url = format!("sqlite://{}", dir.path().join("test.db").display());
+    let pool = project::db::connect(&url).await.unwrap();
+
+    (project::app(pool), dir)
+}
+
+struct Reply {
+    status: StatusCode,
+    location: Option<String>,
+    body: Value,
+}
+
+/// Sends a request, with an optional JSON body.
+async fn send(app: &Router, method: &str, uri: &str, body: Option<Value>) -> Reply {
+    let request = Request::builder().method(method).uri(uri);
+    let request = match body {
+        Some(body) => request
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string())),
+        None => request.body(Body::empty()),
+    }
+    .unwrap();
+
+    // oneshot sends a single request through the router. The router is
+    // cloned, since oneshot takes it by value (which is cheap, since
+    // everything inside it is shared).
+    let response = app.clone().oneshot(request).await.unwrap();
+
+    let status = response.status();
+    let location = response
+        .headers()
+        .get(header::LOCATION)
+        .map(|location| location.to_str().unwrap().to_string());
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let body = if bytes.is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_slice(&bytes).unwrap()
+    };
+
+    Reply { status, location, body }
+}
+
+async fn create(app: &Router, name: &str, email: &str) -> Value {
+    let reply = send(app, "POST", "/users", Some(json!({ "name": name, "email": email }))).await;
+    assert_eq!(reply.status, StatusCode::CREATED, "{}", reply.body);
+    reply.body
+}
+
+#[tokio::test]
+async fn creates_and_gets_users() {
+    let (app, _dir) = app().await;
+
+    let reply = send(
+        &app,
+        "POST",
+        "/users",
+        Some(json!({ "name": " Ferris ", "email": "Ferris@Example.com", "bio": "Crab" })),
+    )
+    .await;
+
+    assert_eq!(reply.status, StatusCode::CREATED);
+    assert_eq!(reply.body["name"], "Ferris");
+    assert_eq!(reply.body["email"], "ferris@example.com");
+    assert_eq!(reply.body["bio"], "Crab");
+
+    let id = reply.body["id"].as_i64().unwrap();
+    assert_eq!(reply.location, Some(format!("/users/{id}")));
+
+    let reply = send(&app, "GET", &format!("/users/{id}"), None).await;
+    assert_eq!(reply.status, StatusCode::OK);
+    assert_eq!(reply.body["name"], "Ferris");
+    assert!(reply.body["created_at"].is_string());
+}
+
+#[tokio::test]
+async fn lists_users_in_pages() {
+    let (app, _dir) = app().await;
+
+    for i in 0..5 {
+        create(&app, &format!("User {i}"), &format!("user{i}@example.com")).await;
+    }
+
+    let reply = send(&app, "GET", "/users", None).await;
+    assert_eq!(reply.body.as_array().unwrap().len(), 5);
+
+    let reply = send(&app, "GET", "/users?limit=2&offset=3", None).await;
+    let names: Vec<_> = reply.body.as_array().unwrap().iter().map(|user| user["name"].clone()).collect();
+    assert_eq!(names, [json!("User 3"), json!("User 4")]);
+}
+
+#[tokio::test]
+async fn updates_users() {
+    let (app, _dir) = app().await;
+    let id = create(&app, "Ferris", "ferris@example.com").await["id"].clone();
+
+    let reply = send(&app, "PATCH", &format!("/users/{id}"), Some(json!({ "bio": "Likes Rust" }))).await;
+
+    assert_eq!(reply.status, StatusCode::OK);
+    assert_eq!(reply.body["bio"], "Likes Rust");
+    // The fields that weren't sent haven't changed.
+    assert_eq!(reply.body["name"], "Ferris");
+    assert_eq!(reply.body["email"], "ferris@example.com");
+
+    let reply = send(&app, "PATCH", &format!("/users/{id}"), Some(json!({ "name": "" }))).await;
+    assert_eq!(reply.status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn deletes_users() {
+    let (app, _dir) = app().await;
+    let id = create(&app, "Ferris", "ferris@example.com").await["id"].clone();
+
+    let reply = send(&app, "DELETE", &format!("/users/{id}"), None).await;
+    assert_eq!(reply.status, StatusCode::NO_CONTENT);
+
+    let reply = send(&app, "GET", &format!("/users/{id}"), None).await;
+    assert_eq!(reply.status, StatusCode::NOT_FOUND);
+
+    let reply = send(&app, "DELETE", &format!("/users/{id}"), None).await;
+    assert_eq!(reply.status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn missing_users_are_not_found() {
+    let (app, _dir) = app().await;
+
+    let reply = send(&app, "GET", "/users/42", None).await;
+    assert_eq!(reply.status, StatusCode::NOT_FOUND);
+    assert_eq!(reply.body, json!({ "error": "user not found" }));
+
+    let reply = send(&app, "PATCH", "/users/42", Some(json!({ "bio": "?" }))).await;
+    assert_eq!(reply.status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn emails_are_unique() {
+    let (app, _dir) = app().await;
+    create(&app, "Ferris", "ferris@example.com").await;
+    let other = create(&app, "Corro", "corro@example.com").await["id"].clone();
+
+    // Emails are lowercased, so this is the same email.
+    let reply = send(
+        &app,
+        "POST",
+        "/users",
+        Some(json!({ "name": "Impostor", "email": "FERRIS@example.com" })),
+    )
+    .await;
+    assert_eq!(reply.status, StatusCode::CONFLICT);
+
+    let reply = send(
+        &app,
+        "PATCH",
+        &format!("/users/{other}"),
+        Some(json!({ "email": "ferris@example.com" })),
+    )
+    .await;
+    assert_eq!(reply.status, StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn rejects_bad_bodies() {
+    let (app, _dir) = app().await;
+
+    // Not JSON at all.
+    let request = Request::post("/users")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from("{oops"))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Every error has the same shape.
+    let reply = send(&app, "POST", "/users", Some(json!({ "name": "Ferris" }))).await;
+    assert_eq!(reply.status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(reply.body["error"].is_string());
+
+    let reply = send(
+        &app,
+        "POST",
+        "/users",
+        Some(json!({ "name": "Ferris", "email": "not an email" })),
+    )
+    .await;
+    assert_eq!(reply.status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let reply = send(
+        &app,
+        "POST",
+        "/users",
+        Some(json!({ "name": "Ferris", "email": "ferris@example.com", "admin": true })),
+    )
+    .await;
+    assert_eq!(reply.status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn rejects_bad_urls() {
+    let (app, _dir) = app().await;
+
+    // These are caught by the Path and Query extractors, before the
+    // handler runs, but still get the same {"error": ...} shape.
+    for uri in ["/users/abc", "/users?limit=lots"] {
+        let reply = send(&app, "GET", uri, None).await;
+        assert_eq!(reply.status, StatusCode::BAD_REQUEST, "{uri}");
+        assert!(reply.body["error"].is_string(), "{uri}");
+    }
+
+    let reply = send(&app, "DELETE", "/users/1.5", None).await;
+    assert_eq!(reply.status, StatusCode::BAD_REQUEST);
+    assert!(reply.body["error"].is_string());
+}
+
+#[tokio::test]
+async fn data_survives_reconnecting() {
+    let dir = tempfile::tempdir().unwrap();
+    let url = format!("sqlite://{}", dir.path().join("test.db").display());
+
+    let app = project::app(project::db::connect(&url).await.unwrap());
+    create(&app, "Ferris", "ferris@example.com").await;
+
+    // Connecting again runs the migrations again, which should skip the
+    // ones that already ran, and keep the data.
+    let app = project::app(project::db::connect(&url).await.unwrap());
+    let reply = send(&app, "GET", "/users", None).await;
+    assert_eq!(reply.body.as_array().unwrap().len(), 1);
+}
@@ -0,0 +1,13 @@
+// Build scripts run before the rest of the crate is compiled. This one
+// generates the Rust code for the messages and the service in
+// proto/weather.proto, which src/lib.rs then includes.
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // tonic-build runs protoc, and looks for it in the PROTOC
+    // environment variable.
+    std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+
+    tonic_build::compile_protos("proto/weather.proto")?;
+
+    Ok(())
+}
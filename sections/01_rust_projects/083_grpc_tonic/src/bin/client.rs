@@ -0,0 +1,123 @@
+// A command-line client for the weather station. Start the server with
+// `cargo run` first, then:
+//     cargo run --bin client -- report kitchen 21.5 22 22.5
+//     cargo run --bin client -- latest kitchen
+//     cargo run --bin client -- history kitchen
+//     cargo run --bin client -- watch kitchen
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use project::pb::station_client::StationClient;
+use project::pb::{Reading, SensorRequest};
+use tonic::transport::Channel;
+
+const SERVER: &str = "http://127.0.0.1:50051";
+
+const USAGE: &str = "Usage:
+    client report <sensor> <celsius>...
+    client latest <sensor>
+    client history <sensor>
+    client watch <sensor>";
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let [command, sensor, rest @ ..] = args.as_slice() else {
+        eprintln!("{USAGE}");
+        std::process::exit(2);
+    };
+
+    // The generated client has one async method for each rpc.
+    let mut client = match StationClient::connect(SERVER).await {
+        Ok(client) => client,
+        Err(err) => {
+            eprintln!("Could not connect to {SERVER}: {err}");
+            std::process::exit(1);
+        }
+    };
+
+    let result = match command.as_str() {
+        "report" => report(&mut client, sensor, rest).await,
+        "latest" => latest(&mut client, sensor).await,
+        "history" => history(&mut client, sensor).await,
+        "watch" => watch(&mut client, sensor).await,
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(2);
+        }
+    };
+
+    // A Status has a code (like NotFound), and a message.
+    if let Err(status) = result {
+        eprintln!("Error ({:?}): {}", status.code(), status.message());
+        std::process::exit(1);
+    }
+}
+
+async fn report(client: &mut StationClient<Channel>, sensor: &str, temperatures: &[String]) -> Result<(), tonic::Status> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+
+    let mut readings = Vec::new();
+    for temperature in temperatures {
+        let Ok(celsius) = temperature.parse() else {
+            return Err(tonic::Status::invalid_argument(format!("`{temperature}` isn't a number")));
+        };
+        readings.push(Reading {
+            sensor: sensor.to_string(),
+            celsius,
+            timestamp: now,
+        });
+    }
+
+    // Any Stream can be sent as a client stream. Here, it's made from a
+    // Vec, but it could just as well be readings from a real sensor,
+    // sent as they're taken.
+    let summary = client.report(tokio_stream::iter(readings)).await?.into_inner();
+
+    println!(
+        "Recorded {} readings (min {:.1}°C, max {:.1}°C, mean {:.1}°C)",
+        summary.count, summary.min, summary.max, summary.mean
+    );
+    Ok(())
+}
+
+async fn latest(client: &mut StationClient<Channel>, sensor: &str) -> Result<(), tonic::Status> {
+    let request = SensorRequest {
+        sensor: sensor.to_string(),
+    };
+    let reading = client.latest(request).await?.into_inner();
+
+    print_reading(&reading);
+    Ok(())
+}
+
+async fn history(client: &mut StationClient<Channel>, sensor: &str) -> Result<(), tonic::Status> {
+    let request = SensorRequest {
+        sensor: sensor.to_string(),
+    };
+    let mut stream = client.history(request).await?.into_inner();
+
+    // message() returns None when the server ends the stream.
+    while let Some(reading) = stream.message().await? {
+        print_reading(&reading);
+    }
+    Ok(())
+}
+
+async fn watch(client: &mut StationClient<Channel>, sensor: &str) -> Result<(), tonic::Status> {
+    let request = SensorRequest {
+        sensor: sensor.to_string(),
+    };
+    let mut stream = client.watch(request).await?.into_inner();
+
+    println!("Watching {sensor}. Press Ctrl+C to stop.");
+    while let Some(reading) = stream.message().await? {
+        print_reading(&reading);
+    }
+    Ok(())
+}
+
+fn print_reading(reading: &Reading) {
+    println!("{} at {}: {:.1}°C", reading.sensor, reading.timestamp, reading.celsius);
+}
@@ -0,0 +1,18 @@
+// A weather station, as a gRPC service.
+//
+// * proto/weather.proto describes the service and its messages.
+// * build.rs turns it into Rust code, which is included in `pb` below.
+// * station.rs implements the service.
+// * main.rs runs the server, and bin/client.rs is a client for it.
+
+/// The code generated from proto/weather.proto. "pb" is short for
+/// "protocol buffers".
+pub mod pb {
+    // This pastes in the file that build.rs generated (inside the
+    // target folder), named after the package in the .proto file.
+    tonic::include_proto!("weather");
+}
+
+pub mod station;
+
+pub use station::WeatherStation;
@@ -0,0 +1,25 @@
+// Start the server with `cargo run`, then use the client in another
+// terminal:
+//     cargo run --bin client -- report kitchen 21.5 22 22.5
+//     cargo run --bin client -- latest kitchen
+
+use project::pb::station_server::StationServer;
+use project::WeatherStation;
+use tonic::transport::Server;
+
+const ADDRESS: &str = "127.0.0.1:50051";
+
+#[tokio::main]
+async fn main() {
+    let address = ADDRESS.parse().unwrap();
+
+    // StationServer wraps our WeatherStation, and handles turning gRPC
+    // requests into calls to its methods.
+    let service = StationServer::new(WeatherStation::new());
+
+    println!("Listening on {ADDRESS}");
+    if let Err(err) = Server::builder().add_service(service).serve(address).await {
+        eprintln!("Server error: {err}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,177 @@
+// The server side of the service: one method for each rpc in the
+// .proto file.
+
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Mutex;
+
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status, Streaming};
+
+use crate::pb::station_server::Station;
+use crate::pb::{Reading, SensorRequest, Summary};
+
+/// How many new readings can be waiting to be sent to a slow watcher
+/// before it starts missing some.
+const WATCH_BUFFER: usize = 64;
+
+/// The most readings that one Report call can send. They're all kept in
+/// memory until the call finishes, so without a limit, a client could
+/// make the server run out of memory.
+pub const MAX_REPORT_READINGS: usize = 10_000;
+
+pub struct WeatherStation {
+    /// Every reading so far, by sensor. This is a std Mutex, since it's
+    /// never held across an .await.
+    readings: Mutex<HashMap<String, Vec<Reading>>>,
+    /// New readings are sent here, and each Watch call listens to it.
+    updates: broadcast::Sender<Reading>,
+}
+
+impl WeatherStation {
+    pub fn new() -> Self {
+        WeatherStation {
+            readings: Mutex::new(HashMap::new()),
+            updates: broadcast::channel(WATCH_BUFFER).0,
+        }
+    }
+
+    /// Every reading from a sensor, or None if it hasn't sent any.
+    fn readings_for(&self, sensor: &str) -> Option<Vec<Reading>> {
+        self.readings.lock().unwrap().get(sensor).cloned()
+    }
+}
+
+/// The error for a sensor that hasn't sent any readings.
+fn no_readings(sensor: &str) -> Status {
+    Status::not_found(format!("no readings from `{sensor}`"))
+}
+
+impl Default for WeatherStation {
+    fn default() -> Self {
+        WeatherStation::new()
+    }
+}
+
+// The Station trait was generated from the `service Station` in the
+// .proto file. Async functions in traits need the async_trait macro
+// here, which tonic re-exports.
+#[tonic::async_trait]
+impl Station for WeatherStation {
+    async fn latest(&self, request: Request<SensorRequest>) -> Result<Response<Reading>, Status> {
+        let sensor = request.into_inner().sensor;
+        let readings = self.readings_for(&sensor).ok_or_else(|| no_readings(&sensor))?;
+
+        // readings_for only finds sensors that have sent something, so
+        // there's always a last one.
+        let latest = readings.last().cloned().unwrap_or_default();
+        Ok(Response::new(latest))
+    }
+
+    // Streaming responses need a type for the stream. Since the
+    // readings are already in a Vec, we can turn it straight into one.
+    type HistoryStream = tokio_stream::Iter<std::vec::IntoIter<Result<Reading, Status>>>;
+
+    async fn history(&self, request: Request<SensorRequest>) -> Result<Response<Self::HistoryStream>, Status> {
+        let sensor = request.into_inner().sensor;
+        let readings = self.readings_for(&sensor).ok_or_else(|| no_readings(&sensor))?;
+
+        // Each item in the stream is a Result, so a stream can fail
+        // part of the way through.
+        let stream = readings.into_iter().map(Ok).collect::<Vec<_>>();
+        Ok(Response::new(tokio_stream::iter(stream)))
+    }
+
+    // This stream is built from a few stream adapters, which makes its
+    // exact type impossible to write out. Boxing it gives it a type that
+    // we can write.
+    type WatchStream = Pin<Box<dyn Stream<Item = Result<Reading, Status>> + Send>>;
+
+    async fn watch(&self, request: Request<SensorRequest>) -> Result<Response<Self::WatchStream>, Status> {
+        let sensor = request.into_inner().sensor;
+        if sensor.is_empty() {
+            return Err(Status::invalid_argument("the sensor can't be empty"));
+        }
+
+        // Every subscriber gets its own copy of every new reading. We
+        // only pass on the ones from the sensor that was asked for.
+        let stream = BroadcastStream::new(self.updates.subscribe()).filter_map(move |reading| match reading {
+            Ok(reading) if reading.sensor == sensor => Some(Ok(reading)),
+            Ok(_) => None,
+            // The client is reading too slowly, and missed some
+            // readings. tonic ends the stream at the first Err, so
+            // sending one would hang up on the client. Instead, we
+            // make a note of it, and carry on with the next reading.
+            Err(err) => {
+                eprintln!("A watcher of `{sensor}` fell behind: {err}");
+                None
+            }
+        });
+
+        // The stream ends when the client hangs up, since tonic drops it.
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn report(&self, request: Request<Streaming<Reading>>) -> Result<Response<Summary>, Status> {
+        let mut stream = request.into_inner();
+        let mut summary = Summary {
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            ..Summary::default()
+        };
+        let mut total = 0.0;
+
+        // Nothing is recorded until the whole stream has arrived and
+        // every reading is valid. Otherwise, a bad reading (or the
+        // client hanging up) part of the way through would leave the
+        // earlier readings recorded, even though the call failed.
+        let mut received = Vec::new();
+
+        // message() waits for the next reading, and returns None once
+        // the client has finished sending.
+        while let Some(reading) = stream.message().await? {
+            if received.len() == MAX_REPORT_READINGS {
+                return Err(Status::resource_exhausted(format!(
+                    "a report can't have more than {MAX_REPORT_READINGS} readings"
+                )));
+            }
+            if reading.sensor.is_empty() {
+                return Err(Status::invalid_argument("the sensor can't be empty"));
+            }
+            if !reading.celsius.is_finite() {
+                return Err(Status::invalid_argument(format!("invalid temperature {}", reading.celsius)));
+            }
+
+            summary.count += 1;
+            summary.min = summary.min.min(reading.celsius);
+            summary.max = summary.max.max(reading.celsius);
+            total += reading.celsius;
+
+            received.push(reading);
+        }
+
+        // Everything is recorded while holding the lock once, so nobody
+        // can see some of the readings without the rest.
+        {
+            let mut readings = self.readings.lock().unwrap();
+            for reading in &received {
+                readings.entry(reading.sensor.clone()).or_default().push(reading.clone());
+            }
+        }
+
+        for reading in received {
+            // This only fails when nobody is watching, which is fine.
+            let _ = self.updates.send(reading);
+        }
+
+        // With no readings, there's no min or max, so everything is 0.
+        if summary.count == 0 {
+            return Ok(Response::new(Summary::default()));
+        }
+
+        summary.mean = total / f64::from(summary.count);
+        Ok(Response::new(summary))
+    }
+}
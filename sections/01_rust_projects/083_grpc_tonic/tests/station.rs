@@ -0,0 +1,171 @@
+// These tests start a real server on a random port, and talk to it with
+// the generated client.
+
+use std::time::Duration;
+
+use project::pb::station_client::StationClient;
+use project::pb::station_server::StationServer;
+use project::pb::{Reading, SensorRequest};
+use project::station::MAX_REPORT_READINGS;
+use project::WeatherStation;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::{Channel, Server};
+use tonic::Code;
+
+/// Starts a server in the background, and returns a client for it.
+async fn start() -> StationClient<Channel> {
+    // Port 0 means "any free port". serve_with_incoming accepts
+    // connections from a listener that we made, instead of making its
+    // own, so that we can find out which port it got.
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    tokio::spawn(
+        Server::builder()
+            .add_service(StationServer::new(WeatherStation::new()))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+
+    StationClient::connect(format!("http://{address}")).await.unwrap()
+}
+
+fn reading(sensor: &str, celsius: f64, timestamp: i64) -> Reading {
+    Reading {
+        sensor: sensor.to_string(),
+        celsius,
+        timestamp,
+    }
+}
+
+fn sensor(sensor: &str) -> SensorRequest {
+    SensorRequest {
+        sensor: sensor.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn reports_and_summarizes() {
+    let mut client = start().await;
+
+    let readings = vec![reading("kitchen", 20.0, 1), reading("kitchen", 23.0, 2), reading("hall", 17.0, 3)];
+    let summary = client.report(tokio_stream::iter(readings)).await.unwrap().into_inner();
+
+    assert_eq!(summary.count, 3);
+    assert_eq!(summary.min, 17.0);
+    assert_eq!(summary.max, 23.0);
+    assert_eq!(summary.mean, 20.0);
+
+    let empty = client.report(tokio_stream::iter(Vec::new())).await.unwrap().into_inner();
+    assert_eq!(empty.count, 0);
+}
+
+#[tokio::test]
+async fn gets_the_latest_reading() {
+    let mut client = start().await;
+
+    let readings = vec![reading("kitchen", 20.0, 1), reading("kitchen", 21.0, 2)];
+    client.report(tokio_stream::iter(readings)).await.unwrap();
+
+    let latest = client.latest(sensor("kitchen")).await.unwrap().into_inner();
+    assert_eq!(latest, reading("kitchen", 21.0, 2));
+
+    let status = client.latest(sensor("attic")).await.unwrap_err();
+    assert_eq!(status.code(), Code::NotFound);
+}
+
+#[tokio::test]
+async fn streams_the_history() {
+    let mut client = start().await;
+
+    let readings = vec![reading("kitchen", 20.0, 1), reading("hall", 17.0, 2), reading("kitchen", 21.0, 3)];
+    client.report(tokio_stream::iter(readings)).await.unwrap();
+
+    let mut stream = client.history(sensor("kitchen")).await.unwrap().into_inner();
+    let mut history = Vec::new();
+    while let Some(reading) = stream.message().await.unwrap() {
+        history.push(reading);
+    }
+
+    assert_eq!(history, [reading("kitchen", 20.0, 1), reading("kitchen", 21.0, 3)]);
+}
+
+#[tokio::test]
+async fn watches_new_readings() {
+    let mut client = start().await;
+
+    let mut stream = client.watch(sensor("kitchen")).await.unwrap().into_inner();
+
+    // The client can be cloned, to make more calls over the same
+    // connection at the same time.
+    let mut reporter = client.clone();
+    let readings = vec![reading("hall", 17.0, 1), reading("kitchen", 22.0, 2)];
+    reporter.report(tokio_stream::iter(readings)).await.unwrap();
+
+    // Only the kitchen reading is sent. The timeout stops the test from
+    // hanging forever if it never arrives.
+    let next = tokio::time::timeout(Duration::from_secs(5), stream.message())
+        .await
+        .expect("no reading arrived")
+        .unwrap();
+    assert_eq!(next, Some(reading("kitchen", 22.0, 2)));
+}
+
+#[tokio::test]
+async fn rejects_bad_readings() {
+    let mut client = start().await;
+
+    for bad in [reading("", 20.0, 1), reading("kitchen", f64::NAN, 1)] {
+        let status = client.report(tokio_stream::iter(vec![bad])).await.unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+    }
+
+    let status = client.watch(sensor("")).await.unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn bad_readings_reject_the_whole_report() {
+    let mut client = start().await;
+
+    // The good reading comes first, but it isn't kept either.
+    let readings = vec![reading("kitchen", 20.0, 1), reading("kitchen", f64::NAN, 2)];
+    let status = client.report(tokio_stream::iter(readings)).await.unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+
+    let status = client.latest(sensor("kitchen")).await.unwrap_err();
+    assert_eq!(status.code(), Code::NotFound);
+}
+
+#[tokio::test]
+async fn limits_the_size_of_a_report() {
+    let mut client = start().await;
+
+    let readings = (0..=MAX_REPORT_READINGS as i64).map(|i| reading("kitchen", 20.0, i)).collect::<Vec<_>>();
+    let status = client.report(tokio_stream::iter(readings)).await.unwrap_err();
+    assert_eq!(status.code(), Code::ResourceExhausted);
+
+    let status = client.latest(sensor("kitchen")).await.unwrap_err();
+    assert_eq!(status.code(), Code::NotFound);
+}
+
+#[tokio::test]
+async fn keeps_watching_after_falling_behind() {
+    let mut client = start().await;
+    let mut stream = client.watch(sensor("kitchen")).await.unwrap().into_inner();
+
+    // The server sends these all at once, without waiting for the
+    // watcher to keep up, so it misses most of them.
+    let readings = (0..200).map(|i| reading("kitchen", 20.0, i)).collect::<Vec<_>>();
+    client.report(tokio_stream::iter(readings)).await.unwrap();
+
+    // The stream skips the ones it missed, instead of ending, and
+    // still gets the newest ones.
+    loop {
+        let next = tokio::time::timeout(Duration::from_secs(5), stream.message()).await.unwrap();
+        let next = next.unwrap().unwrap();
+        if next.timestamp == 199 {
+            break;
+        }
+    }
+}
@@ -0,0 +1,210 @@
+// The chat itself: users, messages, and a channel that announces new
+// messages. None of this knows about GraphQL. schema.rs is what exposes
+// it to clients.
+
+use std::sync::Mutex;
+
+use async_graphql::{ErrorExtensions, ID};
+use thiserror::Error;
+use tokio::sync::broadcast;
+
+/// The longest message that can be sent, in characters.
+pub const MAX_MESSAGE_LENGTH: usize = 500;
+
+/// How many new messages can be waiting for a slow subscriber before it
+/// starts missing some.
+const EVENT_BUFFER: usize = 64;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct User {
+    pub id: usize,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub id: usize,
+    pub author_id: usize,
+    pub text: String,
+}
+
+#[derive(Debug, Error)]
+pub enum ChatError {
+    #[error("no user with the ID `{0}`")]
+    UnknownUser(String),
+    #[error("names can't be empty")]
+    EmptyName,
+    #[error("the name `{0}` is already taken")]
+    NameTaken(String),
+    #[error("messages can't be empty")]
+    EmptyMessage,
+    #[error("messages can't be longer than {MAX_MESSAGE_LENGTH} characters")]
+    MessageTooLong,
+}
+
+impl ChatError {
+    /// A code that clients can check, instead of matching on the
+    /// message (which might change).
+    fn code(&self) -> &'static str {
+        match self {
+            ChatError::UnknownUser(_) => "NOT_FOUND",
+            ChatError::NameTaken(_) => "CONFLICT",
+            ChatError::EmptyName | ChatError::EmptyMessage | ChatError::MessageTooLong => "INVALID_INPUT",
+        }
+    }
+}
+
+// GraphQL errors can carry "extensions", which are extra fields next to
+// the message:
+//     {"message": "names can't be empty", "extensions": {"code": "INVALID_INPUT"}}
+impl ErrorExtensions for ChatError {
+    fn extend(&self) -> async_graphql::Error {
+        async_graphql::Error::new(self.to_string()).extend_with(|_, extensions| extensions.set("code", self.code()))
+    }
+}
+
+pub struct Chat {
+    users: Mutex<Vec<User>>,
+    messages: Mutex<Vec<Message>>,
+    /// Every new message is sent here, and each subscription listens
+    /// to it.
+    events: broadcast::Sender<Message>,
+}
+
+impl Chat {
+    pub fn new() -> Self {
+        Chat {
+            users: Mutex::new(Vec::new()),
+            messages: Mutex::new(Vec::new()),
+            events: broadcast::channel(EVENT_BUFFER).0,
+        }
+    }
+
+    pub fn users(&self) -> Vec<User> {
+        self.users.lock().unwrap().clone()
+    }
+
+    /// Finds a user. IDs come from clients as strings, so anything that
+    /// isn't a number just isn't found.
+    pub fn user(&self, id: &ID) -> Result<User, ChatError> {
+        let users = self.users.lock().unwrap();
+
+        id.parse::<usize>()
+            .ok()
+            .and_then(|id| users.get(id))
+            .cloned()
+            .ok_or_else(|| ChatError::UnknownUser(id.to_string()))
+    }
+
+    pub fn create_user(&self, name: &str) -> Result<User, ChatError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(ChatError::EmptyName);
+        }
+
+        let mut users = self.users.lock().unwrap();
+        if users.iter().any(|user| user.name.eq_ignore_ascii_case(name)) {
+            return Err(ChatError::NameTaken(name.to_string()));
+        }
+
+        // Users are never deleted, so their position is their ID.
+        let user = User {
+            id: users.len(),
+            name: name.to_string(),
+        };
+        users.push(user.clone());
+
+        Ok(user)
+    }
+
+    /// The last `count` messages, oldest first. `author` only includes
+    /// the messages that one user sent.
+    pub fn messages(&self, author: Option<usize>, count: usize) -> Vec<Message> {
+        let messages = self.messages.lock().unwrap();
+        let matching: Vec<&Message> = messages
+            .iter()
+            .filter(|message| author.is_none_or(|author| message.author_id == author))
+            .collect();
+
+        matching[matching.len().saturating_sub(count)..]
+            .iter()
+            .map(|&message| message.clone())
+            .collect()
+    }
+
+    pub fn send_message(&self, author: &ID, text: &str) -> Result<Message, ChatError> {
+        let author = self.user(author)?;
+
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(ChatError::EmptyMessage);
+        }
+        if text.chars().count() > MAX_MESSAGE_LENGTH {
+            return Err(ChatError::MessageTooLong);
+        }
+
+        let message = {
+            let mut messages = self.messages.lock().unwrap();
+            let message = Message {
+                id: messages.len(),
+                author_id: author.id,
+                text: text.to_string(),
+            };
+            messages.push(message.clone());
+            message
+        };
+
+        // This only fails when nobody is subscribed, which is fine.
+        let _ = self.events.send(message.clone());
+
+        Ok(message)
+    }
+
+    /// Listens for new messages.
+    pub fn subscribe(&self) -> broadcast::Receiver<Message> {
+        self.events.subscribe()
+    }
+}
+
+impl Default for Chat {
+    fn default() -> Self {
+        Chat::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn creates_users() {
+        let chat = Chat::new();
+
+        let ferris = chat.create_user(" Ferris ").unwrap();
+        assert_eq!(ferris.name, "Ferris");
+        assert_eq!(chat.user(&ID::from("0")).unwrap(), ferris);
+
+        assert!(matches!(chat.create_user("ferris"), Err(ChatError::NameTaken(_))));
+        assert!(matches!(chat.create_user(""), Err(ChatError::EmptyName)));
+        assert!(matches!(chat.user(&ID::from("nope")), Err(ChatError::UnknownUser(_))));
+    }
+
+    #[test]
+    fn sends_and_lists_messages() {
+        let chat = Chat::new();
+        let ferris = chat.create_user("Ferris").unwrap();
+        let corro = chat.create_user("Corro").unwrap();
+
+        for (author, text) in [(&ferris, "one"), (&corro, "two"), (&ferris, "three")] {
+            chat.send_message(&ID::from(author.id), text).unwrap();
+        }
+
+        let texts = |messages: Vec<Message>| messages.into_iter().map(|message| message.text).collect::<Vec<_>>();
+        assert_eq!(texts(chat.messages(None, 2)), ["two", "three"]);
+        assert_eq!(texts(chat.messages(Some(ferris.id), 10)), ["one", "three"]);
+
+        assert!(matches!(chat.send_message(&ID::from("0"), "  "), Err(ChatError::EmptyMessage)));
+        let long = "a".repeat(MAX_MESSAGE_LENGTH + 1);
+        assert!(matches!(chat.send_message(&ID::from("0"), &long), Err(ChatError::MessageTooLong)));
+    }
+}
@@ -0,0 +1,31 @@
+// A chat server with a GraphQL API, built with async-graphql and axum.
+//
+// * chat.rs stores the users and messages.
+// * schema.rs describes them in GraphQL.
+// * app() below serves the schema over HTTP and WebSockets.
+
+pub mod chat;
+pub mod schema;
+
+use async_graphql::http::GraphiQLSource;
+use async_graphql_axum::{GraphQL, GraphQLSubscription};
+use axum::response::Html;
+use axum::routing::get;
+use axum::Router;
+
+pub use chat::Chat;
+pub use schema::{build_schema, ChatSchema};
+
+/// Builds the router:
+/// * `GET /` is GraphiQL, a page for trying out queries in the browser.
+/// * `POST /` runs queries and mutations.
+/// * `/ws` runs subscriptions, over a WebSocket.
+pub fn app(schema: ChatSchema) -> Router {
+    Router::new()
+        .route("/", get(graphiql).post_service(GraphQL::new(schema.clone())))
+        .route_service("/ws", GraphQLSubscription::new(schema))
+}
+
+async fn graphiql() -> Html<String> {
+    Html(GraphiQLSource::build().endpoint("/").subscription_endpoint("/ws").finish())
+}
@@ -0,0 +1,31 @@
+// Start the server with `cargo run`, then open http://127.0.0.1:8000 to
+// try it out with GraphiQL. `cargo run -- --schema` prints the schema.
+
+use project::{app, build_schema, Chat};
+
+const ADDRESS: &str = "127.0.0.1:8000";
+
+#[tokio::main]
+async fn main() {
+    let schema = build_schema(Chat::new());
+
+    // SDL (the Schema Definition Language) is GraphQL's way of writing
+    // down every type and field in a schema.
+    if std::env::args().nth(1).as_deref() == Some("--schema") {
+        println!("{}", schema.sdl());
+        return;
+    }
+
+    let listener = match tokio::net::TcpListener::bind(ADDRESS).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            eprintln!("Could not listen on {ADDRESS}: {err}");
+            std::process::exit(1);
+        }
+    };
+
+    println!("GraphiQL is running on http://{ADDRESS}");
+    if let Err(err) = axum::serve(listener, app(schema)).await {
+        eprintln!("Server error: {err}");
+    }
+}
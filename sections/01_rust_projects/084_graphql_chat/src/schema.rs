@@ -0,0 +1,147 @@
+// The GraphQL schema: what clients can ask for, and how each field is
+// worked out.
+//
+// In GraphQL, every field is a function (a "resolver"). The #[Object]
+// macro turns each method into a field, and its doc comment into the
+// field's description, which tools like GraphiQL show to clients.
+
+use async_graphql::{Context, ErrorExtensions, Object, Result, Schema, Subscription, ID};
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
+
+use crate::chat::{Chat, Message, User};
+
+/// How many messages a list includes when the client doesn't say.
+const DEFAULT_COUNT: usize = 50;
+
+/// The most messages that one list can include.
+const MAX_COUNT: usize = 500;
+
+/// How deeply queries can nest. Users have messages, which have authors,
+/// who have messages... so without a limit, one query could nest them
+/// forever.
+const MAX_DEPTH: usize = 8;
+
+/// How much work one query can ask for. Every field costs 1, and a list
+/// of messages costs as much as everything asked for in it, times how
+/// many messages it can hold. Limiting the depth isn't enough on its
+/// own: `messages(last: 500) { author { messages(last: 500) { ... } } }`
+/// only nests a few levels, but multiplies out to millions of messages.
+const MAX_COMPLEXITY: usize = 10_000;
+
+pub type ChatSchema = Schema<QueryRoot, MutationRoot, SubscriptionRoot>;
+
+/// Builds the schema. The Chat is stored in the schema's "data", where
+/// every resolver can get at it through its Context.
+pub fn build_schema(chat: Chat) -> ChatSchema {
+    Schema::build(QueryRoot, MutationRoot, SubscriptionRoot)
+        .data(chat)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
+
+/// Gets the Chat out of a resolver's Context.
+fn chat<'a>(ctx: &Context<'a>) -> &'a Chat {
+    // build_schema always adds a Chat, so this can't fail.
+    ctx.data_unchecked::<Chat>()
+}
+
+fn message_count(last: Option<usize>) -> usize {
+    last.unwrap_or(DEFAULT_COUNT).min(MAX_COUNT)
+}
+
+#[Object]
+impl User {
+    async fn id(&self) -> ID {
+        ID::from(self.id)
+    }
+
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The messages that this user sent, oldest first.
+    #[graphql(complexity = "message_count(last) * child_complexity")]
+    async fn messages(&self, ctx: &Context<'_>, last: Option<usize>) -> Vec<Message> {
+        chat(ctx).messages(Some(self.id), message_count(last))
+    }
+}
+
+#[Object]
+impl Message {
+    async fn id(&self) -> ID {
+        ID::from(self.id)
+    }
+
+    async fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Who sent the message. This is only looked up if the query asks
+    /// for it.
+    async fn author(&self, ctx: &Context<'_>) -> Result<User> {
+        chat(ctx).user(&ID::from(self.author_id)).map_err(|err| err.extend())
+    }
+}
+
+// The root types are called Query, Mutation, and Subscription in the
+// schema, which is what GraphQL clients expect.
+pub struct QueryRoot;
+
+#[Object(name = "Query")]
+impl QueryRoot {
+    /// Every user.
+    async fn users(&self, ctx: &Context<'_>) -> Vec<User> {
+        chat(ctx).users()
+    }
+
+    /// One user, or an error if there's no user with this ID.
+    async fn user(&self, ctx: &Context<'_>, id: ID) -> Result<User> {
+        chat(ctx).user(&id).map_err(|err| err.extend())
+    }
+
+    /// The most recent messages, oldest first.
+    #[graphql(complexity = "message_count(last) * child_complexity")]
+    async fn messages(&self, ctx: &Context<'_>, last: Option<usize>) -> Vec<Message> {
+        chat(ctx).messages(None, message_count(last))
+    }
+}
+
+pub struct MutationRoot;
+
+#[Object(name = "Mutation")]
+impl MutationRoot {
+    /// Joins the chat. Names are unique (ignoring case).
+    async fn create_user(&self, ctx: &Context<'_>, name: String) -> Result<User> {
+        chat(ctx).create_user(&name).map_err(|err| err.extend())
+    }
+
+    /// Sends a message, which is passed on to every subscriber.
+    async fn send_message(&self, ctx: &Context<'_>, author_id: ID, text: String) -> Result<Message> {
+        chat(ctx).send_message(&author_id, &text).map_err(|err| err.extend())
+    }
+}
+
+pub struct SubscriptionRoot;
+
+#[Subscription(name = "Subscription")]
+impl SubscriptionRoot {
+    /// Every new message, as it's sent. `authorId` only includes the
+    /// messages from one user.
+    async fn messages(&self, ctx: &Context<'_>, author_id: Option<ID>) -> impl Stream<Item = Message> {
+        let receiver = chat(ctx).subscribe();
+
+        BroadcastStream::new(receiver).filter_map(move |message| {
+            // An Err means that this subscriber fell behind and missed
+            // some messages. There's nothing we can do about that now,
+            // so we carry on with the next one.
+            let message = message.ok()?;
+
+            match &author_id {
+                Some(author_id) if *author_id != ID::from(message.author_id) => None,
+                _ => Some(message),
+            }
+        })
+    }
+}
@@ -0,0 +1,154 @@
+// These tests run queries straight against the schema, without a
+// server. It's the same thing the server does for each request.
+
+use std::time::Duration;
+
+use async_graphql::{value, Request, Value, Variables};
+use project::{build_schema, Chat, ChatSchema};
+use tokio_stream::StreamExt;
+
+/// Runs a query, and returns its data. Panics if there were any errors.
+async fn run(schema: &ChatSchema, query: &str) -> Value {
+    let response = schema.execute(query).await;
+    assert!(response.errors.is_empty(), "{:?}", response.errors);
+    response.data
+}
+
+/// Builds a schema with two users, and a message from each.
+async fn schema() -> ChatSchema {
+    let schema = build_schema(Chat::new());
+    run(
+        &schema,
+        r#"mutation {
+            ferris: createUser(name: "Ferris") { id }
+            corro: createUser(name: "Corro") { id }
+        }"#,
+    )
+    .await;
+    run(
+        &schema,
+        r#"mutation {
+            a: sendMessage(authorId: "0", text: "Hello!") { id }
+            b: sendMessage(authorId: "1", text: "Hi, Ferris.") { id }
+        }"#,
+    )
+    .await;
+    schema
+}
+
+#[tokio::test]
+async fn queries_users_and_messages() {
+    let schema = schema().await;
+
+    let data = run(&schema, "{ users { id name } }").await;
+    assert_eq!(
+        data,
+        value!({ "users": [{ "id": "0", "name": "Ferris" }, { "id": "1", "name": "Corro" }] })
+    );
+
+    // One query can follow the links between types: messages, their
+    // authors, and their authors' messages.
+    let data = run(&schema, "{ messages(last: 1) { text author { name messages { text } } } }").await;
+    assert_eq!(
+        data,
+        value!({
+            "messages": [{
+                "text": "Hi, Ferris.",
+                "author": { "name": "Corro", "messages": [{ "text": "Hi, Ferris." }] }
+            }]
+        })
+    );
+}
+
+#[tokio::test]
+async fn uses_variables() {
+    let schema = schema().await;
+
+    let request = Request::new("query ($id: ID!) { user(id: $id) { name } }")
+        .variables(Variables::from_json(serde_json::json!({ "id": "1" })));
+    let response = schema.execute(request).await;
+
+    assert_eq!(response.data, value!({ "user": { "name": "Corro" } }));
+}
+
+#[tokio::test]
+async fn reports_errors_with_codes() {
+    let schema = schema().await;
+
+    let response = schema.execute(r#"{ user(id: "42") { name } }"#).await;
+    assert_eq!(response.errors.len(), 1);
+    assert_eq!(response.errors[0].message, "no user with the ID `42`");
+
+    let extensions = response.errors[0].extensions.as_ref().unwrap();
+    assert_eq!(extensions.get("code"), Some(&value!("NOT_FOUND")));
+
+    for mutation in [
+        r#"mutation { createUser(name: "ferris") { id } }"#,
+        r#"mutation { createUser(name: "  ") { id } }"#,
+        r#"mutation { sendMessage(authorId: "0", text: "") { id } }"#,
+        r#"mutation { sendMessage(authorId: "9", text: "Hi") { id } }"#,
+    ] {
+        assert_eq!(schema.execute(mutation).await.errors.len(), 1, "{mutation}");
+    }
+}
+
+#[tokio::test]
+async fn limits_query_depth() {
+    let schema = schema().await;
+
+    let response = schema
+        .execute("{ users { messages { author { messages { author { messages { author { messages { text } } } } } } } } }")
+        .await;
+
+    assert!(!response.errors.is_empty());
+}
+
+#[tokio::test]
+async fn limits_query_complexity() {
+    let schema = schema().await;
+
+    // Only three levels deep, but it could return 500 * 500 * 500
+    // messages, and aliases could ask for that many times over.
+    let response = schema
+        .execute("{ messages(last: 500) { author { messages(last: 500) { author { messages(last: 500) { text } } } } } }")
+        .await;
+    assert_eq!(response.errors.len(), 1);
+    assert_eq!(response.errors[0].message, "Query is too complex.");
+
+    // A big list on its own is still fine.
+    run(&schema, "{ messages(last: 500) { id text author { name } } }").await;
+}
+
+#[tokio::test]
+async fn subscribes_to_new_messages() {
+    let schema = schema().await;
+
+    // Only Ferris's messages should come through.
+    let mut stream = schema.execute_stream(r#"subscription { messages(authorId: "0") { text author { name } } }"#);
+
+    // Nothing is sent until the stream is first polled, so the
+    // subscription has to start before the messages are sent. Running
+    // the mutations in another task lets us wait on the stream here.
+    let sender = schema.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        sender
+            .execute(
+                r#"mutation {
+                    a: sendMessage(authorId: "1", text: "Anyone here?") { id }
+                    b: sendMessage(authorId: "0", text: "Yes!") { id }
+                }"#,
+            )
+            .await;
+    });
+
+    let response = tokio::time::timeout(Duration::from_secs(5), stream.next())
+        .await
+        .expect("no message arrived")
+        .unwrap();
+
+    assert_eq!(
+        response.data,
+        value!({ "messages": { "text": "Yes!", "author": { "name": "Ferris" } } })
+    );
+}
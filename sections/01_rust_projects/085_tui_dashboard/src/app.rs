@@ -0,0 +1,313 @@
+// The dashboard's state, and how it changes.
+//
+// Nothing in here draws anything or touches the terminal. Key presses
+// and samples come in, and the state changes. That keeps it easy to
+// test, and ui.rs only has to look at the state to draw it.
+
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::system::{ProcessInfo, Sample};
+
+/// How many CPU samples to keep for the chart.
+pub const HISTORY_LENGTH: usize = 200;
+
+/// How many lines the log keeps before the oldest are dropped.
+pub const LOG_LENGTH: usize = 500;
+
+/// CPU usage (in percent) above which we add a warning to the log.
+pub const HIGH_CPU: f32 = 90.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    Processes,
+    Log,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortBy {
+    Cpu,
+    Memory,
+}
+
+pub struct App {
+    pub running: bool,
+    /// While paused, new samples are ignored, so the numbers stop
+    /// changing.
+    pub paused: bool,
+    /// The pane that the arrow keys control.
+    pub focus: Pane,
+    pub sort_by: SortBy,
+    pub latest: Sample,
+    /// CPU usage over time, oldest first, in whole percent.
+    pub cpu_history: VecDeque<u64>,
+    /// The position of the selected row in the process table.
+    pub selected: usize,
+    pub log: VecDeque<String>,
+    /// How many lines the log is scrolled up from the bottom. 0 means
+    /// that the newest lines are showing.
+    pub log_scroll: usize,
+    /// Whether the CPU was already high on the last sample, so that we
+    /// only log a warning when it first goes over, instead of every tick.
+    cpu_was_high: bool,
+}
+
+impl App {
+    pub fn new() -> Self {
+        let mut app = App {
+            running: true,
+            paused: false,
+            focus: Pane::Processes,
+            sort_by: SortBy::Cpu,
+            latest: Sample::default(),
+            cpu_history: VecDeque::with_capacity(HISTORY_LENGTH),
+            selected: 0,
+            log: VecDeque::new(),
+            log_scroll: 0,
+            cpu_was_high: false,
+        };
+        app.add_log("Started. Press ? for help.");
+        app
+    }
+
+    /// Takes in a new sample.
+    pub fn update(&mut self, mut sample: Sample) {
+        if self.paused {
+            return;
+        }
+
+        // A VecDeque can take items off the front cheaply, so the
+        // oldest sample is dropped once the history is full.
+        if self.cpu_history.len() == HISTORY_LENGTH {
+            self.cpu_history.pop_front();
+        }
+        self.cpu_history.push_back(sample.cpu.round() as u64);
+
+        let cpu_is_high = sample.cpu > HIGH_CPU;
+        if cpu_is_high && !self.cpu_was_high {
+            self.add_log(format!("CPU usage is high ({:.0}%)", sample.cpu));
+        }
+        self.cpu_was_high = cpu_is_high;
+
+        sort_processes(&mut sample.processes, self.sort_by);
+        self.latest = sample;
+
+        // Processes come and go, so the selection might now be past the
+        // end of the table.
+        self.selected = self.selected.min(self.latest.processes.len().saturating_sub(1));
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.running = false,
+            // In raw mode, Ctrl+C is just another key press, so we have
+            // to handle it ourselves.
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.running = false,
+            KeyCode::Tab => {
+                self.focus = match self.focus {
+                    Pane::Processes => Pane::Log,
+                    Pane::Log => Pane::Processes,
+                };
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.move_by(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_by(1),
+            KeyCode::PageUp => self.move_by(-10),
+            KeyCode::PageDown => self.move_by(10),
+            KeyCode::Home => self.move_by(isize::MIN),
+            KeyCode::End => self.move_by(isize::MAX),
+            KeyCode::Char('s') => {
+                self.sort_by = match self.sort_by {
+                    SortBy::Cpu => SortBy::Memory,
+                    SortBy::Memory => SortBy::Cpu,
+                };
+                sort_processes(&mut self.latest.processes, self.sort_by);
+                self.add_log(format!("Sorting by {}", self.sort_name()));
+            }
+            KeyCode::Char('p') => {
+                self.paused = !self.paused;
+                self.add_log(if self.paused { "Paused" } else { "Resumed" });
+            }
+            KeyCode::Char('?') => {
+                self.add_log("Keys: q quit, Tab switch pane, ↑/↓ move, PgUp/PgDn jump, s sort, p pause");
+            }
+            _ => {}
+        }
+    }
+
+    /// Moves the selection (or scrolls the log) by `amount` rows, and
+    /// stops at either end.
+    fn move_by(&mut self, amount: isize) {
+        match self.focus {
+            Pane::Processes => {
+                let last = self.latest.processes.len().saturating_sub(1);
+                self.selected = self.selected.saturating_add_signed(amount).min(last);
+            }
+            Pane::Log => {
+                // Up means further back, which is a bigger scroll.
+                // (isize::MIN can't be negated, so it becomes MAX.)
+                let amount = amount.checked_neg().unwrap_or(isize::MAX);
+                let last = self.log.len().saturating_sub(1);
+                self.log_scroll = self.log_scroll.saturating_add_signed(amount).min(last);
+            }
+        }
+    }
+
+    pub fn add_log(&mut self, line: impl Into<String>) {
+        if self.log.len() == LOG_LENGTH {
+            self.log.pop_front();
+        }
+        self.log.push_back(line.into());
+
+        // If the log is scrolled up, keep the same lines in view.
+        if self.log_scroll > 0 {
+            self.log_scroll = (self.log_scroll + 1).min(self.log.len() - 1);
+        }
+    }
+
+    pub fn selected_process(&self) -> Option<&ProcessInfo> {
+        self.latest.processes.get(self.selected)
+    }
+
+    pub fn sort_name(&self) -> &'static str {
+        match self.sort_by {
+            SortBy::Cpu => "CPU",
+            SortBy::Memory => "memory",
+        }
+    }
+}
+
+impl Default for App {
+    fn default() -> Self {
+        App::new()
+    }
+}
+
+/// Sorts processes with the busiest first.
+fn sort_processes(processes: &mut [ProcessInfo], sort_by: SortBy) {
+    match sort_by {
+        // f32 doesn't implement Ord (because of NaN), so total_cmp is
+        // used to compare them.
+        SortBy::Cpu => processes.sort_by(|a, b| b.cpu.total_cmp(&a.cpu)),
+        // Reverse flips the order, so the biggest comes first.
+        SortBy::Memory => processes.sort_by_key(|process| Reverse(process.memory)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::from(code)
+    }
+
+    fn process(pid: u32, cpu: f32, memory: u64) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            name: format!("process-{pid}"),
+            cpu,
+            memory,
+        }
+    }
+
+    fn sample(cpu: f32) -> Sample {
+        Sample {
+            cpu,
+            memory_used: 1,
+            memory_total: 2,
+            processes: vec![process(1, 5.0, 300), process(2, 50.0, 100), process(3, 20.0, 200)],
+        }
+    }
+
+    #[test]
+    fn keeps_a_bounded_history() {
+        let mut app = App::new();
+
+        for i in 0..HISTORY_LENGTH + 10 {
+            app.update(sample(i as f32 % 100.0));
+        }
+
+        assert_eq!(app.cpu_history.len(), HISTORY_LENGTH);
+        // The first 10 samples were dropped.
+        assert_eq!(app.cpu_history[0], 10);
+    }
+
+    #[test]
+    fn sorts_and_selects_processes() {
+        let mut app = App::new();
+        app.update(sample(10.0));
+
+        let pids = |app: &App| app.latest.processes.iter().map(|process| process.pid).collect::<Vec<_>>();
+        assert_eq!(pids(&app), [2, 3, 1]);
+
+        app.handle_key(key(KeyCode::Char('s')));
+        assert_eq!(pids(&app), [1, 3, 2]);
+
+        app.handle_key(key(KeyCode::Down));
+        assert_eq!(app.selected_process().unwrap().pid, 3);
+        app.handle_key(key(KeyCode::End));
+        assert_eq!(app.selected, 2);
+        app.handle_key(key(KeyCode::Down));
+        assert_eq!(app.selected, 2);
+        app.handle_key(key(KeyCode::Home));
+        assert_eq!(app.selected, 0);
+        app.handle_key(key(KeyCode::Up));
+        assert_eq!(app.selected, 0);
+    }
+
+    #[test]
+    fn scrolls_the_log() {
+        let mut app = App::new();
+        for i in 0..5 {
+            app.add_log(format!("line {i}"));
+        }
+
+        app.handle_key(key(KeyCode::Tab));
+        assert_eq!(app.focus, Pane::Log);
+
+        app.handle_key(key(KeyCode::Up));
+        app.handle_key(key(KeyCode::Up));
+        assert_eq!(app.log_scroll, 2);
+
+        // New lines don't move what's on screen while scrolled up.
+        app.add_log("new");
+        assert_eq!(app.log_scroll, 3);
+
+        app.handle_key(key(KeyCode::End));
+        assert_eq!(app.log_scroll, 0);
+        app.handle_key(key(KeyCode::Home));
+        assert_eq!(app.log_scroll, app.log.len() - 1);
+    }
+
+    #[test]
+    fn warns_once_about_high_cpu() {
+        let mut app = App::new();
+        let warnings = |app: &App| app.log.iter().filter(|line| line.contains("high")).count();
+
+        app.update(sample(95.0));
+        app.update(sample(99.0));
+        assert_eq!(warnings(&app), 1);
+
+        app.update(sample(10.0));
+        app.update(sample(95.0));
+        assert_eq!(warnings(&app), 2);
+    }
+
+    #[test]
+    fn pauses_and_quits() {
+        let mut app = App::new();
+
+        app.handle_key(key(KeyCode::Char('p')));
+        app.update(sample(10.0));
+        assert!(app.cpu_history.is_empty());
+
+        app.handle_key(key(KeyCode::Char('p')));
+        app.update(sample(10.0));
+        assert_eq!(app.cpu_history.len(), 1);
+
+        app.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        assert!(!app.running);
+    }
+}
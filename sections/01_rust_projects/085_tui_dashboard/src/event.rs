@@ -0,0 +1,72 @@
+// Turning key presses and the passing of time into one stream of events.
+//
+// Reading a key blocks until one is pressed, but the dashboard also
+// needs to update on its own every second. So, each of those runs on its
+// own thread, and both send their events down the same channel. The main
+// loop then just waits for the next event, whichever kind it is.
+
+use std::sync::mpsc::{self, Receiver, RecvError, Sender};
+use std::thread;
+use std::time::Duration;
+
+use ratatui::crossterm::event::{self, KeyEvent, KeyEventKind};
+
+#[derive(Debug)]
+pub enum Event {
+    /// A key was pressed.
+    Key(KeyEvent),
+    /// The terminal changed size, so everything should be redrawn.
+    Resize,
+    /// It's time to take a new sample.
+    Tick,
+}
+
+pub struct Events {
+    receiver: Receiver<Event>,
+}
+
+impl Events {
+    /// Starts sending events, with a Tick every `tick_rate`.
+    pub fn new(tick_rate: Duration) -> Self {
+        let (sender, receiver) = mpsc::channel();
+
+        let input = sender.clone();
+        thread::spawn(move || read_input(&input));
+        thread::spawn(move || tick(&sender, tick_rate));
+
+        Events { receiver }
+    }
+
+    /// Waits for the next event.
+    pub fn next(&self) -> Result<Event, RecvError> {
+        self.receiver.recv()
+    }
+}
+
+fn read_input(sender: &Sender<Event>) {
+    loop {
+        let event = match event::read() {
+            // Some terminals also report when keys are released. We
+            // only care about presses.
+            Ok(event::Event::Key(key)) if key.kind == KeyEventKind::Press => Event::Key(key),
+            Ok(event::Event::Resize(_, _)) => Event::Resize,
+            Ok(_) => continue,
+            Err(_) => break,
+        };
+
+        // Sending fails once the receiver is dropped, which means that
+        // the app is shutting down.
+        if sender.send(event).is_err() {
+            break;
+        }
+    }
+}
+
+fn tick(sender: &Sender<Event>, tick_rate: Duration) {
+    loop {
+        thread::sleep(tick_rate);
+        if sender.send(Event::Tick).is_err() {
+            break;
+        }
+    }
+}
@@ -0,0 +1,14 @@
+// A system monitor for the terminal, built with ratatui.
+//
+// * system.rs measures the CPU, memory, and processes with sysinfo.
+// * app.rs holds the dashboard's state, and handles key presses.
+// * event.rs turns key presses and timer ticks into one stream of events.
+// * ui.rs draws the state.
+// * main.rs sets up the terminal, and runs the event loop.
+
+pub mod app;
+pub mod event;
+pub mod system;
+pub mod ui;
+
+pub use app::App;
@@ -0,0 +1,53 @@
+// Run the dashboard with `cargo run`, and press q to quit.
+
+use std::io;
+use std::time::Duration;
+
+use project::event::{Event, Events};
+use project::system::Sampler;
+use project::{ui, App};
+use ratatui::DefaultTerminal;
+
+/// How often to take a new sample.
+const TICK_RATE: Duration = Duration::from_secs(1);
+
+fn main() -> io::Result<()> {
+    // init() switches the terminal into "raw mode" (so we get each key
+    // as it's pressed, instead of whole lines) and to a separate screen,
+    // so that whatever was in the terminal comes back when we're done.
+    let mut terminal = ratatui::init();
+
+    let result = run(&mut terminal);
+
+    // Put the terminal back to normal. This has to happen even if run()
+    // failed, or the user would be left with a broken terminal. (init()
+    // also sets up a panic hook that does this if we panic.)
+    ratatui::restore();
+
+    result
+}
+
+/// The event loop: draw, wait for an event, handle it, and repeat.
+fn run(terminal: &mut DefaultTerminal) -> io::Result<()> {
+    let mut app = App::new();
+    let mut sampler = Sampler::new();
+    let events = Events::new(TICK_RATE);
+
+    app.update(sampler.sample());
+
+    while app.running {
+        terminal.draw(|frame| ui::draw(frame, &app))?;
+
+        match events.next() {
+            Ok(Event::Key(key)) => app.handle_key(key),
+            Ok(Event::Tick) => app.update(sampler.sample()),
+            // The next draw will use the new size.
+            Ok(Event::Resize) => {}
+            // Both event threads have stopped, so nothing else will
+            // ever happen.
+            Err(_) => break,
+        }
+    }
+
+    Ok(())
+}
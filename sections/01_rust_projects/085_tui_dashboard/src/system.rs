@@ -0,0 +1,125 @@
+// Reading CPU, memory, and process information with sysinfo.
+
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, RefreshKind, System};
+
+/// One process, with just the information that we show.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub name: String,
+    /// The percentage of one CPU core that it's using. A process using
+    /// two whole cores is at 200%.
+    pub cpu: f32,
+    /// Memory used, in bytes.
+    pub memory: u64,
+}
+
+/// Everything that we measure on each tick.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Sample {
+    /// The average usage of all cores, from 0 to 100.
+    pub cpu: f32,
+    pub memory_used: u64,
+    pub memory_total: u64,
+    pub processes: Vec<ProcessInfo>,
+}
+
+pub struct Sampler {
+    system: System,
+}
+
+impl Sampler {
+    pub fn new() -> Self {
+        // Only load what we need. Refreshing everything (like every
+        // process's environment variables) would be much slower.
+        let refresh = RefreshKind::nothing().with_cpu(Default::default()).with_memory(Default::default());
+
+        Sampler {
+            system: System::new_with_specifics(refresh),
+        }
+    }
+
+    /// Measures everything again.
+    ///
+    /// CPU usage is worked out from how much the CPU did since the last
+    /// refresh, so the first sample always says 0%. The refreshes need to
+    /// be at least sysinfo::MINIMUM_CPU_UPDATE_INTERVAL apart to be
+    /// accurate.
+    pub fn sample(&mut self) -> Sample {
+        self.system.refresh_cpu_usage();
+        self.system.refresh_memory();
+        // `true` removes processes that have exited since last time.
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing().with_cpu().with_memory(),
+        );
+
+        let processes = self
+            .system
+            .processes()
+            .values()
+            .map(|process| ProcessInfo {
+                pid: process.pid().as_u32(),
+                // Process names aren't always valid UTF-8.
+                name: process.name().to_string_lossy().into_owned(),
+                cpu: process.cpu_usage(),
+                memory: process.memory(),
+            })
+            .collect();
+
+        Sample {
+            cpu: self.system.global_cpu_usage(),
+            memory_used: self.system.used_memory(),
+            memory_total: self.system.total_memory(),
+            processes,
+        }
+    }
+}
+
+impl Default for Sampler {
+    fn default() -> Self {
+        Sampler::new()
+    }
+}
+
+/// Formats a number of bytes to be easy to read, like "1.5 GiB".
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0 GiB");
+    }
+
+    #[test]
+    fn samples_this_computer() {
+        let sample = Sampler::new().sample();
+
+        assert!(sample.memory_total > 0);
+        assert!(sample.memory_used <= sample.memory_total);
+        // At the very least, this test is running.
+        assert!(sample.processes.iter().any(|process| process.pid == std::process::id()));
+    }
+}
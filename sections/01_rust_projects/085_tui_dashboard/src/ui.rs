@@ -0,0 +1,170 @@
+// Drawing the dashboard:
+//
+//     ┌ CPU ──────────────────┐┌ Memory ───────────────┐
+//     │██████░░░░░░░░ 42%     ││████████░░░░ 5.1 GiB   │
+//     └───────────────────────┘└───────────────────────┘
+//     ┌ CPU history ──────────────────────────────────┐
+//     │      ▂▃▅▇█▆▄▃▂▁▁▂▃▄                           │
+//     └───────────────────────────────────────────────┘
+//     ┌ Processes ───────────────┐┌ Log ──────────────┐
+//     │ PID  Name     CPU  Memory ││ Started.          │
+//     │ ...                      ││                   │
+//     └──────────────────────────┘└───────────────────┘
+//      q quit  Tab switch pane  ↑/↓ move  s sort  p pause
+//
+// ratatui redraws everything on every frame, from the App's state. There
+// is no "change this label" like in most GUI libraries: to change what's
+// on screen, change the state, and it'll be drawn that way next frame.
+
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Gauge, List, ListItem, Row, Sparkline, Table, TableState};
+use ratatui::Frame;
+
+use crate::app::{App, Pane};
+use crate::system::format_bytes;
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    // Split the screen into rows. Length is an exact height, and Min
+    // takes whatever space is left.
+    let [gauges, history, bottom, help] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Length(8),
+        Constraint::Min(5),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let [cpu, memory] = Layout::horizontal([Constraint::Percentage(50); 2]).areas(gauges);
+    let [processes, log] = Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(bottom);
+
+    draw_gauges(frame, app, cpu, memory);
+    draw_history(frame, app, history);
+    draw_processes(frame, app, processes);
+    draw_log(frame, app, log);
+
+    let status = if app.paused { " PAUSED " } else { "" };
+    frame.render_widget(
+        Line::from(vec![
+            status.black().on_yellow(),
+            " q quit  Tab switch pane  ↑/↓ move  s sort  p pause  ? help".dark_gray(),
+        ]),
+        help,
+    );
+}
+
+fn draw_gauges(frame: &mut Frame, app: &App, cpu: Rect, memory: Rect) {
+    let sample = &app.latest;
+
+    let cpu_gauge = Gauge::default()
+        .block(Block::bordered().title(" CPU "))
+        .gauge_style(Style::new().fg(usage_color(f64::from(sample.cpu))))
+        .ratio((f64::from(sample.cpu) / 100.0).clamp(0.0, 1.0))
+        .label(format!("{:.0}%", sample.cpu));
+    frame.render_widget(cpu_gauge, cpu);
+
+    // Avoid dividing by 0 before the first sample.
+    let memory_ratio = if sample.memory_total == 0 {
+        0.0
+    } else {
+        sample.memory_used as f64 / sample.memory_total as f64
+    };
+    let memory_gauge = Gauge::default()
+        .block(Block::bordered().title(" Memory "))
+        .gauge_style(Style::new().fg(usage_color(memory_ratio * 100.0)))
+        .ratio(memory_ratio.clamp(0.0, 1.0))
+        .label(format!(
+            "{} / {}",
+            format_bytes(sample.memory_used),
+            format_bytes(sample.memory_total)
+        ));
+    frame.render_widget(memory_gauge, memory);
+}
+
+fn draw_history(frame: &mut Frame, app: &App, area: Rect) {
+    // A sparkline draws one bar per column, starting from the left. We
+    // want the newest samples, so we only pass in as many as will fit.
+    let width = area.width.saturating_sub(2) as usize;
+    let skip = app.cpu_history.len().saturating_sub(width);
+    let data: Vec<u64> = app.cpu_history.iter().skip(skip).copied().collect();
+
+    let sparkline = Sparkline::default()
+        .block(Block::bordered().title(" CPU history "))
+        .data(&data)
+        .max(100)
+        .style(Style::new().fg(Color::Cyan));
+    frame.render_widget(sparkline, area);
+}
+
+fn draw_processes(frame: &mut Frame, app: &App, area: Rect) {
+    let header = Row::new(["PID", "Name", "CPU", "Memory"]).style(Style::new().add_modifier(Modifier::BOLD));
+
+    let rows = app.latest.processes.iter().map(|process| {
+        Row::new([
+            process.pid.to_string(),
+            process.name.clone(),
+            format!("{:.1}%", process.cpu),
+            format_bytes(process.memory),
+        ])
+    });
+
+    let widths = [
+        Constraint::Length(8),
+        Constraint::Min(10),
+        Constraint::Length(8),
+        Constraint::Length(10),
+    ];
+
+    let title = format!(" Processes ({}, by {}) ", app.latest.processes.len(), app.sort_name());
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(pane_block(title, app.focus == Pane::Processes))
+        .row_highlight_style(Style::new().black().on_cyan())
+        .highlight_symbol("> ");
+
+    // The TableState says which row is selected. The table scrolls
+    // itself so that the selected row is always on screen.
+    let mut state = TableState::default().with_selected(Some(app.selected));
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn draw_log(frame: &mut Frame, app: &App, area: Rect) {
+    // Show the lines that end `log_scroll` lines before the newest one.
+    let height = area.height.saturating_sub(2) as usize;
+    let end = app.log.len() - app.log_scroll.min(app.log.len());
+    let start = end.saturating_sub(height);
+
+    let items: Vec<ListItem> = app
+        .log
+        .range(start..end)
+        .map(|line| ListItem::new(line.as_str()))
+        .collect();
+
+    let title = if app.log_scroll > 0 {
+        format!(" Log (↑{}) ", app.log_scroll)
+    } else {
+        " Log ".to_string()
+    };
+    frame.render_widget(List::new(items).block(pane_block(title, app.focus == Pane::Log)), area);
+}
+
+/// A bordered block, with a highlighted border if it has focus.
+fn pane_block(title: String, focused: bool) -> Block<'static> {
+    let style = if focused {
+        Style::new().fg(Color::Yellow)
+    } else {
+        Style::new()
+    };
+
+    Block::bordered().title(title).border_style(style)
+}
+
+/// Green, yellow, or red, depending on how high a percentage is.
+fn usage_color(percent: f64) -> Color {
+    match percent {
+        p if p >= 90.0 => Color::Red,
+        p if p >= 60.0 => Color::Yellow,
+        _ => Color::Green,
+    }
+}
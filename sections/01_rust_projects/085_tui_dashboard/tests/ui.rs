@@ -0,0 +1,87 @@
+// ratatui's TestBackend draws into a buffer instead of a real terminal,
+// so we can check what would be on screen.
+
+use project::system::{ProcessInfo, Sample};
+use project::{ui, App};
+use ratatui::backend::TestBackend;
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Terminal;
+
+/// Draws the app, and returns the screen as text, one line per row.
+fn render(app: &App, width: u16, height: u16) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(|frame| ui::draw(frame, app)).unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let mut screen = String::new();
+    for y in 0..buffer.area.height {
+        for x in 0..buffer.area.width {
+            screen.push_str(buffer[(x, y)].symbol());
+        }
+        screen.push('\n');
+    }
+    screen
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.update(Sample {
+        cpu: 42.0,
+        memory_used: 2 * 1024 * 1024 * 1024,
+        memory_total: 8 * 1024 * 1024 * 1024,
+        processes: vec![
+            ProcessInfo {
+                pid: 7,
+                name: "ferris-server".to_string(),
+                cpu: 12.5,
+                memory: 50 * 1024 * 1024,
+            },
+            ProcessInfo {
+                pid: 9,
+                name: "crab-shell".to_string(),
+                cpu: 0.5,
+                memory: 1024,
+            },
+        ],
+    });
+    app
+}
+
+#[test]
+fn draws_every_pane() {
+    let screen = render(&app(), 100, 30);
+
+    for text in [
+        "CPU",
+        "42%",
+        "2.0 GiB / 8.0 GiB",
+        "CPU history",
+        "Processes (2, by CPU)",
+        "ferris-server",
+        "12.5%",
+        "50.0 MiB",
+        "Log",
+        "Started.",
+        "q quit",
+    ] {
+        assert!(screen.contains(text), "`{text}` is missing from:\n{screen}");
+    }
+}
+
+#[test]
+fn shows_the_selection_and_pause() {
+    let mut app = app();
+    app.handle_key(KeyEvent::from(KeyCode::Down));
+    app.handle_key(KeyEvent::from(KeyCode::Char('p')));
+
+    let screen = render(&app, 100, 30);
+
+    assert!(screen.contains("> 9"), "{screen}");
+    assert!(screen.contains("PAUSED"), "{screen}");
+}
+
+#[test]
+fn fits_in_a_small_terminal() {
+    // This shouldn't panic, even though not everything fits.
+    render(&app(), 20, 10);
+}
@@ -0,0 +1,402 @@
+// The rules of snake.
+//
+// This module doesn't know about the terminal, the keyboard, or time.
+// The game only moves when step() is called, and only turns when turn()
+// is called. That makes every rule easy to test: set up a game, call
+// step() a few times, and check what happened.
+
+use std::collections::VecDeque;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// How many turns can be waiting to happen. Pressing ↑ then → quickly
+/// should turn twice (once per step), instead of the second press
+/// replacing the first.
+const MAX_QUEUED_TURNS: usize = 2;
+
+/// How long the snake is at the start.
+const START_LENGTH: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+}
+
+/// A cell on the board. (0, 0) is the top left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Point {
+    pub x: u16,
+    pub y: u16,
+}
+
+impl Point {
+    pub fn new(x: u16, y: u16) -> Self {
+        Point { x, y }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    Playing,
+    /// The snake hit a wall or itself.
+    GameOver,
+    /// The snake filled the whole board, so there's nowhere left to put
+    /// food.
+    Won,
+}
+
+pub struct Game {
+    width: u16,
+    height: u16,
+    /// The cells that the snake is in, with the head at the front.
+    snake: VecDeque<Point>,
+    direction: Direction,
+    turns: VecDeque<Direction>,
+    food: Point,
+    /// How many more steps the tail should stay where it is, so that the
+    /// snake gets longer.
+    growing: usize,
+    score: u32,
+    state: State,
+    rng: StdRng,
+}
+
+impl Game {
+    /// Starts a game with a random seed.
+    pub fn new(width: u16, height: u16) -> Self {
+        Game::with_rng(width, height, StdRng::from_entropy())
+    }
+
+    /// Starts a game where the food always appears in the same places,
+    /// which is what tests need.
+    pub fn with_seed(width: u16, height: u16, seed: u64) -> Self {
+        Game::with_rng(width, height, StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(width: u16, height: u16, rng: StdRng) -> Self {
+        assert!(
+            width as usize > START_LENGTH && height > 0,
+            "the board is too small for the snake"
+        );
+
+        let mut game = Game {
+            width,
+            height,
+            snake: VecDeque::new(),
+            direction: Direction::Right,
+            turns: VecDeque::new(),
+            food: Point::new(0, 0),
+            growing: 0,
+            score: 0,
+            state: State::Playing,
+            rng,
+        };
+        game.restart();
+        game
+    }
+
+    /// Puts everything back to how it was at the start, keeping the
+    /// board size.
+    pub fn restart(&mut self) {
+        // The snake starts in the middle, facing right, with its tail
+        // to the left of its head.
+        let middle = Point::new(self.width / 2, self.height / 2);
+        self.snake = (0..START_LENGTH as u16)
+            .map(|i| Point::new(middle.x - i, middle.y))
+            .collect();
+
+        self.direction = Direction::Right;
+        self.turns.clear();
+        self.growing = 0;
+        self.score = 0;
+        self.state = State::Playing;
+        self.place_food();
+    }
+
+    /// Asks the snake to turn on its next step.
+    ///
+    /// Turning back on itself isn't allowed (the snake would instantly
+    /// run into its own neck), and neither is "turning" the way it's
+    /// already going.
+    pub fn turn(&mut self, direction: Direction) {
+        // Compare against the last queued turn, since that's the way the
+        // snake will be going by the time this turn happens.
+        let current = self.turns.back().copied().unwrap_or(self.direction);
+
+        if direction != current && direction != current.opposite() && self.turns.len() < MAX_QUEUED_TURNS {
+            self.turns.push_back(direction);
+        }
+    }
+
+    /// Moves the snake one cell.
+    pub fn step(&mut self) {
+        if self.state != State::Playing {
+            return;
+        }
+
+        if let Some(direction) = self.turns.pop_front() {
+            self.direction = direction;
+        }
+
+        let Some(head) = self.next_head() else {
+            self.state = State::GameOver;
+            return;
+        };
+
+        // The tail moves out of its cell at the same time as the head
+        // moves, so the head can move into the cell where the tail is
+        // now. But not if the snake is growing, since then the tail
+        // stays put.
+        if self.growing > 0 {
+            self.growing -= 1;
+        } else {
+            self.snake.pop_back();
+        }
+
+        if self.snake.contains(&head) {
+            self.state = State::GameOver;
+            return;
+        }
+
+        self.snake.push_front(head);
+
+        if head == self.food {
+            self.score += 1;
+            self.growing += 1;
+            self.place_food();
+        }
+    }
+
+    /// Where the head will be after the next step, or None if that's
+    /// off the board.
+    fn next_head(&self) -> Option<Point> {
+        let head = self.head();
+
+        // checked_sub fails below 0, and the filters catch going past
+        // the right and bottom edges.
+        match self.direction {
+            Direction::Up => head.y.checked_sub(1).map(|y| Point::new(head.x, y)),
+            Direction::Down => Some(Point::new(head.x, head.y + 1)).filter(|point| point.y < self.height),
+            Direction::Left => head.x.checked_sub(1).map(|x| Point::new(x, head.y)),
+            Direction::Right => Some(Point::new(head.x + 1, head.y)).filter(|point| point.x < self.width),
+        }
+    }
+
+    /// Puts the food in a random empty cell.
+    fn place_food(&mut self) {
+        // Once the snake has finished growing, it'll fill the board.
+        let cells = usize::from(self.width) * usize::from(self.height);
+        if self.snake.len() + self.growing >= cells {
+            self.state = State::Won;
+            return;
+        }
+
+        // Picking random cells until we find an empty one gets very slow
+        // once the snake fills most of the board. Picking from a list of
+        // the empty cells always takes the same time. (There's always at
+        // least one, because of the check above.)
+        let empty: Vec<Point> = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| Point::new(x, y)))
+            .filter(|point| !self.snake.contains(point))
+            .collect();
+
+        self.food = empty[self.rng.gen_range(0..empty.len())];
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    pub fn head(&self) -> Point {
+        self.snake[0]
+    }
+
+    /// The cells that the snake is in, head first.
+    pub fn snake(&self) -> impl Iterator<Item = Point> + '_ {
+        self.snake.iter().copied()
+    }
+
+    pub fn food(&self) -> Point {
+        self.food
+    }
+
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Puts the food somewhere specific, so tests can steer into it.
+    fn put_food(game: &mut Game, x: u16, y: u16) {
+        game.food = Point::new(x, y);
+    }
+
+    #[test]
+    fn starts_in_the_middle() {
+        let game = Game::with_seed(10, 5, 1);
+
+        assert_eq!(game.snake().collect::<Vec<_>>(), [Point::new(5, 2), Point::new(4, 2), Point::new(3, 2)]);
+        assert_eq!(game.state(), State::Playing);
+        assert!(!game.snake().any(|point| point == game.food()));
+    }
+
+    #[test]
+    fn moves_and_turns() {
+        let mut game = Game::with_seed(10, 5, 1);
+        put_food(&mut game, 0, 0);
+
+        game.step();
+        assert_eq!(game.head(), Point::new(6, 2));
+
+        game.turn(Direction::Up);
+        game.step();
+        assert_eq!(game.head(), Point::new(6, 1));
+        assert_eq!(game.snake().count(), START_LENGTH);
+    }
+
+    #[test]
+    fn cant_reverse() {
+        let mut game = Game::with_seed(10, 5, 1);
+        put_food(&mut game, 0, 0);
+
+        game.turn(Direction::Left);
+        game.step();
+
+        assert_eq!(game.head(), Point::new(6, 2));
+        assert_eq!(game.state(), State::Playing);
+    }
+
+    #[test]
+    fn queues_quick_turns() {
+        let mut game = Game::with_seed(10, 5, 1);
+        put_food(&mut game, 0, 0);
+
+        // A quick U-turn: up, then left, before the next step.
+        game.turn(Direction::Up);
+        game.turn(Direction::Left);
+        game.step();
+        game.step();
+
+        assert_eq!(game.head(), Point::new(4, 1));
+    }
+
+    #[test]
+    fn eats_and_grows() {
+        let mut game = Game::with_seed(10, 5, 1);
+        put_food(&mut game, 6, 2);
+
+        game.step();
+        assert_eq!(game.score(), 1);
+        assert_ne!(game.food(), Point::new(6, 2));
+
+        // The tail stays put for one step, so the snake is one longer.
+        game.step();
+        assert_eq!(game.snake().count(), START_LENGTH + 1);
+    }
+
+    #[test]
+    fn hits_walls() {
+        let mut game = Game::with_seed(10, 5, 1);
+        put_food(&mut game, 0, 0);
+
+        for _ in 0..4 {
+            game.step();
+        }
+        assert_eq!(game.head(), Point::new(9, 2));
+        assert_eq!(game.state(), State::Playing);
+
+        game.step();
+        assert_eq!(game.state(), State::GameOver);
+
+        // Nothing moves after the game is over.
+        game.step();
+        assert_eq!(game.head(), Point::new(9, 2));
+    }
+
+    #[test]
+    fn hits_itself() {
+        let mut game = Game::with_seed(10, 5, 1);
+        put_food(&mut game, 0, 0);
+        // Make the snake 5 long, so it's long enough to run into itself.
+        game.growing = 2;
+        game.step();
+        game.step();
+
+        for direction in [Direction::Down, Direction::Left, Direction::Up] {
+            game.turn(direction);
+            game.step();
+        }
+
+        assert_eq!(game.state(), State::GameOver);
+    }
+
+    #[test]
+    fn can_chase_its_tail() {
+        let mut game = Game::with_seed(10, 5, 1);
+        put_food(&mut game, 0, 0);
+        // A snake of 4 going round in a square moves into the cell that
+        // its tail is just leaving, every step.
+        game.growing = 1;
+        game.step();
+
+        for direction in [Direction::Down, Direction::Left, Direction::Up, Direction::Right] {
+            game.turn(direction);
+            game.step();
+        }
+
+        assert_eq!(game.state(), State::Playing);
+    }
+
+    #[test]
+    fn wins_when_the_board_is_full() {
+        // On a 4x1 board, the snake (3 long) only has one cell free.
+        let mut game = Game::with_seed(4, 1, 1);
+        assert_eq!(game.food(), Point::new(3, 0));
+
+        game.step();
+        assert_eq!(game.state(), State::Won);
+    }
+
+    #[test]
+    fn restarts() {
+        let mut game = Game::with_seed(10, 5, 1);
+        put_food(&mut game, 6, 2);
+        game.step();
+        for _ in 0..10 {
+            game.step();
+        }
+        assert_eq!(game.state(), State::GameOver);
+
+        game.restart();
+
+        assert_eq!(game.state(), State::Playing);
+        assert_eq!(game.score(), 0);
+        assert_eq!(game.head(), Point::new(5, 2));
+    }
+}
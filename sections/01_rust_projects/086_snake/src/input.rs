@@ -0,0 +1,82 @@
+// Reading the keyboard on its own thread.
+//
+// Waiting for a key press blocks until one arrives, but the snake has to
+// keep moving whether or not keys are pressed. So, a separate thread
+// waits for keys, and sends what they mean to the game loop down a
+// channel. The game loop checks the channel without ever waiting on it.
+
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+use crate::game::Direction;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Input {
+    Turn(Direction),
+    Pause,
+    Restart,
+    Quit,
+}
+
+/// Starts the input thread, and returns the channel that it sends to.
+pub fn spawn() -> Receiver<Input> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || read_keys(&sender));
+    receiver
+}
+
+fn read_keys(sender: &Sender<Input>) {
+    loop {
+        let input = match event::read() {
+            // Some terminals also send an event when a key is released,
+            // which would make every key count twice.
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => map_key(key),
+            Ok(_) => None,
+            Err(_) => break,
+        };
+
+        // Sending fails once the game loop has stopped.
+        if let Some(input) = input {
+            if sender.send(input).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+/// Works out what a key press means, if anything. Arrow keys, WASD, and
+/// HJKL (like in vim) all steer.
+pub fn map_key(key: KeyEvent) -> Option<Input> {
+    let input = match key.code {
+        KeyCode::Up | KeyCode::Char('w' | 'k') => Input::Turn(Direction::Up),
+        KeyCode::Down | KeyCode::Char('s' | 'j') => Input::Turn(Direction::Down),
+        KeyCode::Left | KeyCode::Char('a' | 'h') => Input::Turn(Direction::Left),
+        KeyCode::Right | KeyCode::Char('d' | 'l') => Input::Turn(Direction::Right),
+        // In raw mode, Ctrl+C doesn't stop the program, so we have to
+        // handle it ourselves.
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Input::Quit,
+        KeyCode::Char('q') | KeyCode::Esc => Input::Quit,
+        KeyCode::Char('p') | KeyCode::Char(' ') => Input::Pause,
+        KeyCode::Char('r') | KeyCode::Enter => Input::Restart,
+        _ => return None,
+    };
+
+    Some(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_keys() {
+        assert_eq!(map_key(KeyEvent::from(KeyCode::Up)), Some(Input::Turn(Direction::Up)));
+        assert_eq!(map_key(KeyEvent::from(KeyCode::Char('a'))), Some(Input::Turn(Direction::Left)));
+        assert_eq!(map_key(KeyEvent::from(KeyCode::Char('l'))), Some(Input::Turn(Direction::Right)));
+        assert_eq!(map_key(KeyEvent::from(KeyCode::Char(' '))), Some(Input::Pause));
+        assert_eq!(map_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)), Some(Input::Quit));
+        assert_eq!(map_key(KeyEvent::from(KeyCode::Char('c'))), None);
+    }
+}
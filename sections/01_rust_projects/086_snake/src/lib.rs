@@ -0,0 +1,12 @@
+// Snake, in the terminal.
+//
+// * game.rs has the rules, and nothing else.
+// * input.rs reads the keyboard on its own thread.
+// * render.rs draws the game.
+// * main.rs runs the game loop.
+
+pub mod game;
+pub mod input;
+pub mod render;
+
+pub use game::{Direction, Game, State};
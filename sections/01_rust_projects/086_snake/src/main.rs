@@ -0,0 +1,100 @@
+// Play with `cargo run`. Steer with the arrow keys, and press q to quit.
+
+use std::io::{self, Stdout};
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::{Duration, Instant};
+
+use crossterm::cursor::{Hide, Show};
+use crossterm::execute;
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use project::input::{self, Input};
+use project::{render, Game, State};
+
+const WIDTH: u16 = 20;
+const HEIGHT: u16 = 15;
+
+/// How long each step takes. The snake moves one cell per step.
+const STEP: Duration = Duration::from_millis(130);
+
+fn main() -> io::Result<()> {
+    // The board needs 2 characters per cell, plus the border, and 2
+    // lines for the score.
+    let (columns, rows) = terminal::size()?;
+    if columns < WIDTH * 2 + 2 || rows < HEIGHT + 4 {
+        eprintln!("The terminal is too small. It needs to be at least {}x{}.", WIDTH * 2 + 2, HEIGHT + 4);
+        std::process::exit(1);
+    }
+
+    let mut out = io::stdout();
+    let _guard = TerminalGuard::new(&mut out)?;
+
+    run(&mut out)
+}
+
+fn run(out: &mut Stdout) -> io::Result<()> {
+    let inputs = input::spawn();
+    let mut game = Game::new(WIDTH, HEIGHT);
+    let mut paused = false;
+    let mut best = 0;
+
+    // A fixed timestep: the game steps exactly once every STEP, no
+    // matter how often the loop runs, or how long drawing takes.
+    let mut next_step = Instant::now() + STEP;
+
+    loop {
+        render::draw(out, &render::frame(&game, paused, best))?;
+
+        // Wait for a key, but only until the next step is due.
+        // recv_timeout returns as soon as a key arrives, so the game
+        // reacts straight away, instead of at the next step.
+        let timeout = next_step.saturating_duration_since(Instant::now());
+        match inputs.recv_timeout(timeout) {
+            Ok(Input::Quit) | Err(RecvTimeoutError::Disconnected) => return Ok(()),
+            Ok(Input::Turn(direction)) if !paused => game.turn(direction),
+            Ok(Input::Pause) if game.state() == State::Playing => paused = !paused,
+            Ok(Input::Restart) if game.state() != State::Playing => {
+                game.restart();
+                next_step = Instant::now() + STEP;
+            }
+            Ok(_) | Err(RecvTimeoutError::Timeout) => {}
+        }
+
+        // Take every step that's due. Usually that's none (a key woke
+        // us up early) or one, but if the computer was busy for a
+        // moment, we catch up, so the snake's speed stays the same.
+        let now = Instant::now();
+        while next_step <= now {
+            if !paused {
+                game.step();
+            }
+            next_step += STEP;
+        }
+
+        best = best.max(game.score());
+    }
+}
+
+/// Sets the terminal up for the game, and puts it back when dropped.
+///
+/// Since Drop runs even when the game returns an error or panics, the
+/// terminal is always put back to normal.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn new(out: &mut Stdout) -> io::Result<Self> {
+        // Raw mode sends each key to us as soon as it's pressed, and
+        // stops the terminal from printing it. The alternate screen is
+        // a blank screen that goes away when we leave it, bringing back
+        // whatever was in the terminal before.
+        terminal::enable_raw_mode()?;
+        execute!(out, EnterAlternateScreen, Hide)?;
+        Ok(TerminalGuard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
@@ -0,0 +1,140 @@
+// Drawing the game in the terminal.
+//
+// frame() builds the picture as lines of text, and draw() puts them on
+// screen. Keeping those apart means that frame() can be tested without a
+// terminal.
+
+use std::io::{self, Write};
+
+use crossterm::cursor::MoveTo;
+use crossterm::style::{Color, Print, ResetColor, SetForegroundColor};
+use crossterm::terminal::{Clear, ClearType};
+use crossterm::QueueableCommand;
+
+use crate::game::{Game, Point, State};
+
+// Terminal characters are about twice as tall as they are wide, so each
+// cell is two characters wide, to make it roughly square.
+const HEAD: &str = "▓▓";
+const BODY: &str = "██";
+const FOOD: &str = "<>";
+const EMPTY: &str = "  ";
+
+/// The color of each piece of the board.
+const PIECES: [(&str, Color); 3] = [(HEAD, Color::Green), (BODY, Color::DarkGreen), (FOOD, Color::Red)];
+
+/// Builds the picture of the game: the board with a border around it,
+/// and a status line under it.
+pub fn frame(game: &Game, paused: bool, best: u32) -> Vec<String> {
+    let width = usize::from(game.width()) * 2;
+    let mut lines = vec![format!("┌{}┐", "─".repeat(width))];
+
+    let snake: Vec<Point> = game.snake().collect();
+    for y in 0..game.height() {
+        let mut line = String::from("│");
+        for x in 0..game.width() {
+            let point = Point::new(x, y);
+            line.push_str(if point == game.head() {
+                HEAD
+            } else if snake.contains(&point) {
+                BODY
+            } else if point == game.food() && game.state() == State::Playing {
+                FOOD
+            } else {
+                EMPTY
+            });
+        }
+        line.push('│');
+        lines.push(line);
+    }
+
+    lines.push(format!("└{}┘", "─".repeat(width)));
+    lines.push(format!(" Score: {}   Best: {}", game.score(), best.max(game.score())));
+    lines.push(
+        match game.state() {
+            State::Playing if paused => " Paused. Press p to carry on.",
+            State::Playing => " Arrow keys to steer, p to pause, q to quit.",
+            State::GameOver => " Game over! Press r to play again, or q to quit.",
+            State::Won => " You filled the board! Press r to play again, or q to quit.",
+        }
+        .to_string(),
+    );
+
+    lines
+}
+
+/// Draws the lines at the top left of the terminal.
+pub fn draw(out: &mut impl Write, lines: &[String]) -> io::Result<()> {
+    // queue() saves up each command, and flush() sends them all at
+    // once, which stops the screen from flickering.
+    for (y, line) in lines.iter().enumerate() {
+        out.queue(MoveTo(0, y as u16))?;
+
+        // Color each piece of the board by what it is.
+        let mut rest = line.as_str();
+        while !rest.is_empty() {
+            let (text, color) = match PIECES.iter().find(|(piece, _)| rest.starts_with(piece)) {
+                Some(&(piece, color)) => (piece, color),
+                // Everything up to the next piece is printed as it is.
+                None => {
+                    let end = PIECES
+                        .iter()
+                        .filter_map(|(piece, _)| rest.find(piece))
+                        .min()
+                        .unwrap_or(rest.len());
+                    (&rest[..end], Color::Reset)
+                }
+            };
+
+            out.queue(SetForegroundColor(color))?.queue(Print(text))?;
+            rest = &rest[text.len()..];
+        }
+
+        // Clear whatever was left on this line from the last frame.
+        out.queue(ResetColor)?.queue(Clear(ClearType::UntilNewLine))?;
+    }
+
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draws_the_board() {
+        let game = Game::with_seed(5, 3, 1);
+        let lines = frame(&game, false, 7);
+
+        assert_eq!(lines.len(), 3 + 4);
+        assert_eq!(lines[0], "┌──────────┐");
+        assert!(lines[2].starts_with("│████▓▓"), "{}", lines[2]);
+        assert_eq!(lines[4], "└──────────┘");
+        assert_eq!(lines[5], " Score: 0   Best: 7");
+
+        // The food is somewhere on the board.
+        assert_eq!(lines.iter().filter(|line| line.contains(FOOD)).count(), 1);
+    }
+
+    #[test]
+    fn shows_the_state() {
+        let mut game = Game::with_seed(5, 3, 1);
+        assert!(frame(&game, true, 0)[6].contains("Paused"));
+
+        while game.state() == State::Playing {
+            game.step();
+        }
+        assert!(frame(&game, false, 0)[6].contains("Game over"));
+    }
+
+    #[test]
+    fn draws_with_colors() {
+        let mut out = Vec::new();
+        draw(&mut out, &frame(&Game::with_seed(5, 3, 1), false, 0)).unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("Score: 0"));
+        // The escape code that sets the color to red (for the food).
+        assert!(out.contains("\x1b[38;5;9m"));
+    }
+}
@@ -0,0 +1,310 @@
+// The window, and everything in it.
+//
+// egui is an "immediate mode" GUI library. There are no button or slider
+// objects that stick around. Instead, update() is called every frame,
+// and it describes the whole window from scratch: "there's a slider
+// here, for this number". egui draws it, and if the user dragged it,
+// changes the number. All of the state lives in our own structs, and
+// the UI is just a function of it.
+
+use std::time::Duration;
+
+use eframe::egui::{self, Color32, ColorImage, Pos2, Rect, Sense, TextureHandle, TextureOptions, Vec2};
+use serde::{Deserialize, Serialize};
+
+use crate::fractal::View;
+use crate::worker::Worker;
+
+/// Everything that's saved when the app closes, and loaded when it
+/// starts.
+#[derive(Default, Serialize, Deserialize)]
+// If a field is missing (because it was added after the file was
+// saved), use its default instead of failing to load.
+#[serde(default)]
+pub struct Settings {
+    pub view: View,
+    pub bookmarks: Vec<Bookmark>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub name: String,
+    pub view: View,
+}
+
+/// The image on screen, and the view it was rendered for.
+struct Shown {
+    texture: TextureHandle,
+    view: View,
+    /// The image's size, in points (egui's units, which might not be
+    /// the same as pixels).
+    size: Vec2,
+}
+
+pub struct FractalViewer {
+    settings: Settings,
+    worker: Worker,
+    shown: Option<Shown>,
+    /// The view and size (in pixels) of the last render we asked for, so
+    /// that we only ask again when one of them changes.
+    requested: Option<(View, [usize; 2])>,
+    rendering: bool,
+    last_took: Option<Duration>,
+    /// What's typed into the bookmark name box. Even the text in a text
+    /// box is our state, not egui's.
+    bookmark_name: String,
+}
+
+impl FractalViewer {
+    pub fn new(cc: &eframe::CreationContext) -> Self {
+        // Load the settings from last time, if there are any.
+        let settings = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, eframe::APP_KEY))
+            .unwrap_or_default();
+
+        // egui only redraws when something happens, like the mouse
+        // moving. Finishing a render doesn't count, so the worker has to
+        // ask for a redraw, or the image wouldn't show up until the
+        // mouse moved.
+        let ctx = cc.egui_ctx.clone();
+        let worker = Worker::spawn(move || ctx.request_repaint());
+
+        FractalViewer {
+            settings,
+            worker,
+            shown: None,
+            requested: None,
+            rendering: false,
+            last_took: None,
+            bookmark_name: String::new(),
+        }
+    }
+
+    fn receive_image(&mut self, ctx: &egui::Context) {
+        let Some(rendered) = self.worker.try_recv() else {
+            return;
+        };
+
+        let image = ColorImage::from_rgba_unmultiplied([rendered.width, rendered.height], &rendered.pixels);
+        // Uploading the image to the GPU gives us a texture to draw.
+        // Dropping the old TextureHandle frees the old one.
+        let texture = ctx.load_texture("fractal", image, TextureOptions::LINEAR);
+
+        let pixels_per_point = ctx.pixels_per_point();
+        self.shown = Some(Shown {
+            texture,
+            // The image was rendered with one pixel per pixel, but the
+            // rest of the app works in points.
+            view: View {
+                scale: rendered.view.scale * f64::from(pixels_per_point),
+                ..rendered.view
+            },
+            size: Vec2::new(rendered.width as f32, rendered.height as f32) / pixels_per_point,
+        });
+        self.rendering = !self.worker.is_latest(rendered.id);
+        self.last_took = Some(rendered.took);
+    }
+
+    fn controls(&mut self, ui: &mut egui::Ui) {
+        let view = &mut self.settings.view;
+
+        ui.heading("Fractal Viewer");
+        ui.label("Drag to move, scroll to zoom, and double-click to zoom in.");
+        ui.separator();
+
+        ui.add(
+            egui::Slider::new(&mut view.max_iterations, 50..=10_000)
+                .logarithmic(true)
+                .text("Iterations"),
+        );
+        ui.label(format!("Center: {:.10}, {:.10}", view.center_x, view.center_y));
+        ui.label(format!("Zoom: {:.1}x", View::default().scale / view.scale));
+
+        ui.horizontal(|ui| {
+            if ui.button("Reset").clicked() {
+                *view = View::default();
+            }
+            if ui.button("Zoom in").clicked() {
+                view.scale /= 2.0;
+            }
+            if ui.button("Zoom out").clicked() {
+                view.scale *= 2.0;
+            }
+        });
+
+        ui.horizontal(|ui| {
+            if self.rendering {
+                ui.spinner();
+                ui.label("Rendering...");
+            } else if let Some(took) = self.last_took {
+                ui.label(format!("Rendered in {} ms", took.as_millis()));
+            }
+        });
+
+        ui.separator();
+        ui.heading("Bookmarks");
+
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.bookmark_name);
+            if ui.button("Add").clicked() {
+                let name = match self.bookmark_name.trim() {
+                    "" => format!("Bookmark {}", self.settings.bookmarks.len() + 1),
+                    name => name.to_string(),
+                };
+                self.settings.bookmarks.push(Bookmark {
+                    name,
+                    view: self.settings.view,
+                });
+                self.bookmark_name.clear();
+            }
+        });
+
+        // We can't remove a bookmark while looping over them, so remember
+        // which one to remove, and do it afterwards.
+        let mut remove = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (i, bookmark) in self.settings.bookmarks.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    if ui.button("x").on_hover_text("Delete").clicked() {
+                        remove = Some(i);
+                    }
+                    if ui.link(&bookmark.name).clicked() {
+                        self.settings.view = bookmark.view;
+                    }
+                });
+            }
+        });
+        if let Some(i) = remove {
+            self.settings.bookmarks.remove(i);
+        }
+    }
+
+    fn fractal(&mut self, ui: &mut egui::Ui) {
+        // Take up all of the space that's left, and listen for drags (and
+        // clicks, which come along with them).
+        let (response, painter) = ui.allocate_painter(ui.available_size(), Sense::drag());
+        let rect = response.rect;
+        let view = &mut self.settings.view;
+
+        if response.dragged() {
+            // Dragging right moves the picture right, which means the
+            // view moves left.
+            let delta = response.drag_delta();
+            view.pan(-f64::from(delta.x), -f64::from(delta.y));
+        }
+
+        if let Some(pointer) = response.hover_pos() {
+            let offset = pointer - rect.min;
+            let (x, y) = (f64::from(offset.x), f64::from(offset.y));
+            let (width, height) = (rect.width() as usize, rect.height() as usize);
+
+            // Scrolling up zooms in. exp() turns the scroll distance into
+            // a factor, so that scrolling up and then back down by the
+            // same amount ends up where it started.
+            let scroll = ui.input(|input| input.smooth_scroll_delta.y);
+            if scroll != 0.0 {
+                view.zoom_at((-f64::from(scroll) * 0.003).exp(), x, y, width, height);
+            }
+            if response.double_clicked() {
+                view.zoom_at(0.5, x, y, width, height);
+            }
+        }
+
+        // Ask for a new image if the view or the window's size changed.
+        // Rendering at the screen's real pixel size keeps it sharp on
+        // high DPI screens.
+        let pixels_per_point = ui.ctx().pixels_per_point();
+        let size = [
+            (rect.width() * pixels_per_point) as usize,
+            (rect.height() * pixels_per_point) as usize,
+        ];
+        if size[0] > 0 && size[1] > 0 && self.requested != Some((*view, size)) {
+            let pixel_view = View {
+                scale: view.scale / f64::from(pixels_per_point),
+                ..*view
+            };
+            self.worker.request(pixel_view, size[0], size[1]);
+            self.requested = Some((*view, size));
+            self.rendering = true;
+        }
+
+        // Until the new image is ready, show the old one, moved and
+        // scaled to where it belongs. That way, dragging and zooming feel
+        // instant, even if the image is a bit blurry for a moment.
+        painter.rect_filled(rect, 0.0, Color32::BLACK);
+        if let Some(shown) = &self.shown {
+            let uv = Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0));
+            painter.image(shown.texture.id(), placement(&shown.view, shown.size, view, rect), uv, Color32::WHITE);
+        }
+    }
+}
+
+impl eframe::App for FractalViewer {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.receive_image(ctx);
+
+        egui::SidePanel::left("controls")
+            .default_width(260.0)
+            .show(ctx, |ui| self.controls(ui));
+
+        // No margin around the fractal, so it fills the rest of the
+        // window.
+        egui::CentralPanel::default()
+            .frame(egui::Frame::none())
+            .show(ctx, |ui| self.fractal(ui));
+    }
+
+    /// Called when the app closes (and every 30 seconds while it's
+    /// open), to save the settings.
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, eframe::APP_KEY, &self.settings);
+    }
+}
+
+/// Where an image of `image_view`, `image_size` points big, should be
+/// drawn on screen, when `rect` is showing `view`.
+pub fn placement(image_view: &View, image_size: Vec2, view: &View, rect: Rect) -> Rect {
+    // How far the image's center is from the screen's center, in points.
+    let offset = Vec2::new(
+        ((image_view.center_x - view.center_x) / view.scale) as f32,
+        // y goes up in maths, but down on screen.
+        ((view.center_y - image_view.center_y) / view.scale) as f32,
+    );
+    // If the view is zoomed in twice as far as the image, the image
+    // needs to be twice as big.
+    let size = image_size * (image_view.scale / view.scale) as f32;
+
+    Rect::from_center_size(rect.center() + offset, size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn places_old_images() {
+        let rect = Rect::from_min_size(Pos2::ZERO, Vec2::new(100.0, 100.0));
+        let image_view = View::default();
+
+        // The same view fills the screen.
+        assert_eq!(placement(&image_view, rect.size(), &image_view, rect), rect);
+
+        // Moving the view right by 10 points moves the image left.
+        let mut view = image_view;
+        view.pan(10.0, 0.0);
+        assert_eq!(
+            placement(&image_view, rect.size(), &view, rect),
+            rect.translate(Vec2::new(-10.0, 0.0))
+        );
+
+        // Zooming in twice as far makes the image twice as big, around
+        // the center.
+        let mut view = image_view;
+        view.zoom_at(0.5, 50.0, 50.0, 100, 100);
+        assert_eq!(
+            placement(&image_view, rect.size(), &view, rect),
+            Rect::from_center_size(rect.center(), Vec2::new(200.0, 200.0))
+        );
+    }
+}
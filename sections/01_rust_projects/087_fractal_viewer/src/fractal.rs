@@ -0,0 +1,193 @@
+// The Mandelbrot set, and which part of it we're looking at.
+//
+// Nothing in here knows about egui or threads. render() takes a View and
+// a size, and returns the pixels, which makes it easy to test, and easy
+// to run on another thread.
+
+use serde::{Deserialize, Serialize};
+
+/// Which part of the fractal is on screen.
+///
+/// This is what gets saved when the app closes, so it derives Serialize
+/// and Deserialize.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct View {
+    /// The point in the middle of the screen.
+    pub center_x: f64,
+    pub center_y: f64,
+    /// How far apart pixels are. Smaller is more zoomed in.
+    pub scale: f64,
+    /// How many times to iterate before deciding that a point is in the
+    /// set. Zooming in further needs more of these to show detail.
+    pub max_iterations: u32,
+}
+
+impl Default for View {
+    /// The whole set, in a window about 800 pixels wide.
+    fn default() -> Self {
+        View {
+            center_x: -0.5,
+            center_y: 0.0,
+            scale: 3.5 / 800.0,
+            max_iterations: 200,
+        }
+    }
+}
+
+impl View {
+    /// Works out which point a pixel shows, for an image of the given
+    /// size. (0, 0) is the top left pixel.
+    pub fn pixel_to_point(&self, x: f64, y: f64, width: usize, height: usize) -> (f64, f64) {
+        // The middle of the image is at the center, and y goes up in
+        // maths, but down on screen, so it's flipped.
+        let point_x = self.center_x + (x - width as f64 / 2.0) * self.scale;
+        let point_y = self.center_y - (y - height as f64 / 2.0) * self.scale;
+        (point_x, point_y)
+    }
+
+    /// Moves the view by some number of pixels.
+    pub fn pan(&mut self, dx: f64, dy: f64) {
+        self.center_x += dx * self.scale;
+        self.center_y -= dy * self.scale;
+    }
+
+    /// Zooms in (factor < 1) or out (factor > 1), keeping the point
+    /// under the pixel (x, y) in the same place on screen.
+    pub fn zoom_at(&mut self, factor: f64, x: f64, y: f64, width: usize, height: usize) {
+        let (before_x, before_y) = self.pixel_to_point(x, y, width, height);
+        self.scale *= factor;
+        let (after_x, after_y) = self.pixel_to_point(x, y, width, height);
+
+        // Move the view so that the point ends up back under the pixel.
+        self.center_x += before_x - after_x;
+        self.center_y += before_y - after_y;
+    }
+}
+
+/// How many iterations it takes for `c` to escape, or None if it never
+/// does (so it's in the set).
+///
+/// The result is a smooth (fractional) count, so that colors blend
+/// instead of forming bands.
+pub fn escape_time(c_x: f64, c_y: f64, max_iterations: u32) -> Option<f64> {
+    // z = z² + c, written out with real numbers. If |z| ever goes above
+    // 2, it's sure to escape to infinity, but we wait for a bigger limit
+    // so the smoothing below works well.
+    let (mut z_x, mut z_y): (f64, f64) = (0.0, 0.0);
+
+    for i in 0..max_iterations {
+        let (x2, y2) = (z_x * z_x, z_y * z_y);
+        if x2 + y2 > 256.0 {
+            // How far past the limit z went tells us how far between
+            // this iteration and the last one it "really" escaped.
+            let log_z = (x2 + y2).ln() / 2.0;
+            let nu = (log_z / 2f64.ln()).ln() / 2f64.ln();
+            return Some(f64::from(i) + 1.0 - nu);
+        }
+
+        z_y = 2.0 * z_x * z_y + c_y;
+        z_x = x2 - y2 + c_x;
+    }
+
+    None
+}
+
+/// Turns an escape time into a color, as [red, green, blue].
+pub fn color(escape: Option<f64>) -> [u8; 3] {
+    let Some(escape) = escape else {
+        return [0, 0, 0];
+    };
+
+    // Go round a gradient of colors once every 64 iterations, so there
+    // are still plenty of colors when zoomed in a long way.
+    const GRADIENT: [[f64; 3]; 5] = [
+        [0.0, 7.0, 100.0],
+        [32.0, 107.0, 203.0],
+        [237.0, 255.0, 255.0],
+        [255.0, 170.0, 0.0],
+        [0.0, 2.0, 0.0],
+    ];
+    let position = (escape / 64.0).fract() * GRADIENT.len() as f64;
+    let from = GRADIENT[position as usize % GRADIENT.len()];
+    let to = GRADIENT[(position as usize + 1) % GRADIENT.len()];
+    let t = position.fract();
+
+    // Blend between the two colors on either side.
+    std::array::from_fn(|channel| (from[channel] + (to[channel] - from[channel]) * t) as u8)
+}
+
+/// Renders the view into an image of the given size, as RGBA bytes (4
+/// per pixel, row by row, starting from the top left).
+///
+/// `cancelled` is checked after every row. Once it returns true, this
+/// gives up and returns None, so that a render nobody wants anymore
+/// doesn't hold up the next one.
+pub fn render(view: &View, width: usize, height: usize, cancelled: impl Fn() -> bool) -> Option<Vec<u8>> {
+    let mut pixels = Vec::with_capacity(width * height * 4);
+
+    for y in 0..height {
+        if cancelled() {
+            return None;
+        }
+
+        for x in 0..width {
+            let (c_x, c_y) = view.pixel_to_point(x as f64, y as f64, width, height);
+            let [r, g, b] = color(escape_time(c_x, c_y, view.max_iterations));
+            pixels.extend_from_slice(&[r, g, b, 255]);
+        }
+    }
+
+    Some(pixels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_in_and_out_of_the_set() {
+        // 0 and -1 stay small forever.
+        assert_eq!(escape_time(0.0, 0.0, 100), None);
+        assert_eq!(escape_time(-1.0, 0.0, 100), None);
+
+        // 1 goes 0, 1, 2, 5, 26, ... and escapes quickly.
+        let escape = escape_time(1.0, 0.0, 100).unwrap();
+        assert!(escape > 0.0 && escape < 10.0, "{escape}");
+    }
+
+    #[test]
+    fn maps_pixels_to_points() {
+        let view = View {
+            center_x: 1.0,
+            center_y: 2.0,
+            scale: 0.5,
+            max_iterations: 10,
+        };
+
+        assert_eq!(view.pixel_to_point(5.0, 5.0, 10, 10), (1.0, 2.0));
+        // Up and to the left of the middle.
+        assert_eq!(view.pixel_to_point(0.0, 0.0, 10, 10), (-1.5, 4.5));
+    }
+
+    #[test]
+    fn zooming_keeps_the_point_under_the_cursor() {
+        let mut view = View::default();
+        let before = view.pixel_to_point(100.0, 50.0, 800, 600);
+
+        view.zoom_at(0.5, 100.0, 50.0, 800, 600);
+
+        let after = view.pixel_to_point(100.0, 50.0, 800, 600);
+        assert!((before.0 - after.0).abs() < 1e-12 && (before.1 - after.1).abs() < 1e-12);
+        assert_eq!(view.scale, View::default().scale / 2.0);
+    }
+
+    #[test]
+    fn renders_and_cancels() {
+        let view = View::default();
+
+        let pixels = render(&view, 8, 6, || false).unwrap();
+        assert_eq!(pixels.len(), 8 * 6 * 4);
+
+        assert_eq!(render(&view, 8, 6, || true), None);
+    }
+}
@@ -0,0 +1,13 @@
+// A desktop app for exploring the Mandelbrot set, built with egui.
+//
+// * fractal.rs works out the colors, and which part of the set is on
+//   screen.
+// * worker.rs renders images on a background thread.
+// * app.rs draws the window, handles the mouse, and saves the settings.
+// * main.rs opens the window.
+
+pub mod app;
+pub mod fractal;
+pub mod worker;
+
+pub use app::FractalViewer;
@@ -0,0 +1,21 @@
+// Open the viewer with `cargo run --release`. (Without --release,
+// rendering is a lot slower.)
+
+use eframe::egui;
+use project::FractalViewer;
+
+fn main() -> eframe::Result {
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_title("Fractal Viewer")
+            .with_inner_size([1100.0, 650.0]),
+        ..Default::default()
+    };
+
+    // The name is also used to decide where the settings are saved.
+    eframe::run_native(
+        "Fractal Viewer",
+        options,
+        Box::new(|cc| Ok(Box::new(FractalViewer::new(cc)))),
+    )
+}
@@ -0,0 +1,120 @@
+// Rendering on a background thread.
+//
+// egui redraws the whole window every frame, and a frame has to be
+// finished in a few milliseconds to feel smooth. Rendering a fractal can
+// take much longer than that, so it happens on a separate thread
+// instead. The UI sends jobs down one channel, and checks another
+// channel for finished images each frame, without ever waiting on it.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::fractal::{self, View};
+
+struct Job {
+    id: u64,
+    view: View,
+    width: usize,
+    height: usize,
+}
+
+/// A finished image, and what it's an image of.
+pub struct Rendered {
+    pub id: u64,
+    pub view: View,
+    pub width: usize,
+    pub height: usize,
+    /// RGBA bytes, 4 per pixel.
+    pub pixels: Vec<u8>,
+    pub took: Duration,
+}
+
+pub struct Worker {
+    jobs: Sender<Job>,
+    results: Receiver<Rendered>,
+    /// The ID of the newest job. The thread checks this while it's
+    /// rendering, and gives up on a job once there's a newer one.
+    latest: Arc<AtomicU64>,
+}
+
+impl Worker {
+    /// Starts the thread. `on_done` is called (on the worker thread)
+    /// after each image is finished.
+    pub fn spawn(on_done: impl Fn() + Send + 'static) -> Self {
+        let (jobs, job_receiver) = mpsc::channel();
+        let (result_sender, results) = mpsc::channel();
+        let latest = Arc::new(AtomicU64::new(0));
+
+        let thread_latest = Arc::clone(&latest);
+        thread::spawn(move || run(&job_receiver, &result_sender, &thread_latest, on_done));
+
+        Worker { jobs, results, latest }
+    }
+
+    /// Asks for a view to be rendered, and returns the job's ID.
+    ///
+    /// Any jobs that haven't finished yet are cancelled, since only the
+    /// newest image is wanted.
+    pub fn request(&self, view: View, width: usize, height: usize) -> u64 {
+        // fetch_add returns the old value, so add 1 to get the new ID.
+        let id = self.latest.fetch_add(1, Ordering::Relaxed) + 1;
+
+        // Sending only fails if the thread has stopped, which only
+        // happens when the Worker is being dropped.
+        let _ = self.jobs.send(Job {
+            id,
+            view,
+            width,
+            height,
+        });
+
+        id
+    }
+
+    /// Returns the newest finished image, if there are any. Never waits.
+    pub fn try_recv(&self) -> Option<Rendered> {
+        // If a few images finished since the last check, skip to the
+        // newest one.
+        self.results.try_iter().last()
+    }
+
+    /// Whether the image for `id` is still the one we want.
+    pub fn is_latest(&self, id: u64) -> bool {
+        self.latest.load(Ordering::Relaxed) == id
+    }
+}
+
+fn run(jobs: &Receiver<Job>, results: &Sender<Rendered>, latest: &AtomicU64, on_done: impl Fn()) {
+    // recv fails once the Worker (and so the job Sender) is dropped,
+    // which stops the thread.
+    while let Ok(mut job) = jobs.recv() {
+        // If more jobs came in while we were busy, only the newest one
+        // matters.
+        while let Ok(newer) = jobs.try_recv() {
+            job = newer;
+        }
+
+        let start = Instant::now();
+        let cancelled = || latest.load(Ordering::Relaxed) != job.id;
+        let Some(pixels) = fractal::render(&job.view, job.width, job.height, cancelled) else {
+            continue;
+        };
+
+        let rendered = Rendered {
+            id: job.id,
+            view: job.view,
+            width: job.width,
+            height: job.height,
+            pixels,
+            took: start.elapsed(),
+        };
+        if results.send(rendered).is_err() {
+            break;
+        }
+
+        on_done();
+    }
+}
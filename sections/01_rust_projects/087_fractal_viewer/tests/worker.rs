@@ -0,0 +1,56 @@
+// The worker runs on a real thread, so these tests wait for it with a
+// channel (standing in for egui's request_repaint) instead of a window.
+
+use std::sync::mpsc;
+use std::time::Duration;
+
+use project::fractal::View;
+use project::worker::{Rendered, Worker};
+
+/// Starts a worker, and returns a channel that gets a message every
+/// time it finishes an image.
+fn worker() -> (Worker, mpsc::Receiver<()>) {
+    let (done, finished) = mpsc::channel();
+    let worker = Worker::spawn(move || {
+        let _ = done.send(());
+    });
+    (worker, finished)
+}
+
+fn wait(worker: &Worker, finished: &mpsc::Receiver<()>) -> Rendered {
+    finished.recv_timeout(Duration::from_secs(10)).expect("the render never finished");
+    worker.try_recv().expect("the render wasn't sent")
+}
+
+#[test]
+fn renders_in_the_background() {
+    let (worker, finished) = worker();
+    assert!(worker.try_recv().is_none());
+
+    let id = worker.request(View::default(), 40, 30);
+    let rendered = wait(&worker, &finished);
+
+    assert_eq!(rendered.id, id);
+    assert!(worker.is_latest(id));
+    assert_eq!((rendered.width, rendered.height), (40, 30));
+    assert_eq!(rendered.pixels.len(), 40 * 30 * 4);
+}
+
+#[test]
+fn only_the_newest_request_matters() {
+    let (worker, finished) = worker();
+
+    // A big, slow render, and then a small one straight after. The big
+    // one is cancelled (or skipped), so the small one is all we get.
+    let slow = View {
+        max_iterations: 100_000,
+        ..View::default()
+    };
+    let first = worker.request(slow, 2000, 2000);
+    let second = worker.request(View::default(), 10, 10);
+    assert!(!worker.is_latest(first));
+
+    let rendered = wait(&worker, &finished);
+    assert_eq!(rendered.id, second);
+    assert!(finished.recv_timeout(Duration::from_millis(200)).is_err());
+}
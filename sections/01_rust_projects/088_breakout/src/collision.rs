@@ -0,0 +1,130 @@
+// The maths for the ball hitting things.
+//
+// These are plain functions of positions and sizes, with no entities or
+// systems involved, so they're easy to test on their own. The
+// check_collisions system uses them on every collider.
+
+use bevy::math::Vec2;
+
+/// The side of a box that the ball hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+/// Checks whether a ball overlaps a box, and if it does, which side of
+/// the box it hit.
+pub fn collide(ball: Vec2, radius: f32, center: Vec2, half_size: Vec2) -> Option<Side> {
+    // The point in the box that's closest to the ball's center. If
+    // that's further away than the radius, they don't touch.
+    let closest = ball.clamp(center - half_size, center + half_size);
+    let offset = ball - closest;
+    if offset.length_squared() > radius * radius {
+        return None;
+    }
+
+    // If the ball's center has got inside the box, there's no offset to
+    // go by, so use which edge it's closest to instead.
+    let offset = if offset == Vec2::ZERO {
+        (ball - center) / half_size
+    } else {
+        offset
+    };
+
+    let side = if offset.x.abs() > offset.y.abs() {
+        if offset.x < 0.0 {
+            Side::Left
+        } else {
+            Side::Right
+        }
+    } else if offset.y > 0.0 {
+        Side::Top
+    } else {
+        Side::Bottom
+    };
+
+    Some(side)
+}
+
+/// The ball's velocity after bouncing off a side of a box.
+pub fn bounce(velocity: Vec2, side: Side) -> Vec2 {
+    // Only bounce if the ball is moving towards the box. Otherwise, a
+    // ball that's still overlapping after a bounce would bounce straight
+    // back in again.
+    let mut velocity = velocity;
+    match side {
+        Side::Left if velocity.x > 0.0 => velocity.x = -velocity.x,
+        Side::Right if velocity.x < 0.0 => velocity.x = -velocity.x,
+        Side::Top if velocity.y < 0.0 => velocity.y = -velocity.y,
+        Side::Bottom if velocity.y > 0.0 => velocity.y = -velocity.y,
+        _ => {}
+    }
+    velocity
+}
+
+/// The ball's velocity after bouncing off the top of the paddle.
+///
+/// `offset` is where the ball hit, from -1 (the left end) to 1 (the
+/// right end). Hitting the middle sends the ball straight up, and
+/// hitting further out sends it off at more of an angle, which is how
+/// the player aims.
+pub fn paddle_bounce(offset: f32, speed: f32) -> Vec2 {
+    const MAX_ANGLE: f32 = 60.0;
+
+    let angle = (offset.clamp(-1.0, 1.0) * MAX_ANGLE).to_radians();
+    Vec2::new(angle.sin(), angle.cos()) * speed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOX: Vec2 = Vec2::new(0.0, 0.0);
+    const HALF: Vec2 = Vec2::new(40.0, 10.0);
+
+    #[test]
+    fn finds_the_side_that_was_hit() {
+        assert_eq!(collide(Vec2::new(-45.0, 0.0), 10.0, BOX, HALF), Some(Side::Left));
+        assert_eq!(collide(Vec2::new(45.0, 5.0), 10.0, BOX, HALF), Some(Side::Right));
+        assert_eq!(collide(Vec2::new(20.0, 15.0), 10.0, BOX, HALF), Some(Side::Top));
+        assert_eq!(collide(Vec2::new(-20.0, -15.0), 10.0, BOX, HALF), Some(Side::Bottom));
+
+        // Inside the box, but nearest the top edge.
+        assert_eq!(collide(Vec2::new(0.0, 8.0), 10.0, BOX, HALF), Some(Side::Top));
+    }
+
+    #[test]
+    fn misses() {
+        assert_eq!(collide(Vec2::new(0.0, 25.0), 10.0, BOX, HALF), None);
+        // Near the corner, but not close enough to touch it.
+        assert_eq!(collide(Vec2::new(48.0, 18.0), 10.0, BOX, HALF), None);
+    }
+
+    #[test]
+    fn bounces_only_when_moving_towards_the_box() {
+        let down_right = Vec2::new(100.0, -100.0);
+
+        assert_eq!(bounce(down_right, Side::Top), Vec2::new(100.0, 100.0));
+        assert_eq!(bounce(down_right, Side::Left), Vec2::new(-100.0, -100.0));
+        // Already moving away from these sides.
+        assert_eq!(bounce(down_right, Side::Right), down_right);
+        assert_eq!(bounce(down_right, Side::Bottom), down_right);
+    }
+
+    #[test]
+    fn aims_off_the_paddle() {
+        let middle = paddle_bounce(0.0, 100.0);
+        assert!(middle.x.abs() < 1e-4 && (middle.y - 100.0).abs() < 1e-4);
+
+        let right = paddle_bounce(1.0, 100.0);
+        assert!(right.x > 80.0 && right.y > 0.0);
+        // The speed stays the same.
+        assert!((right.length() - 100.0).abs() < 1e-3);
+
+        // Off the end is treated like the end.
+        assert_eq!(paddle_bounce(-5.0, 100.0), paddle_bounce(-1.0, 100.0));
+    }
+}
@@ -0,0 +1,38 @@
+// Components are the data attached to entities.
+//
+// An entity is just an ID. What makes it a paddle, a ball, or a brick is
+// which components it has. Many of these are "marker" components with
+// no data at all: they're only there so that systems can find the
+// entities they care about, like `Query<&Transform, With<Paddle>>`.
+
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct Paddle;
+
+#[derive(Component)]
+pub struct Ball;
+
+#[derive(Component)]
+pub struct Brick;
+
+/// Things that the ball bounces off: the walls, the paddle, and the
+/// bricks. Their size is their Transform's scale.
+#[derive(Component)]
+pub struct Collider;
+
+/// How fast something is moving, in pixels per second.
+///
+/// Deref lets us use it like the Vec2 inside, so `velocity.x` works
+/// instead of `velocity.0.x`.
+#[derive(Component, Deref, DerefMut, Debug, Clone, Copy)]
+pub struct Velocity(pub Vec2);
+
+/// The text at the top that shows the score and lives.
+#[derive(Component)]
+pub struct Scoreboard;
+
+/// Everything that belongs to one game. When a new game starts, all of
+/// these are removed, and the level is spawned again from scratch.
+#[derive(Component)]
+pub struct InLevel;
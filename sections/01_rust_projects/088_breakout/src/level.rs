@@ -0,0 +1,175 @@
+// Spawning everything in the game.
+//
+// Bevy's 2D camera puts (0, 0) in the middle of the window, with y going
+// up. Every sprite here is a 1x1 pixel square, stretched to size with
+// its Transform's scale, which means the scale is also the size that
+// collisions use.
+
+use bevy::prelude::*;
+
+use crate::components::{Ball, Brick, Collider, InLevel, Paddle, Scoreboard, Velocity};
+use crate::resources::{Lives, Score};
+use crate::GameState;
+
+// The edges of the arena.
+pub const LEFT: f32 = -450.0;
+pub const RIGHT: f32 = 450.0;
+pub const BOTTOM: f32 = -300.0;
+pub const TOP: f32 = 300.0;
+pub const WALL_THICKNESS: f32 = 10.0;
+
+pub const PADDLE_SIZE: Vec2 = Vec2::new(120.0, 20.0);
+pub const PADDLE_Y: f32 = BOTTOM + 60.0;
+/// How fast the paddle moves, in pixels per second.
+pub const PADDLE_SPEED: f32 = 550.0;
+
+pub const BALL_SIZE: f32 = 16.0;
+pub const BALL_SPEED: f32 = 400.0;
+pub const BALL_START: Vec2 = Vec2::new(0.0, PADDLE_Y + 80.0);
+
+pub const BRICK_SIZE: Vec2 = Vec2::new(80.0, 25.0);
+const BRICK_GAP: f32 = 8.0;
+pub const BRICK_ROWS: usize = 5;
+pub const BRICK_COLUMNS: usize = 9;
+/// How far below the top wall the first row of bricks is.
+const BRICKS_FROM_TOP: f32 = 70.0;
+
+const WALL_COLOR: Color = Color::srgb(0.8, 0.8, 0.8);
+const PADDLE_COLOR: Color = Color::srgb(0.3, 0.3, 0.7);
+const BALL_COLOR: Color = Color::srgb(1.0, 0.5, 0.5);
+const TEXT_COLOR: Color = Color::srgb(0.5, 0.5, 1.0);
+
+/// Spawns the things that stay for the whole time the game is open.
+pub fn setup(mut commands: Commands) {
+    commands.spawn(Camera2d);
+
+    // The walls. There isn't one at the bottom: that's where the ball
+    // gets lost.
+    let width = RIGHT - LEFT;
+    let height = TOP - BOTTOM;
+    let walls = [
+        (Vec2::new(LEFT, 0.0), Vec2::new(WALL_THICKNESS, height + WALL_THICKNESS)),
+        (Vec2::new(RIGHT, 0.0), Vec2::new(WALL_THICKNESS, height + WALL_THICKNESS)),
+        (Vec2::new(0.0, TOP), Vec2::new(width + WALL_THICKNESS, WALL_THICKNESS)),
+    ];
+    for (position, size) in walls {
+        commands.spawn((
+            Sprite::from_color(WALL_COLOR, Vec2::ONE),
+            Transform::from_translation(position.extend(0.0)).with_scale(size.extend(1.0)),
+            Collider,
+        ));
+    }
+
+    commands.spawn((
+        Scoreboard,
+        Text::new(""),
+        TextFont {
+            font_size: 28.0,
+            ..default()
+        },
+        TextColor(TEXT_COLOR),
+        // UI is laid out with Nodes, which work like CSS boxes.
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+    ));
+}
+
+/// Starts a new game: clears away the last one, and spawns the paddle,
+/// ball, and bricks. This runs whenever we enter GameState::Playing.
+pub fn start_level(mut commands: Commands, old: Query<Entity, With<InLevel>>) {
+    for entity in &old {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    commands.insert_resource(Score::default());
+    commands.insert_resource(Lives::default());
+
+    commands.spawn((
+        Paddle,
+        Sprite::from_color(PADDLE_COLOR, Vec2::ONE),
+        Transform::from_xyz(0.0, PADDLE_Y, 0.0).with_scale(PADDLE_SIZE.extend(1.0)),
+        Collider,
+        InLevel,
+    ));
+
+    commands.spawn((
+        Ball,
+        Sprite::from_color(BALL_COLOR, Vec2::ONE),
+        Transform::from_translation(BALL_START.extend(1.0)).with_scale(Vec3::splat(BALL_SIZE)),
+        Velocity(ball_start_velocity()),
+        InLevel,
+    ));
+
+    // Center the grid of bricks between the walls.
+    let grid_width = BRICK_COLUMNS as f32 * (BRICK_SIZE.x + BRICK_GAP) - BRICK_GAP;
+    let first = Vec2::new(
+        -grid_width / 2.0 + BRICK_SIZE.x / 2.0,
+        TOP - BRICKS_FROM_TOP - BRICK_SIZE.y / 2.0,
+    );
+
+    for row in 0..BRICK_ROWS {
+        // Each row is a different color, going from red at the top to
+        // yellow at the bottom.
+        let color = Color::hsl(row as f32 * 12.0, 0.8, 0.6);
+
+        for column in 0..BRICK_COLUMNS {
+            let position = first
+                + Vec2::new(
+                    column as f32 * (BRICK_SIZE.x + BRICK_GAP),
+                    -(row as f32) * (BRICK_SIZE.y + BRICK_GAP),
+                );
+
+            commands.spawn((
+                Brick,
+                Sprite::from_color(color, Vec2::ONE),
+                Transform::from_translation(position.extend(0.0)).with_scale(BRICK_SIZE.extend(1.0)),
+                Collider,
+                InLevel,
+            ));
+        }
+    }
+}
+
+/// The ball starts off heading down and to the right, towards the
+/// paddle.
+pub fn ball_start_velocity() -> Vec2 {
+    Vec2::new(0.5, -1.0).normalize() * BALL_SPEED
+}
+
+/// Shows a message when the game is won or lost. It's part of the
+/// level, so it goes away when the next game starts.
+pub fn show_message(mut commands: Commands, state: Res<State<GameState>>) {
+    let message = match state.get() {
+        GameState::Won => "You won!",
+        GameState::GameOver => "Game over!",
+        GameState::Playing => return,
+    };
+
+    // A node that fills the window, and centers what's inside it. The
+    // text is its child, so despawning the node (with despawn_recursive)
+    // removes the text too.
+    let screen = Node {
+        position_type: PositionType::Absolute,
+        width: Val::Percent(100.0),
+        height: Val::Percent(100.0),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+
+    commands.spawn((screen, InLevel)).with_children(|parent| {
+        parent.spawn((
+            Text::new(format!("{message}\nPress Space to play again.")),
+            TextFont {
+                font_size: 40.0,
+                ..default()
+            },
+            TextColor(TEXT_COLOR),
+            TextLayout::new_with_justify(JustifyText::Center),
+        ));
+    });
+}
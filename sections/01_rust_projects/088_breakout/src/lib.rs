@@ -0,0 +1,76 @@
+// Breakout, built with Bevy.
+//
+// Bevy is built around an ECS (Entity Component System):
+// * components.rs has the components: the data attached to entities.
+// * resources.rs has the resources: data there's only one of.
+// * systems.rs has the systems: functions that run every frame, and
+//   work on components and resources.
+// * level.rs spawns the walls, paddle, ball, and bricks.
+// * collision.rs has the maths for bouncing the ball.
+//
+// BreakoutPlugin below puts it all together.
+
+pub mod collision;
+pub mod components;
+pub mod level;
+pub mod resources;
+pub mod systems;
+
+use bevy::prelude::*;
+
+use crate::resources::{Lives, Score};
+
+/// Which part of the game we're in. Systems can be set to only run in
+/// some states, and to run when a state is entered.
+#[derive(States, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum GameState {
+    #[default]
+    Playing,
+    GameOver,
+    Won,
+}
+
+/// Adds the whole game to an App.
+///
+/// Bundling everything into a plugin keeps main.rs short, and lets the
+/// tests add the game to an App without a window.
+pub struct BreakoutPlugin;
+
+impl Plugin for BreakoutPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<GameState>()
+            .init_resource::<Score>()
+            .init_resource::<Lives>()
+            .insert_resource(ClearColor(Color::srgb(0.1, 0.1, 0.12)))
+            .add_systems(Startup, level::setup)
+            .add_systems(OnEnter(GameState::Playing), level::start_level)
+            .add_systems(OnEnter(GameState::GameOver), level::show_message)
+            .add_systems(OnEnter(GameState::Won), level::show_message)
+            // FixedUpdate runs at a steady 64 times a second, however
+            // fast the game is drawing frames. That keeps the ball's
+            // movement (and so the collisions) the same on every
+            // computer. chain() runs these in order, one after another.
+            .add_systems(
+                FixedUpdate,
+                (
+                    systems::move_paddle,
+                    systems::apply_velocity,
+                    systems::check_collisions,
+                    systems::check_ball_lost,
+                    systems::check_won,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            // Update runs once per frame. Reading just_pressed keys has
+            // to happen here, since FixedUpdate can run zero or several
+            // times in a frame, and would miss or repeat key presses.
+            .add_systems(
+                Update,
+                (
+                    systems::update_scoreboard,
+                    systems::restart.run_if(not(in_state(GameState::Playing))),
+                ),
+            );
+    }
+}
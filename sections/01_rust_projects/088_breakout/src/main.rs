@@ -0,0 +1,21 @@
+// Play with `cargo run`. Move the paddle with the arrow keys (or A and
+// D). The first build takes a while, since Bevy is big!
+
+use bevy::prelude::*;
+use project::BreakoutPlugin;
+
+fn main() {
+    App::new()
+        // DefaultPlugins adds everything a game needs: a window, input,
+        // rendering, text, and so on.
+        .add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(Window {
+                title: "Breakout".to_string(),
+                resolution: (960.0, 660.0).into(),
+                ..default()
+            }),
+            ..default()
+        }))
+        .add_plugins(BreakoutPlugin)
+        .run();
+}
@@ -0,0 +1,22 @@
+// Resources are data that there's only one of, which doesn't belong to
+// any entity. Systems get them with Res<T> (to read) or ResMut<T> (to
+// change).
+
+use bevy::prelude::*;
+
+/// How many lives the player starts with.
+pub const STARTING_LIVES: u32 = 3;
+
+/// How many bricks have been broken this game.
+#[derive(Resource, Default, Debug)]
+pub struct Score(pub u32);
+
+/// How many more times the ball can be lost before the game is over.
+#[derive(Resource, Debug)]
+pub struct Lives(pub u32);
+
+impl Default for Lives {
+    fn default() -> Self {
+        Lives(STARTING_LIVES)
+    }
+}
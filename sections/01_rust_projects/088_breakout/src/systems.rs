@@ -0,0 +1,135 @@
+// Systems are the game's logic.
+//
+// A system is a normal function. Its parameters say what it needs:
+// Query for entities with certain components, Res and ResMut for
+// resources, Commands for spawning and despawning. Bevy looks at the
+// parameters, hands over the right data, and runs systems that don't
+// touch the same data at the same time, on different threads.
+
+use bevy::prelude::*;
+
+use crate::collision::{bounce, collide, paddle_bounce, Side};
+use crate::components::{Ball, Brick, Collider, Paddle, Scoreboard, Velocity};
+use crate::level::{
+    self, BALL_SIZE, BALL_SPEED, BALL_START, BOTTOM, LEFT, PADDLE_SIZE, PADDLE_SPEED, RIGHT, WALL_THICKNESS,
+};
+use crate::resources::{Lives, Score};
+use crate::GameState;
+
+/// Moves the paddle left and right with the arrow keys (or A and D).
+///
+/// Single means "there's exactly one of these". If there isn't, the
+/// system just doesn't run.
+pub fn move_paddle(keyboard: Res<ButtonInput<KeyCode>>, time: Res<Time>, mut paddle: Single<&mut Transform, With<Paddle>>) {
+    let mut direction = 0.0;
+    if keyboard.any_pressed([KeyCode::ArrowLeft, KeyCode::KeyA]) {
+        direction -= 1.0;
+    }
+    if keyboard.any_pressed([KeyCode::ArrowRight, KeyCode::KeyD]) {
+        direction += 1.0;
+    }
+
+    // Multiplying by the time since the last run keeps the speed the
+    // same, however often the system runs.
+    let x = paddle.translation.x + direction * PADDLE_SPEED * time.delta_secs();
+
+    // Stop at the walls.
+    let left = LEFT + WALL_THICKNESS / 2.0 + PADDLE_SIZE.x / 2.0;
+    let right = RIGHT - WALL_THICKNESS / 2.0 - PADDLE_SIZE.x / 2.0;
+    paddle.translation.x = x.clamp(left, right);
+}
+
+/// Moves everything that has a Velocity.
+pub fn apply_velocity(time: Res<Time>, mut query: Query<(&mut Transform, &Velocity)>) {
+    for (mut transform, velocity) in &mut query {
+        transform.translation += velocity.extend(0.0) * time.delta_secs();
+    }
+}
+
+/// What check_collisions needs to know about each collider.
+///
+/// Has<Brick> is true or false, depending on whether the entity has that
+/// component, instead of filtering out the ones that don't. Queries get
+/// long quickly, so giving them a name like this is common in Bevy.
+type ColliderData = (Entity, &'static Transform, Has<Brick>, Has<Paddle>);
+
+/// Bounces the ball off anything it touches, and breaks bricks.
+pub fn check_collisions(
+    mut commands: Commands,
+    mut score: ResMut<Score>,
+    ball: Single<(&Transform, &mut Velocity), With<Ball>>,
+    colliders: Query<ColliderData, With<Collider>>,
+) {
+    let (ball, mut velocity) = ball.into_inner();
+    let ball_position = ball.translation.truncate();
+
+    for (entity, transform, is_brick, is_paddle) in &colliders {
+        let center = transform.translation.truncate();
+        let half_size = transform.scale.truncate() / 2.0;
+
+        let Some(side) = collide(ball_position, BALL_SIZE / 2.0, center, half_size) else {
+            continue;
+        };
+
+        if is_paddle && side == Side::Top {
+            // Aim based on where the ball hit the paddle.
+            let offset = (ball_position.x - center.x) / half_size.x;
+            velocity.0 = paddle_bounce(offset, BALL_SPEED);
+        } else {
+            velocity.0 = bounce(velocity.0, side);
+        }
+
+        if is_brick {
+            // Commands don't happen straight away. The brick is removed
+            // after this system finishes.
+            commands.entity(entity).despawn();
+            score.0 += 1;
+        }
+    }
+}
+
+/// Takes away a life if the ball falls off the bottom, and ends the game
+/// once there are none left.
+pub fn check_ball_lost(
+    mut lives: ResMut<Lives>,
+    mut next_state: ResMut<NextState<GameState>>,
+    ball: Single<(&mut Transform, &mut Velocity), With<Ball>>,
+) {
+    let (mut transform, mut velocity) = ball.into_inner();
+    if transform.translation.y > BOTTOM - BALL_SIZE {
+        return;
+    }
+
+    lives.0 = lives.0.saturating_sub(1);
+    if lives.0 == 0 {
+        // The state changes before the next frame, which stops the
+        // gameplay systems (and shows the message).
+        next_state.set(GameState::GameOver);
+    } else {
+        transform.translation = BALL_START.extend(1.0);
+        velocity.0 = level::ball_start_velocity();
+    }
+}
+
+/// The game is won once every brick is broken.
+pub fn check_won(bricks: Query<(), With<Brick>>, mut next_state: ResMut<NextState<GameState>>) {
+    if bricks.is_empty() {
+        next_state.set(GameState::Won);
+    }
+}
+
+/// Updates the text at the top of the screen.
+pub fn update_scoreboard(score: Res<Score>, lives: Res<Lives>, mut text: Single<&mut Text, With<Scoreboard>>) {
+    // Only touch the text when something changed. Bevy keeps track of
+    // which resources were changed, so this is cheap to check.
+    if score.is_changed() || lives.is_changed() {
+        text.0 = format!("Score: {}   Lives: {}", score.0, lives.0);
+    }
+}
+
+/// Starts a new game when Space is pressed, after winning or losing.
+pub fn restart(keyboard: Res<ButtonInput<KeyCode>>, mut next_state: ResMut<NextState<GameState>>) {
+    if keyboard.just_pressed(KeyCode::Space) {
+        next_state.set(GameState::Playing);
+    }
+}
@@ -0,0 +1,132 @@
+// Runs the game without a window.
+//
+// MinimalPlugins has Bevy's core (like time and the task pools), but no
+// window or rendering, so the game's systems run just like normal, and
+// we can look at the entities and resources afterwards.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use project::components::{Ball, Brick, Velocity};
+use project::level::{BALL_SIZE, BOTTOM, BRICK_COLUMNS, BRICK_ROWS, BRICK_SIZE};
+use project::resources::{Lives, Score, STARTING_LIVES};
+use project::{BreakoutPlugin, GameState};
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, StatesPlugin, BreakoutPlugin))
+        // Without the InputPlugin, nothing presses keys, or clears
+        // them, so the tests can press them by hand.
+        .init_resource::<ButtonInput<KeyCode>>()
+        // Stop time, so nothing moves unless a test moves it.
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::ZERO));
+
+    // The first update runs the Startup systems, and enters the Playing
+    // state, which spawns the level.
+    app.update();
+    app
+}
+
+fn bricks(app: &mut App) -> Vec<Vec3> {
+    let world = app.world_mut();
+    world
+        .query_filtered::<&Transform, With<Brick>>()
+        .iter(world)
+        .map(|transform| transform.translation)
+        .collect()
+}
+
+/// Puts the ball somewhere, moving at some speed.
+fn place_ball(app: &mut App, position: Vec2, velocity: Vec2) {
+    let world = app.world_mut();
+    let (mut transform, mut ball_velocity) = world
+        .query_filtered::<(&mut Transform, &mut Velocity), With<Ball>>()
+        .single_mut(world);
+    transform.translation = position.extend(1.0);
+    ball_velocity.0 = velocity;
+}
+
+fn ball_velocity(app: &mut App) -> Vec2 {
+    let world = app.world_mut();
+    world.query_filtered::<&Velocity, With<Ball>>().single(world).0
+}
+
+fn state(app: &App) -> GameState {
+    *app.world().resource::<State<GameState>>().get()
+}
+
+/// Runs the gameplay systems once.
+fn step(app: &mut App) {
+    app.world_mut().run_schedule(FixedUpdate);
+}
+
+#[test]
+fn starts_a_game() {
+    let mut app = app();
+
+    assert_eq!(state(&app), GameState::Playing);
+    assert_eq!(bricks(&mut app).len(), BRICK_ROWS * BRICK_COLUMNS);
+    assert_eq!(app.world().resource::<Score>().0, 0);
+    assert_eq!(app.world().resource::<Lives>().0, STARTING_LIVES);
+}
+
+#[test]
+fn breaks_bricks() {
+    let mut app = app();
+    // A brick in the bottom row, so the ball doesn't touch any others.
+    let brick = bricks(&mut app)
+        .into_iter()
+        .min_by(|a, b| a.y.total_cmp(&b.y))
+        .unwrap();
+
+    // Just touching the bottom of the brick, moving up.
+    let below = brick.truncate() - Vec2::new(0.0, BRICK_SIZE.y / 2.0 + BALL_SIZE / 2.0 - 1.0);
+    place_ball(&mut app, below, Vec2::new(0.0, 400.0));
+    step(&mut app);
+
+    assert_eq!(bricks(&mut app).len(), BRICK_ROWS * BRICK_COLUMNS - 1);
+    assert_eq!(app.world().resource::<Score>().0, 1);
+    assert!(ball_velocity(&mut app).y < 0.0, "the ball should bounce back down");
+}
+
+#[test]
+fn loses_lives_and_restarts() {
+    let mut app = app();
+
+    for lives_left in (0..STARTING_LIVES).rev() {
+        place_ball(&mut app, Vec2::new(0.0, BOTTOM - 100.0), Vec2::new(0.0, -400.0));
+        step(&mut app);
+        assert_eq!(app.world().resource::<Lives>().0, lives_left);
+    }
+
+    // The state changes on the next update.
+    app.update();
+    assert_eq!(state(&app), GameState::GameOver);
+
+    // Space starts a new game, which takes another update to enter.
+    app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(KeyCode::Space);
+    app.update();
+    app.update();
+
+    assert_eq!(state(&app), GameState::Playing);
+    assert_eq!(app.world().resource::<Lives>().0, STARTING_LIVES);
+    assert_eq!(bricks(&mut app).len(), BRICK_ROWS * BRICK_COLUMNS);
+}
+
+#[test]
+fn wins_when_every_brick_is_broken() {
+    let mut app = app();
+
+    let world = app.world_mut();
+    let all: Vec<Entity> = world.query_filtered::<Entity, With<Brick>>().iter(world).collect();
+    for brick in all {
+        world.despawn(brick);
+    }
+
+    step(&mut app);
+    app.update();
+
+    assert_eq!(state(&app), GameState::Won);
+}
@@ -0,0 +1,53 @@
+// Writes a made-up access log, for trying the analyzer on something big.
+//     cargo run --example generate -- 1000000 > big.log
+
+use std::io::{self, BufWriter, Write};
+
+const PATHS: [&str; 8] = [
+    "/",
+    "/about",
+    "/login",
+    "/users/{}",
+    "/users/{}/posts",
+    "/posts/{}?ref=home",
+    "/static/app.js",
+    "/api/search?q=rust",
+];
+const STATUSES: [u16; 10] = [200, 200, 200, 200, 200, 200, 304, 404, 500, 503];
+
+fn main() -> io::Result<()> {
+    let lines: u64 = std::env::args().nth(1).and_then(|n| n.parse().ok()).unwrap_or(100_000);
+
+    // Writing millions of lines one at a time to stdout would be slow,
+    // so buffer them.
+    let mut out = BufWriter::new(io::stdout().lock());
+
+    // A tiny random number generator, so this doesn't need any crates.
+    // It's not very random, but it's plenty for made-up logs.
+    let mut seed: u64 = 0x2545_F491_4F6C_DD1D;
+    let mut random = move |max: u64| {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed % max
+    };
+
+    for i in 0..lines {
+        // Busier in the middle of the day.
+        let hour = (random(12) + random(13)).min(23);
+        let path = PATHS[random(PATHS.len() as u64) as usize].replace("{}", &random(500).to_string());
+        let status = STATUSES[random(STATUSES.len() as u64) as usize];
+
+        writeln!(
+            out,
+            r#"10.0.{}.{} - - [12/Mar/2024:{hour:02}:{:02}:{:02} +0000] "GET {path} HTTP/1.1" {status} {} "-" "Mozilla/5.0""#,
+            random(256),
+            random(256),
+            i % 60,
+            random(60),
+            random(50_000),
+        )?;
+    }
+
+    out.flush()
+}
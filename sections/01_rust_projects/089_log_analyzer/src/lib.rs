@@ -0,0 +1,44 @@
+// A tool that reads web server access logs, and reports what's been
+// happening.
+//
+// * parse.rs reads each line with a regex.
+// * report.rs adds the requests up, and prints the report.
+// * analyze() below reads a log a line at a time, so that it can handle
+//   logs far bigger than the computer's memory.
+
+pub mod parse;
+pub mod report;
+
+use std::io::{self, BufRead};
+
+pub use report::Report;
+
+/// Reads a whole log, adding every line to the report.
+///
+/// This takes anything that implements BufRead, so it works the same on
+/// a file, stdin, or (in tests) a string.
+pub fn analyze(mut reader: impl BufRead, report: &mut Report) -> io::Result<()> {
+    // One buffer, reused for every line, instead of a new String each
+    // time like lines() would make.
+    let mut buffer = Vec::new();
+
+    loop {
+        buffer.clear();
+        if reader.read_until(b'\n', &mut buffer)? == 0 {
+            // 0 bytes read means we've reached the end.
+            return Ok(());
+        }
+
+        // Logs can have bytes in them that aren't valid UTF-8 (like a
+        // badly encoded path from a broken client). read_line would fail
+        // on those, but from_utf8_lossy replaces them with �, so one
+        // bad line doesn't stop the whole report.
+        let line = String::from_utf8_lossy(&buffer);
+
+        match parse::parse_line(line.trim_end()) {
+            Some(request) => report.add(&request),
+            None if line.trim().is_empty() => {}
+            None => report.malformed += 1,
+        }
+    }
+}
@@ -0,0 +1,56 @@
+// Prints a report about one or more access logs.
+//     cargo run -- access.log
+//     cargo run -- --top 5 access.log older.log
+//     cat access.log | cargo run
+//
+// To try it on something bigger, generate a log of any size with
+// `cargo run --example generate -- 1000000 > big.log`.
+
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::process;
+
+use project::{analyze, Report};
+
+fn main() {
+    let mut top = 10;
+    let mut paths = Vec::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--top" => match args.next().and_then(|n| n.parse().ok()) {
+                Some(n) => top = n,
+                None => fail("--top needs a number"),
+            },
+            _ if arg.starts_with("--") => fail(&format!("unknown flag '{arg}'")),
+            _ => paths.push(arg),
+        }
+    }
+
+    let mut report = Report::new(top);
+
+    if paths.is_empty() {
+        // No files, so read from stdin. lock() gives a BufRead.
+        if let Err(err) = analyze(io::stdin().lock(), &mut report) {
+            fail(&format!("couldn't read stdin: {err}"));
+        }
+    }
+
+    for path in &paths {
+        // BufReader reads the file in big chunks, instead of asking the
+        // operating system for every line separately.
+        let result = File::open(path).and_then(|file| analyze(BufReader::new(file), &mut report));
+        if let Err(err) = result {
+            fail(&format!("couldn't read {path}: {err}"));
+        }
+    }
+
+    print!("{report}");
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("Error: {message}");
+    eprintln!("Usage: log-analyzer [--top N] [files...]");
+    process::exit(2);
+}
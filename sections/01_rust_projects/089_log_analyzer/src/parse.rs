@@ -0,0 +1,145 @@
+// Reading one line of an access log.
+//
+// Web servers like nginx and Apache write a line for every request, in
+// the "combined" log format:
+//
+//     203.0.113.9 - - [10/Oct/2024:13:55:36 +0000] "GET /users/42?tab=posts HTTP/1.1" 200 2326 "-" "curl/8.5"
+//
+// That's the client's address, two fields that are almost always "-",
+// the time, the request, the status code, the size of the response in
+// bytes, and (optionally) the referrer and user agent.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+/// The regex for a whole line, compiled once, the first time it's used.
+///
+/// Compiling a regex is slow compared to running it, so compiling it
+/// again for every line would be a big waste. A LazyLock in a static
+/// runs the closure the first time it's used, and hands out the same
+/// Regex every time after that, even across threads.
+static LINE: LazyLock<Regex> = LazyLock::new(|| {
+    // (?x) turns on "verbose" mode, where spaces and newlines in the
+    // pattern are ignored (use \s or \  for a real space), so it can be
+    // split over lines. (?P<name>...) is a named capture group.
+    Regex::new(
+        r#"(?x)
+        ^(?P<ip>\S+) \s \S+ \s \S+ \s
+        \[ \d{2}/\w{3}/\d{4} : (?P<hour>\d{2}) :\d{2}:\d{2} \s [+-]\d{4} \] \s
+        " (?P<method>[A-Z]+) \s (?P<path>\S+) \s [^"]* " \s
+        (?P<status>\d{3}) \s (?P<bytes>\d+|-)
+        (?: \s "[^"]*" \s "(?P<agent>[^"]*)" )?
+        "#,
+    )
+    .unwrap()
+});
+
+/// A number on its own between slashes, like the 42 in /users/42/posts.
+static ID: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"/\d+(/|$)").unwrap());
+
+/// One request from the log.
+///
+/// The fields borrow from the line, instead of each being copied into
+/// its own String. With millions of lines, that saves a lot of work.
+#[derive(Debug, PartialEq)]
+pub struct Request<'a> {
+    pub ip: &'a str,
+    /// The hour of the day (0 to 23) that the request was made in.
+    pub hour: u8,
+    pub method: &'a str,
+    pub path: &'a str,
+    pub status: u16,
+    /// How big the response was. "-" in the log means there was no body.
+    pub bytes: u64,
+    pub user_agent: Option<&'a str>,
+}
+
+/// Reads one line of the log, or returns None if it isn't in the right
+/// format.
+pub fn parse_line(line: &str) -> Option<Request<'_>> {
+    let captures = LINE.captures(line)?;
+
+    // A named group that matched can be taken out by name. Groups that
+    // have to match for the whole regex to match (like ip) are always
+    // there, but optional ones (like agent) might not be.
+    Some(Request {
+        ip: captures.name("ip")?.as_str(),
+        // The regex only lets digits through, but 99 isn't an hour.
+        hour: captures["hour"].parse().ok().filter(|hour| *hour < 24)?,
+        method: captures.name("method")?.as_str(),
+        path: captures.name("path")?.as_str(),
+        status: captures["status"].parse().ok()?,
+        bytes: captures["bytes"].parse().unwrap_or(0),
+        user_agent: captures.name("agent").map(|agent| agent.as_str()),
+    })
+}
+
+/// Groups similar paths together, so that /users/1 and /users/2 are
+/// counted as the same page.
+///
+/// The query string (everything after ?) is dropped, and numbers on
+/// their own between slashes become :id.
+pub fn normalize_path(path: &str) -> String {
+    let path = path.split('?').next().unwrap_or(path);
+
+    // $1 puts back whatever the first group matched (a / or nothing).
+    // replace_all doesn't see overlapping matches, so /1/2 needs two
+    // passes: the first uses up the / between them.
+    let once = ID.replace_all(path, "/:id$1");
+    ID.replace_all(&once, "/:id$1").into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_combined_lines() {
+        let line =
+            r#"203.0.113.9 - alice [10/Oct/2024:13:55:36 +0000] "GET /users/42?tab=posts HTTP/1.1" 200 2326 "-" "curl/8.5""#;
+
+        assert_eq!(
+            parse_line(line),
+            Some(Request {
+                ip: "203.0.113.9",
+                hour: 13,
+                method: "GET",
+                path: "/users/42?tab=posts",
+                status: 200,
+                bytes: 2326,
+                user_agent: Some("curl/8.5"),
+            })
+        );
+    }
+
+    #[test]
+    fn parses_common_lines() {
+        // The "common" format leaves off the referrer and user agent,
+        // and "-" means there was no body.
+        let line = r#"::1 - - [01/Jan/2024:00:00:01 -0500] "HEAD / HTTP/1.0" 304 -"#;
+        let request = parse_line(line).unwrap();
+
+        assert_eq!((request.hour, request.status, request.bytes), (0, 304, 0));
+        assert_eq!(request.user_agent, None);
+    }
+
+    #[test]
+    fn rejects_other_lines() {
+        assert_eq!(parse_line(""), None);
+        assert_eq!(parse_line("not a log line"), None);
+        assert_eq!(
+            parse_line(r#"1.2.3.4 - - [10/Oct/2024:99:00:00 +0000] "GET / HTTP/1.1" 200 1"#),
+            None
+        );
+    }
+
+    #[test]
+    fn normalizes_paths() {
+        assert_eq!(normalize_path("/users/42?tab=posts"), "/users/:id");
+        assert_eq!(normalize_path("/users/42/posts/7"), "/users/:id/posts/:id");
+        assert_eq!(normalize_path("/a/1/2"), "/a/:id/:id");
+        // Numbers that are only part of a segment are left alone.
+        assert_eq!(normalize_path("/v2/report-2024.csv"), "/v2/report-2024.csv");
+    }
+}
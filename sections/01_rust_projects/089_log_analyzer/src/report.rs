@@ -0,0 +1,174 @@
+// Adding up requests, and printing the totals.
+//
+// Each request is added to a few HashMaps, counting requests per path,
+// per status code, and per hour. Only the counts are kept, never the
+// lines themselves, so the memory used stays the same however big the
+// log is.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::parse::{normalize_path, Request};
+
+/// How wide the longest bar in the chart of hours is.
+const BAR_WIDTH: u64 = 40;
+
+#[derive(Debug, Default)]
+pub struct Report {
+    pub requests: u64,
+    /// Lines that weren't in the right format.
+    pub malformed: u64,
+    pub bytes: u64,
+    pub by_path: HashMap<String, u64>,
+    pub by_status: HashMap<u16, u64>,
+    pub by_hour: HashMap<u8, u64>,
+    /// Requests that the server failed (status 500 and up), by path.
+    pub errors_by_path: HashMap<String, u64>,
+    /// How many paths to list in the report.
+    pub top: usize,
+}
+
+impl Report {
+    pub fn new(top: usize) -> Self {
+        Report {
+            top,
+            ..Report::default()
+        }
+    }
+
+    pub fn add(&mut self, request: &Request) {
+        self.requests += 1;
+        self.bytes += request.bytes;
+
+        let path = normalize_path(request.path);
+
+        // entry() finds the count for a key, or adds one starting at
+        // 0, so we can add 1 either way, with only one lookup.
+        *self.by_status.entry(request.status).or_insert(0) += 1;
+        *self.by_hour.entry(request.hour).or_insert(0) += 1;
+        if request.status >= 500 {
+            *self.errors_by_path.entry(path.clone()).or_insert(0) += 1;
+        }
+        *self.by_path.entry(path).or_insert(0) += 1;
+    }
+
+    /// The paths with the most requests, busiest first.
+    pub fn top_paths(&self) -> Vec<(&str, u64)> {
+        top(&self.by_path, self.top)
+    }
+
+    /// The paths with the most server errors, worst first.
+    pub fn top_errors(&self) -> Vec<(&str, u64)> {
+        top(&self.errors_by_path, self.top)
+    }
+}
+
+/// The `n` biggest counts in a map. Ties are sorted by path, so the
+/// order is always the same (HashMaps don't have an order).
+fn top(counts: &HashMap<String, u64>, n: usize) -> Vec<(&str, u64)> {
+    let mut counts: Vec<(&str, u64)> = counts.iter().map(|(path, count)| (path.as_str(), *count)).collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    counts.truncate(n);
+    counts
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} requests, {} sent", self.requests, format_bytes(self.bytes))?;
+        if self.malformed > 0 {
+            writeln!(f, "{} lines couldn't be read", self.malformed)?;
+        }
+
+        writeln!(f, "\nTop paths:")?;
+        for (path, count) in self.top_paths() {
+            writeln!(f, "{count:>8}  {path}")?;
+        }
+
+        // Sort the status codes, so 200 comes before 404.
+        let mut statuses: Vec<_> = self.by_status.iter().collect();
+        statuses.sort();
+        writeln!(f, "\nStatus codes:")?;
+        for (status, count) in statuses {
+            writeln!(f, "{count:>8}  {status}")?;
+        }
+
+        let errors = self.top_errors();
+        if !errors.is_empty() {
+            writeln!(f, "\nServer errors:")?;
+            for (path, count) in errors {
+                writeln!(f, "{count:>8}  {path}")?;
+            }
+        }
+
+        // A bar chart, scaled so the busiest hour has the longest bar.
+        let busiest = self.by_hour.values().copied().max().unwrap_or(0);
+        writeln!(f, "\nRequests by hour:")?;
+        for hour in 0..24 {
+            let count = self.by_hour.get(&hour).copied().unwrap_or(0);
+            // checked_div gives None instead of crashing when dividing by
+            // 0, which happens when the log had no requests at all.
+            let bar = (count * BAR_WIDTH).checked_div(busiest).unwrap_or(0);
+            writeln!(f, "  {hour:02}:00 {count:>8} {}", "#".repeat(bar as usize))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Formats a number of bytes with a unit, like 1.5 MB.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1000.0 && unit < UNITS.len() - 1 {
+        size /= 1000.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(path: &str, status: u16, hour: u8) -> Request<'_> {
+        Request {
+            ip: "127.0.0.1",
+            hour,
+            method: "GET",
+            path,
+            status,
+            bytes: 100,
+            user_agent: None,
+        }
+    }
+
+    #[test]
+    fn counts_requests() {
+        let mut report = Report::new(2);
+        report.add(&request("/users/1", 200, 9));
+        report.add(&request("/users/2?x=y", 200, 9));
+        report.add(&request("/", 500, 10));
+        report.add(&request("/about", 404, 23));
+
+        assert_eq!(report.requests, 4);
+        assert_eq!(report.bytes, 400);
+        assert_eq!(report.top_paths(), [("/users/:id", 2), ("/", 1)]);
+        assert_eq!(report.by_status[&200], 2);
+        assert_eq!(report.by_hour[&9], 2);
+        assert_eq!(report.top_errors(), [("/", 1)]);
+    }
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(999), "999 B");
+        assert_eq!(format_bytes(1_500), "1.5 KB");
+        assert_eq!(format_bytes(2_000_000_000), "2.0 GB");
+    }
+}
@@ -0,0 +1,53 @@
+use std::io::Cursor;
+
+use project::{analyze, Report};
+
+const LOG: &str = r#"1.1.1.1 - - [12/Mar/2024:08:00:00 +0000] "GET /users/1 HTTP/1.1" 200 100 "-" "a"
+1.1.1.1 - - [12/Mar/2024:08:30:00 +0000] "GET /users/2?tab=posts HTTP/1.1" 200 100 "-" "a"
+2.2.2.2 - - [12/Mar/2024:09:00:00 +0000] "POST /login HTTP/1.1" 500 50 "-" "b"
+
+garbage
+2.2.2.2 - - [12/Mar/2024:09:00:01 +0000] "GET / HTTP/1.1" 304 -"#;
+
+fn report(log: &[u8]) -> Report {
+    let mut report = Report::new(10);
+    // A Cursor over some bytes implements BufRead, just like a file.
+    analyze(Cursor::new(log), &mut report).unwrap();
+    report
+}
+
+#[test]
+fn adds_up_a_log() {
+    let report = report(LOG.as_bytes());
+
+    assert_eq!(report.requests, 4);
+    // The blank line doesn't count as malformed, but "garbage" does.
+    assert_eq!(report.malformed, 1);
+    assert_eq!(report.bytes, 250);
+    assert_eq!(report.top_paths()[0], ("/users/:id", 2));
+    assert_eq!(report.by_hour[&8], 2);
+    assert_eq!(report.top_errors(), [("/login", 1)]);
+}
+
+#[test]
+fn keeps_going_after_bad_bytes() {
+    // \xff is never valid UTF-8.
+    let mut log = b"1.1.1.1 - - [12/Mar/2024:08:00:00 +0000] \"GET /caf\xff HTTP/1.1\" 404 0\n".to_vec();
+    log.extend_from_slice(LOG.as_bytes());
+
+    let report = report(&log);
+
+    assert_eq!(report.requests, 5);
+    assert_eq!(report.by_status[&404], 1);
+}
+
+#[test]
+fn prints_a_report() {
+    let text = report(LOG.as_bytes()).to_string();
+
+    assert!(text.starts_with("4 requests, 250 B sent\n1 lines couldn't be read\n"), "{text}");
+    assert!(text.contains("       2  /users/:id\n"), "{text}");
+    assert!(text.contains("Server errors:\n       1  /login\n"), "{text}");
+    // The busiest hour has the full-width bar.
+    assert!(text.contains(&format!("  08:00        2 {}\n", "#".repeat(40))), "{text}");
+}
@@ -0,0 +1,96 @@
+// Writing and reading single bits.
+//
+// Files are made of bytes, but Huffman codes are made of bits, and most
+// of them aren't 8 bits long. BitWriter packs bits into bytes, and
+// BitReader takes them back out again.
+//
+// Bits are stored "most significant first": the first bit written goes
+// in the top (128) bit of the first byte.
+
+#[derive(Default)]
+pub struct BitWriter {
+    bytes: Vec<u8>,
+    /// The byte being filled in.
+    current: u8,
+    /// How many bits of `current` are used.
+    used: u8,
+}
+
+impl BitWriter {
+    pub fn new() -> Self {
+        BitWriter::default()
+    }
+
+    pub fn write_bit(&mut self, bit: bool) {
+        // Shift the bits along to make room, and put the new one at the
+        // bottom.
+        self.current = (self.current << 1) | bit as u8;
+        self.used += 1;
+
+        if self.used == 8 {
+            self.bytes.push(self.current);
+            self.current = 0;
+            self.used = 0;
+        }
+    }
+
+    pub fn write_bits(&mut self, bits: &[bool]) {
+        for &bit in bits {
+            self.write_bit(bit);
+        }
+    }
+
+    /// Returns the bytes written, with the last byte padded out with
+    /// zeros.
+    pub fn finish(mut self) -> Vec<u8> {
+        if self.used > 0 {
+            // Move the bits we have up to the top of the byte.
+            self.bytes.push(self.current << (8 - self.used));
+        }
+        self.bytes
+    }
+}
+
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    /// Which bit to read next, counting from the start.
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        BitReader { bytes, position: 0 }
+    }
+
+    /// Reads the next bit, or returns None if there are none left.
+    pub fn read_bit(&mut self) -> Option<bool> {
+        let byte = *self.bytes.get(self.position / 8)?;
+        let bit = (byte >> (7 - self.position % 8)) & 1;
+        self.position += 1;
+        Some(bit == 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packs_bits_into_bytes() {
+        let mut writer = BitWriter::new();
+        writer.write_bits(&[true, false, true, true, false, false, false, true]);
+        writer.write_bits(&[true, true]);
+
+        assert_eq!(writer.finish(), [0b1011_0001, 0b1100_0000]);
+    }
+
+    #[test]
+    fn reads_bits_back() {
+        let mut reader = BitReader::new(&[0b1011_0001, 0b1000_0000]);
+        let bits: Vec<bool> = std::iter::from_fn(|| reader.read_bit()).collect();
+
+        assert_eq!(bits.len(), 16);
+        assert_eq!(bits[..9], [true, false, true, true, false, false, false, true, true]);
+        assert!(bits[9..].iter().all(|bit| !bit));
+    }
+}
@@ -0,0 +1,183 @@
+// Compressing and decompressing whole files.
+//
+// A compressed file starts with a header, so that the decompressor can
+// build the same tree that the compressor used:
+//
+//     "HUF1"                   4 bytes, to recognize our files
+//     original length          8 bytes
+//     number of bytes used     2 bytes (from 0 to 256)
+//     each byte, and its count 1 + 8 bytes each
+//
+// After that come the codes for every byte of the original file, packed
+// together with a BitWriter.
+
+use std::fmt;
+
+use crate::bits::{BitReader, BitWriter};
+use crate::tree::{build, codes, count, Frequencies, Node};
+
+const MAGIC: &[u8; 4] = b"HUF1";
+
+#[derive(Debug, PartialEq)]
+pub enum DecompressError {
+    /// The data doesn't start with MAGIC.
+    NotCompressed,
+    /// The data ends too soon.
+    Truncated,
+    /// The header doesn't make sense.
+    Corrupt,
+}
+
+pub fn compress(data: &[u8]) -> Vec<u8> {
+    let frequencies = count(data);
+
+    let mut output = MAGIC.to_vec();
+    output.extend_from_slice(&(data.len() as u64).to_le_bytes());
+
+    let used: Vec<u8> = (0..=255).filter(|&byte| frequencies[byte as usize] > 0).collect();
+    output.extend_from_slice(&(used.len() as u16).to_le_bytes());
+    for byte in used {
+        output.push(byte);
+        output.extend_from_slice(&frequencies[byte as usize].to_le_bytes());
+    }
+
+    // An empty file has no tree, and nothing else to write.
+    let Some(root) = build(&frequencies) else {
+        return output;
+    };
+    let codes = codes(&root);
+
+    let mut writer = BitWriter::new();
+    for &byte in data {
+        writer.write_bits(&codes[byte as usize]);
+    }
+    output.extend_from_slice(&writer.finish());
+
+    output
+}
+
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>, DecompressError> {
+    let mut input = Input(data);
+
+    if input.take(MAGIC.len()).ok() != Some(MAGIC.as_slice()) {
+        return Err(DecompressError::NotCompressed);
+    }
+    let length = input.u64()?;
+
+    let used = u16::from_le_bytes(input.take(2)?.try_into().unwrap());
+    if used > 256 {
+        return Err(DecompressError::Corrupt);
+    }
+    let mut frequencies: Frequencies = [0; 256];
+    let mut total: u64 = 0;
+    for _ in 0..used {
+        let byte = input.take(1)?[0];
+        let frequency = input.u64()?;
+        frequencies[byte as usize] = frequency;
+
+        // The counts have to add up to the length. Someone could make a
+        // file with counts so big that adding them overflows, so check
+        // for that too.
+        total = total.checked_add(frequency).ok_or(DecompressError::Corrupt)?;
+    }
+    if total != length {
+        return Err(DecompressError::Corrupt);
+    }
+
+    let Some(root) = build(&frequencies) else {
+        return Ok(Vec::new());
+    };
+
+    // The length comes from the file, so it can't be trusted to be
+    // sensible. Don't use it to allocate memory up front.
+    let mut output = Vec::new();
+    let mut reader = BitReader::new(input.0);
+    for _ in 0..length {
+        output.push(decode(&root, &mut reader)?);
+    }
+
+    Ok(output)
+}
+
+/// Reads one code, by following it down the tree until reaching a leaf.
+fn decode(root: &Node, reader: &mut BitReader) -> Result<u8, DecompressError> {
+    let mut node = root;
+    loop {
+        match node {
+            Node::Leaf(byte) => {
+                // A tree that's only a leaf uses the code 0, which still
+                // needs reading, even though there's nothing to choose.
+                if std::ptr::eq(node, root) {
+                    reader.read_bit().ok_or(DecompressError::Truncated)?;
+                }
+                return Ok(*byte);
+            }
+            Node::Internal(left, right) => {
+                let bit = reader.read_bit().ok_or(DecompressError::Truncated)?;
+                node = if bit { right } else { left };
+            }
+        }
+    }
+}
+
+/// The part of the file that hasn't been read yet.
+struct Input<'a>(&'a [u8]);
+
+impl<'a> Input<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], DecompressError> {
+        if self.0.len() < count {
+            return Err(DecompressError::Truncated);
+        }
+        let (taken, rest) = self.0.split_at(count);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    fn u64(&mut self) -> Result<u64, DecompressError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+}
+
+impl fmt::Display for DecompressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecompressError::NotCompressed => write!(f, "this isn't a compressed file"),
+            DecompressError::Truncated => write!(f, "the compressed file is cut short"),
+            DecompressError::Corrupt => write!(f, "the compressed file is corrupt"),
+        }
+    }
+}
+
+impl std::error::Error for DecompressError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_the_header() {
+        let compressed = compress(b"aab");
+
+        assert_eq!(&compressed[..4], b"HUF1");
+        assert_eq!(compressed[4..12], 3u64.to_le_bytes());
+        assert_eq!(compressed[12..14], 2u16.to_le_bytes());
+        assert_eq!(compressed[14], b'a');
+        assert_eq!(compressed[15..23], 2u64.to_le_bytes());
+        // The header, and then 3 codes in 1 byte.
+        assert_eq!(compressed.len(), 14 + 9 * 2 + 1);
+    }
+
+    #[test]
+    fn rejects_bad_files() {
+        let compressed = compress(b"hello, world");
+
+        assert_eq!(decompress(b"PK\x03\x04"), Err(DecompressError::NotCompressed));
+        assert_eq!(decompress(&compressed[..20]), Err(DecompressError::Truncated));
+        assert_eq!(decompress(&compressed[..compressed.len() - 1]), Err(DecompressError::Truncated));
+
+        // Change the length, so it doesn't match the counts.
+        let mut corrupt = compressed.clone();
+        corrupt[4] += 1;
+        assert_eq!(decompress(&corrupt), Err(DecompressError::Corrupt));
+    }
+}
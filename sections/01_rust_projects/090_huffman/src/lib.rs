@@ -0,0 +1,11 @@
+// Huffman compression, from start to finish.
+//
+// * tree.rs counts the bytes, and builds the tree and codes.
+// * bits.rs writes and reads single bits.
+// * codec.rs puts them together to compress and decompress files.
+
+pub mod bits;
+pub mod codec;
+pub mod tree;
+
+pub use codec::{compress, decompress, DecompressError};
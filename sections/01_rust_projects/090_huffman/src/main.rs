@@ -0,0 +1,37 @@
+// Compresses and decompresses files.
+//     cargo run -- compress README.md README.md.huf
+//     cargo run -- decompress README.md.huf README.copy.md
+
+use std::fs;
+use std::process;
+
+use project::{compress, decompress};
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [command, input, output] = args.as_slice() else {
+        fail("expected a command and two files");
+    };
+
+    let data = fs::read(input).unwrap_or_else(|err| fail(&format!("couldn't read {input}: {err}")));
+
+    let result = match command.as_str() {
+        "compress" => compress(&data),
+        "decompress" => decompress(&data).unwrap_or_else(|err| fail(&format!("{input}: {err}"))),
+        _ => fail(&format!("unknown command '{command}'")),
+    };
+
+    fs::write(output, &result).unwrap_or_else(|err| fail(&format!("couldn't write {output}: {err}")));
+
+    println!("{input}: {} bytes", data.len());
+    println!("{output}: {} bytes", result.len());
+    if command == "compress" && !data.is_empty() {
+        println!("{:.1}% of the original size", result.len() as f64 / data.len() as f64 * 100.0);
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("Error: {message}");
+    eprintln!("Usage: huffman (compress | decompress) <input> <output>");
+    process::exit(2);
+}
@@ -0,0 +1,167 @@
+// Building a Huffman tree, and the codes that come from it.
+//
+// The idea behind Huffman coding is to give common bytes short codes,
+// and rare bytes long ones. In English text, 'e' might get 3 bits and
+// 'z' might get 11, instead of 8 bits each, so the text gets smaller.
+//
+// The codes come from a binary tree, with a byte at each leaf. The code
+// for a byte is the path from the root to its leaf: 0 for left, and 1
+// for right. No code is the start of another code (since bytes are
+// only at the leaves), so a reader always knows where each code ends.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// How many times each byte appears.
+pub type Frequencies = [u64; 256];
+
+#[derive(Debug, PartialEq)]
+pub enum Node {
+    Leaf(u8),
+    /// The left (0) and right (1) branches.
+    Internal(Box<Node>, Box<Node>),
+}
+
+/// Counts how many times each byte appears in the data.
+pub fn count(data: &[u8]) -> Frequencies {
+    let mut frequencies = [0; 256];
+    for &byte in data {
+        frequencies[byte as usize] += 1;
+    }
+    frequencies
+}
+
+/// A tree that's waiting in the heap to be joined to another one.
+struct Pending {
+    /// How many bytes of the data this tree covers.
+    weight: u64,
+    /// When this tree was made. It's only used to break ties, so that
+    /// the same frequencies always give exactly the same tree. Without
+    /// it, the compressor and decompressor could build different trees!
+    order: usize,
+    node: Node,
+}
+
+// BinaryHeap always gives back the *biggest* item first, but we want the
+// lightest trees. So these compare backwards: a lighter tree counts as
+// "bigger".
+impl Ord for Pending {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (other.weight, other.order).cmp(&(self.weight, self.order))
+    }
+}
+
+impl PartialOrd for Pending {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Pending {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Pending {}
+
+/// Builds the Huffman tree for some frequencies, or returns None if
+/// they're all 0.
+///
+/// Start with a tree for each byte, on its own. Then keep taking the
+/// two lightest trees, and joining them together under a new node,
+/// until there's only one tree left. The rarest bytes are joined first,
+/// so they end up deepest in the tree, with the longest codes.
+pub fn build(frequencies: &Frequencies) -> Option<Node> {
+    let mut heap: BinaryHeap<Pending> = (0..=255u8)
+        .filter(|&byte| frequencies[byte as usize] > 0)
+        .enumerate()
+        .map(|(order, byte)| Pending {
+            weight: frequencies[byte as usize],
+            order,
+            node: Node::Leaf(byte),
+        })
+        .collect();
+    let mut order = heap.len();
+
+    while heap.len() > 1 {
+        let left = heap.pop().unwrap();
+        let right = heap.pop().unwrap();
+
+        heap.push(Pending {
+            weight: left.weight + right.weight,
+            order,
+            node: Node::Internal(Box::new(left.node), Box::new(right.node)),
+        });
+        order += 1;
+    }
+
+    heap.pop().map(|pending| pending.node)
+}
+
+/// Works out the code for every byte in the tree. Bytes that aren't in
+/// the tree get an empty code.
+pub fn codes(root: &Node) -> Vec<Vec<bool>> {
+    let mut codes = vec![Vec::new(); 256];
+
+    match root {
+        // With only one kind of byte, the root is a leaf, and the path
+        // to it is empty. But every byte needs at least one bit, or we
+        // couldn't tell how many there are, so give it the code 0.
+        Node::Leaf(byte) => codes[*byte as usize] = vec![false],
+        Node::Internal(..) => add_codes(root, &mut Vec::new(), &mut codes),
+    }
+
+    codes
+}
+
+fn add_codes(node: &Node, path: &mut Vec<bool>, codes: &mut [Vec<bool>]) {
+    match node {
+        Node::Leaf(byte) => codes[*byte as usize] = path.clone(),
+        Node::Internal(left, right) => {
+            // Go down each side, adding that side's bit to the path, and
+            // taking it off again on the way back up.
+            path.push(false);
+            add_codes(left, path, codes);
+            path.pop();
+
+            path.push(true);
+            add_codes(right, path, codes);
+            path.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn common_bytes_get_short_codes() {
+        let codes = codes(&build(&count(b"aaaaaaaabbbbccd")).unwrap());
+
+        assert_eq!(codes[b'a' as usize].len(), 1);
+        assert_eq!(codes[b'b' as usize].len(), 2);
+        assert_eq!(codes[b'c' as usize].len(), 3);
+        assert_eq!(codes[b'd' as usize].len(), 3);
+        assert!(codes[b'e' as usize].is_empty());
+    }
+
+    #[test]
+    fn no_code_starts_another() {
+        let codes = codes(&build(&count(b"the quick brown fox jumps over the lazy dog")).unwrap());
+        let used: Vec<&Vec<bool>> = codes.iter().filter(|code| !code.is_empty()).collect();
+
+        for (i, a) in used.iter().enumerate() {
+            for (j, b) in used.iter().enumerate() {
+                assert!(i == j || !b.starts_with(a), "{a:?} starts {b:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn handles_tiny_inputs() {
+        assert_eq!(build(&count(b"")), None);
+        assert_eq!(codes(&build(&count(b"zzz")).unwrap())[b'z' as usize], [false]);
+    }
+}
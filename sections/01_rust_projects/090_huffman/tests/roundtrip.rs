@@ -0,0 +1,49 @@
+use proptest::prelude::*;
+
+use project::{compress, decompress};
+
+// Whatever goes in, decompressing what comes out should give it back
+// exactly. proptest tries hundreds of random inputs, and if one fails,
+// shrinks it down to the smallest input that still fails.
+proptest! {
+    #[test]
+    fn round_trips_any_bytes(data in prop::collection::vec(any::<u8>(), 0..2000)) {
+        prop_assert_eq!(decompress(&compress(&data)).unwrap(), data);
+    }
+
+    // Random bytes use every byte about equally, which Huffman coding
+    // can't shrink. Data made of only a few different bytes, with some
+    // much more common than others, is what it's good at, and it makes
+    // much deeper trees.
+    #[test]
+    fn round_trips_skewed_bytes(data in prop::collection::vec(prop::sample::select(b"aaaaaaaabbbbccde".to_vec()), 0..2000)) {
+        let compressed = compress(&data);
+
+        prop_assert_eq!(decompress(&compressed).unwrap(), data.clone());
+        // The header is at most 14 + 5 * 9 bytes, and no byte takes
+        // more than 4 bits.
+        prop_assert!(compressed.len() <= 14 + 5 * 9 + data.len() / 2 + 1);
+    }
+
+    // Decompressing garbage should give an error, not a panic (or use
+    // up all of the computer's memory).
+    #[test]
+    fn never_panics_on_garbage(data in prop::collection::vec(any::<u8>(), 0..200)) {
+        let _ = decompress(&data);
+
+        let mut header = b"HUF1".to_vec();
+        header.extend_from_slice(&data);
+        let _ = decompress(&header);
+    }
+}
+
+#[test]
+fn shrinks_text() {
+    let text = include_str!("../README.md");
+    let compressed = compress(text.as_bytes());
+
+    // Text only uses a few of the 256 bytes, and some of those (like
+    // spaces and e) much more than others, so it always shrinks.
+    assert!(compressed.len() < text.len() * 8 / 10, "{} of {}", compressed.len(), text.len());
+    assert_eq!(decompress(&compressed).unwrap(), text.as_bytes());
+}
@@ -0,0 +1,273 @@
+//! A calculator for expressions written in Reverse Polish Notation (RPN).
+//!
+//! In RPN, operators come after the numbers they work on,
+//! so `1 + 2` is written as `1 2 +`, and `(1 + 2) * 3` is
+//! written as `1 2 + 3 *`.
+//! No parentheses are ever needed.
+//!
+//! ```
+//! assert_eq!(project::evaluate("1 2 + 3 *"), Ok(9));
+//! ```
+
+// The comments above start with //! instead of ///.
+// They document the thing that they're inside of (this crate)
+// instead of the thing that comes after them.
+//
+// The code block in them is a doc test.
+// When you run `cargo test`, Rust compiles and runs every
+// code block in your documentation, so your examples can
+// never go out of date.
+
+use std::fmt;
+
+/// Everything that can go wrong while evaluating an expression.
+#[derive(Debug, PartialEq)]
+pub enum RpnError {
+    /// The expression didn't contain anything.
+    Empty,
+    /// An operator didn't have two numbers to work on.
+    StackUnderflow,
+    /// A token wasn't a number or an operator.
+    UnknownToken(String),
+    /// The expression tried to divide by zero.
+    DivisionByZero,
+    /// A result was too big (or too small) to fit in an i64.
+    Overflow,
+    /// There were numbers left over at the end.
+    /// Contains how many numbers were left.
+    LeftoverValues(usize),
+}
+
+impl fmt::Display for RpnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RpnError::Empty => write!(f, "the expression is empty"),
+            RpnError::StackUnderflow => write!(f, "an operator needs two numbers"),
+            RpnError::UnknownToken(token) => write!(f, "unknown token '{token}'"),
+            RpnError::DivisionByZero => write!(f, "division by zero"),
+            RpnError::Overflow => write!(f, "the result is too large"),
+            RpnError::LeftoverValues(count) => write!(f, "unused numbers left on the stack: {count}"),
+        }
+    }
+}
+
+/// Applies an operator to two numbers.
+///
+/// Returns None if `op` isn't an operator.
+/// Otherwise, returns the result (or an error if the
+/// calculation failed).
+///
+/// ```
+/// use project::{apply, RpnError};
+///
+/// assert_eq!(apply("-", 5, 3), Some(Ok(2)));
+/// assert_eq!(apply("/", 1, 0), Some(Err(RpnError::DivisionByZero)));
+/// assert_eq!(apply("?", 1, 2), None);
+/// ```
+pub fn apply(op: &str, a: i64, b: i64) -> Option<Result<i64, RpnError>> {
+    // The checked_ functions return None instead of
+    // overflowing (or panicking, for division by zero),
+    // so we can turn that into an error.
+    let result = match op {
+        "+" => a.checked_add(b),
+        "-" => a.checked_sub(b),
+        "*" => a.checked_mul(b),
+        "/" if b == 0 => return Some(Err(RpnError::DivisionByZero)),
+        "/" => a.checked_div(b),
+        _ => return None,
+    };
+
+    Some(result.ok_or(RpnError::Overflow))
+}
+
+/// Evaluates an RPN expression.
+///
+/// Tokens are separated by whitespace.
+/// Numbers are pushed onto a stack, and operators (`+`, `-`,
+/// `*`, and `/`) pop two numbers off, then push the result.
+///
+/// ```
+/// assert_eq!(project::evaluate("10 2 /"), Ok(5));
+/// ```
+///
+/// Doc tests can check for errors, too:
+///
+/// ```
+/// use project::{evaluate, RpnError};
+///
+/// assert_eq!(evaluate("1 +"), Err(RpnError::StackUnderflow));
+/// ```
+pub fn evaluate(expression: &str) -> Result<i64, RpnError> {
+    let mut stack = Vec::new();
+
+    for token in expression.split_whitespace() {
+        // If the token is a number, push it.
+        if let Ok(number) = token.parse() {
+            stack.push(number);
+            continue;
+        }
+
+        // Otherwise, it should be an operator.
+        // The second number is on top of the stack, so
+        // we pop it first.
+        let (Some(b), Some(a)) = (stack.pop(), stack.pop()) else {
+            // We check that it's an operator first so that
+            // garbage like "1 x" gives a better error.
+            return Err(match apply(token, 0, 1) {
+                Some(_) => RpnError::StackUnderflow,
+                None => RpnError::UnknownToken(token.into()),
+            });
+        };
+
+        match apply(token, a, b) {
+            Some(result) => stack.push(result?),
+            None => return Err(RpnError::UnknownToken(token.into())),
+        }
+    }
+
+    match stack.len() {
+        0 => Err(RpnError::Empty),
+        1 => Ok(stack[0]),
+        n => Err(RpnError::LeftoverValues(n - 1)),
+    }
+}
+
+// This is a unit test module.
+// #[cfg(test)] means that it's only compiled when running
+// `cargo test`, so it doesn't end up in our final program.
+// Because it's inside this file, it can use private
+// functions too (although we don't have any here).
+
+#[cfg(test)]
+mod tests {
+    // Bring everything from the file above into scope.
+    use super::*;
+
+    // Each function marked with #[test] is a separate test.
+    // If it panics (like when an assert fails), the test fails.
+
+    #[test]
+    fn single_number() {
+        assert_eq!(evaluate("42"), Ok(42));
+    }
+
+    #[test]
+    fn each_operator() {
+        assert_eq!(evaluate("3 4 +"), Ok(7));
+        assert_eq!(evaluate("3 4 -"), Ok(-1));
+        assert_eq!(evaluate("3 4 *"), Ok(12));
+        assert_eq!(evaluate("8 4 /"), Ok(2));
+    }
+
+    #[test]
+    fn nested_expression() {
+        // (5 + 1) * (6 - 2) = 24
+        assert_eq!(evaluate("5 1 + 6 2 - *"), Ok(24));
+    }
+
+    #[test]
+    fn negative_numbers() {
+        assert_eq!(evaluate("-3 -4 *"), Ok(12));
+    }
+
+    #[test]
+    fn extra_whitespace_is_ignored() {
+        assert_eq!(evaluate("  1\n2\t+  "), Ok(3));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(evaluate(""), Err(RpnError::Empty));
+        assert_eq!(evaluate("+"), Err(RpnError::StackUnderflow));
+        assert_eq!(evaluate("1 2 x"), Err(RpnError::UnknownToken("x".into())));
+        assert_eq!(evaluate("1 x"), Err(RpnError::UnknownToken("x".into())));
+        assert_eq!(evaluate("1 0 /"), Err(RpnError::DivisionByZero));
+        assert_eq!(evaluate("1 2 3 +"), Err(RpnError::LeftoverValues(1)));
+    }
+
+    #[test]
+    fn overflow_is_an_error() {
+        let expression = format!("{} 1 +", i64::MAX);
+
+        assert_eq!(evaluate(&expression), Err(RpnError::Overflow));
+    }
+
+    // #[should_panic] flips a test around: it only passes
+    // if the code panics.
+    // This one shows that our errors really are errors.
+    #[test]
+    #[should_panic(expected = "DivisionByZero")]
+    fn unwrapping_an_error_panics() {
+        evaluate("1 0 /").unwrap();
+    }
+
+    // Tests can also return a Result.
+    // If they return an Err, the test fails.
+    // This lets us use ? instead of unwrap.
+    #[test]
+    fn tests_can_use_the_question_mark() -> Result<(), RpnError> {
+        let result = evaluate("2 3 *")?;
+        assert_eq!(result, 6);
+
+        Ok(())
+    }
+}
+
+// Property tests are a different way of testing.
+// Instead of writing out inputs and outputs by hand, we
+// describe a property that should always be true, and
+// proptest generates hundreds of random inputs to check it.
+// If it finds one that fails, it shrinks it down to the
+// smallest input that still fails, so it's easy to debug.
+
+#[cfg(test)]
+mod property_tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    // proptest! is a macro that turns these functions into tests.
+    // `a in any::<i32>()` means "try lots of different i32s for a".
+    // We use i32s so that adding them never overflows an i64.
+    proptest! {
+        #[test]
+        fn addition_matches_rust(a in any::<i32>(), b in any::<i32>()) {
+            let expression = format!("{a} {b} +");
+
+            prop_assert_eq!(evaluate(&expression), Ok(a as i64 + b as i64));
+        }
+
+        #[test]
+        fn addition_is_commutative(a in any::<i64>(), b in any::<i64>()) {
+            // Even if this overflows, it should overflow
+            // both ways.
+            prop_assert_eq!(evaluate(&format!("{a} {b} +")), evaluate(&format!("{b} {a} +")));
+        }
+
+        #[test]
+        fn a_number_evaluates_to_itself(n in any::<i64>()) {
+            prop_assert_eq!(evaluate(&n.to_string()), Ok(n));
+        }
+
+        // Strategies can be more complicated than "any number".
+        // This one generates a list of 1-20 small numbers.
+        #[test]
+        fn summing_a_list(numbers in prop::collection::vec(-1000i64..1000, 1..20)) {
+            // Build "n1 n2 + n3 + n4 + ...".
+            let mut expression = numbers[0].to_string();
+            for n in &numbers[1..] {
+                expression.push_str(&format!(" {n} +"));
+            }
+
+            prop_assert_eq!(evaluate(&expression), Ok(numbers.iter().sum()));
+        }
+
+        // This is one of the most useful properties of all:
+        // no matter what garbage we pass in, it shouldn't panic.
+        // The pattern is a regular expression describing the
+        // strings to generate.
+        #[test]
+        fn never_panics(expression in "[0-9+*/ -]{0,30}") {
+            let _ = evaluate(&expression);
+        }
+    }
+}
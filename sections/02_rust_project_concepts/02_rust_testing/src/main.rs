@@ -0,0 +1,18 @@
+// This program uses the calculator in lib.rs.
+// The interesting part of this lesson is the tests, though:
+// try running `cargo test` to run all of them.
+// You can also run a single test by passing its name,
+// like `cargo test overflow`.
+
+use project::evaluate;
+
+fn main() {
+    let expressions = ["1 2 +", "5 1 + 6 2 - *", "1 0 /", "1 2", "2 x *"];
+
+    for expression in expressions {
+        match evaluate(expression) {
+            Ok(result) => println!("{expression} = {result}"),
+            Err(err) => println!("{expression} -> Error: {err}"),
+        }
+    }
+}
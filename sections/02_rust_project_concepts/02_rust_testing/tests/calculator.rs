@@ -0,0 +1,33 @@
+// This is an integration test.
+// Cargo compiles each file in the tests/ folder as its own
+// crate, which uses our library the same way any other
+// program would.
+// That means we can only use things that are public (pub),
+// and we need to import them with `use project::...`.
+// Integration tests are great for checking that the pieces
+// of a library work together.
+
+use project::{apply, evaluate, RpnError};
+
+#[test]
+fn apply_agrees_with_evaluate() {
+    for op in ["+", "-", "*", "/"] {
+        let expression = format!("12 4 {op}");
+
+        assert_eq!(Some(evaluate(&expression)), apply(op, 12, 4));
+    }
+}
+
+#[test]
+fn long_expression() {
+    // ((2 + 3) * 4 - 6) / 7 = 2
+    assert_eq!(evaluate("2 3 + 4 * 6 - 7 /"), Ok(2));
+}
+
+#[test]
+fn errors_can_be_displayed() {
+    let err = evaluate("1 2 3 +").unwrap_err();
+
+    assert_eq!(err, RpnError::LeftoverValues(1));
+    assert_eq!(err.to_string(), "unused numbers left on the stack: 1");
+}
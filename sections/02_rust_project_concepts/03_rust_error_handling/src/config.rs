@@ -0,0 +1,153 @@
+// This module loads a config file that looks like this:
+//
+// # Comments start with a hash.
+// name = My Server
+// port = 8080
+// workers = 8
+//
+// It's written like a library: it doesn't print anything,
+// and it returns a specific error type that describes
+// exactly what went wrong.
+// That way, whoever uses it can decide how to handle
+// each kind of error.
+
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+// thiserror's derive writes the Display and Error
+// implementations for us.
+// The #[error(...)] attribute on each variant is the message
+// that gets shown, and it can use the variant's fields.
+//
+// #[source] marks the field that holds the error that caused
+// this one.
+// This is how errors get chained together: a ConfigError::Io
+// says "we couldn't read the config", and its source says why
+// (like "No such file or directory").
+
+/// Everything that can go wrong while loading a config.
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    /// The file couldn't be read.
+    #[error("could not read {}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    /// A line wasn't in the `key = value` format.
+    #[error("line {line}: expected `key = value`")]
+    Syntax { line: usize },
+    /// A key that we don't know about was used.
+    #[error("line {line}: unknown key `{key}`")]
+    UnknownKey { line: usize, key: String },
+    /// A value couldn't be parsed.
+    /// The source is whatever error the parser gave us,
+    /// which could be a ParseIntError or a ParseBoolError,
+    /// so we store it as a Box<dyn Error>.
+    #[error("line {line}: invalid value for `{key}`")]
+    InvalidValue {
+        line: usize,
+        key: String,
+        #[source]
+        source: Box<dyn Error + Send + Sync>,
+    },
+    /// A key that has to be set wasn't.
+    #[error("missing required key `{0}`")]
+    MissingKey(&'static str),
+}
+
+/// The settings for our (imaginary) server.
+#[derive(Debug)]
+pub struct Config {
+    /// The server's name.
+    pub name: String,
+    /// The port to listen on.
+    pub port: u16,
+    /// How many worker threads to use.
+    pub workers: u32,
+    /// Whether to print extra debugging information.
+    pub debug: bool,
+}
+
+/// Parses a value, turning any error into a ConfigError::InvalidValue.
+///
+/// `T::Err: Error + Send + Sync + 'static` means that whatever
+/// error parsing T gives us has to be a real error type that we
+/// can put in a Box.
+fn parse_value<T>(line: usize, key: &str, value: &str) -> Result<T, ConfigError>
+where
+    T: std::str::FromStr,
+    T::Err: Error + Send + Sync + 'static,
+{
+    value.parse().map_err(|err| ConfigError::InvalidValue {
+        line,
+        key: key.to_string(),
+        source: Box::new(err),
+    })
+}
+
+/// Parses a config from a string.
+pub fn parse(text: &str) -> Result<Config, ConfigError> {
+    let mut name = None;
+    let mut port = None;
+    // These have default values.
+    let mut workers = 4;
+    let mut debug = false;
+
+    for (i, line) in text.lines().enumerate() {
+        let line_number = i + 1;
+        let line = line.trim();
+
+        // Skip blank lines and comments.
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        // split_once returns None if there's no `=`.
+        // ok_or turns that None into our error, and ?
+        // returns it.
+        let (key, value) = line
+            .split_once('=')
+            .ok_or(ConfigError::Syntax { line: line_number })?;
+        let (key, value) = (key.trim(), value.trim());
+
+        match key {
+            "name" => name = Some(value.to_string()),
+            "port" => port = Some(parse_value(line_number, key, value)?),
+            "workers" => workers = parse_value(line_number, key, value)?,
+            "debug" => debug = parse_value(line_number, key, value)?,
+            _ => {
+                return Err(ConfigError::UnknownKey {
+                    line: line_number,
+                    key: key.to_string(),
+                })
+            }
+        }
+    }
+
+    Ok(Config {
+        name: name.ok_or(ConfigError::MissingKey("name"))?,
+        port: port.ok_or(ConfigError::MissingKey("port"))?,
+        workers,
+        debug,
+    })
+}
+
+/// Loads a config from a file.
+pub fn load(path: impl AsRef<Path>) -> Result<Config, ConfigError> {
+    let path = path.as_ref();
+
+    // We can't use ? directly on the io::Error, since there's no
+    // way to turn it into a ConfigError without the path.
+    // map_err lets us build the error ourselves.
+    let text = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    parse(&text)
+}
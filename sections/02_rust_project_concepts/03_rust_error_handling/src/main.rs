@@ -0,0 +1,121 @@
+// This shows how errors are handled in a real Rust program.
+// config.rs defines its own error type using thiserror, and this
+// file uses anyhow to pass errors around and add context to them.
+//
+// A good rule of thumb is:
+// - Libraries (code that other code uses) should use specific
+//   error types (thiserror), so callers can tell errors apart.
+// - Applications (the program itself) can use anyhow, since
+//   most errors just need to be shown to the user.
+
+mod config;
+
+use anyhow::{bail, ensure, Context};
+use config::{Config, ConfigError};
+
+/// A config that's built into the program, used when
+/// no config file can be found.
+const DEFAULT_CONFIG: &str = "\
+# The built-in config.
+name = Cratecode Server
+port = 8080
+";
+
+/// Some broken configs to show off the different errors.
+const BROKEN_CONFIGS: [&str; 4] = [
+    "name = Broken\nport = eighty",
+    "name = Broken\nport = 80\nthis line is wrong",
+    "name = Broken\ncolor = blue",
+    "port = 80",
+];
+
+// anyhow::Result<T> is short for Result<T, anyhow::Error>.
+// anyhow::Error can hold any error type, so ? works on
+// pretty much anything.
+
+/// Loads a config from a file, falling back to the built-in one
+/// if the file doesn't exist.
+fn load_config(path: &str) -> anyhow::Result<Config> {
+    match config::load(path) {
+        Ok(config) => Ok(config),
+        // If the file doesn't exist, that's fine.
+        // We can look inside our error to check for that case
+        // specifically, and handle everything else as an error.
+        Err(ConfigError::Io { source, .. }) if source.kind() == std::io::ErrorKind::NotFound => {
+            println!("{path} doesn't exist, so the built-in config will be used.");
+
+            // .context() wraps an error with a message
+            // describing what we were trying to do.
+            config::parse(DEFAULT_CONFIG).context("the built-in config is invalid")
+        }
+        // with_context is like context, but it only builds the
+        // message if there's an error.
+        // That saves us from calling format! when everything works.
+        Err(err) => Err(err).with_context(|| format!("failed to load config from {path}")),
+    }
+}
+
+/// Checks that the config makes sense.
+fn validate(config: &Config) -> anyhow::Result<()> {
+    // ensure! returns an error if the condition is false.
+    ensure!(config.workers > 0, "at least one worker is required");
+
+    // bail! returns an error immediately.
+    // Both of these create anyhow errors from a message, so
+    // there's no need to make an error type for one-off checks.
+    if config.port < 1024 && !config.debug {
+        bail!("port {} requires administrator privileges", config.port);
+    }
+
+    Ok(())
+}
+
+/// Loads and validates the config, then "starts" the server.
+fn run() -> anyhow::Result<()> {
+    let config = load_config("server.conf")?;
+    validate(&config).context("the config is invalid")?;
+
+    println!("Starting {} on port {} with {} workers.", config.name, config.port, config.workers);
+
+    Ok(())
+}
+
+/// Shows what each kind of error looks like.
+fn show_broken_configs() {
+    for text in BROKEN_CONFIGS {
+        // Turn the ConfigError into an anyhow::Error with some context.
+        // The ? operator does this for us automatically, but we
+        // want to look at the error here instead of returning it.
+        let err = match config::parse(text).context("failed to parse config") {
+            Ok(_) => continue,
+            Err(err) => err,
+        };
+
+        // {err} only shows the outermost message.
+        println!("\nError: {err}");
+
+        // chain() goes through the error, then its source,
+        // then its source's source, and so on.
+        // This is how we get to the underlying cause.
+        for cause in err.chain().skip(1) {
+            println!("  Caused by: {cause}");
+        }
+
+        // Even though anyhow::Error can hold any error, we can
+        // still get the original error back out with downcast_ref.
+        // It returns None if the error isn't that type.
+        if let Some(ConfigError::MissingKey(key)) = err.downcast_ref::<ConfigError>() {
+            println!("  Hint: add a line like `{key} = ...` to your config.");
+        }
+    }
+}
+
+// main can return a Result, too.
+// If it returns an Err, Rust prints it (with its whole chain of
+// causes) and exits with an error code.
+fn main() -> anyhow::Result<()> {
+    show_broken_configs();
+    println!();
+
+    run()
+}
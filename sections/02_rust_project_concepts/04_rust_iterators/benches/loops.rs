@@ -0,0 +1,37 @@
+// This benchmark compares index loops with iterator chains.
+// Run it with `cargo bench`.
+// Criterion runs each function many times and reports how long
+// it takes on average, along with how much the timing varies.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use project::{longest_word_iter, longest_word_loop, sum_even_squares_iter, sum_even_squares_loop};
+
+fn even_squares(c: &mut Criterion) {
+    let numbers: Vec<u64> = (0..10_000).collect();
+
+    // A benchmark group shows its results side by side.
+    let mut group = c.benchmark_group("sum_even_squares");
+
+    // black_box stops the compiler from optimizing our code away.
+    // Without it, the compiler might notice that the input never
+    // changes and calculate the answer ahead of time.
+    group.bench_function("loop", |b| b.iter(|| sum_even_squares_loop(black_box(&numbers))));
+    group.bench_function("iterator", |b| b.iter(|| sum_even_squares_iter(black_box(&numbers))));
+
+    group.finish();
+}
+
+fn longest_word(c: &mut Criterion) {
+    let text = "the quick brown fox jumps over the lazy dog ".repeat(1_000);
+    let words: Vec<&str> = text.split_whitespace().collect();
+
+    let mut group = c.benchmark_group("longest_word");
+
+    group.bench_function("loop", |b| b.iter(|| longest_word_loop(black_box(&words))));
+    group.bench_function("iterator", |b| b.iter(|| longest_word_iter(black_box(&words))));
+
+    group.finish();
+}
+
+criterion_group!(benches, even_squares, longest_word);
+criterion_main!(benches);
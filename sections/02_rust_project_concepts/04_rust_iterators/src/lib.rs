@@ -0,0 +1,213 @@
+// This file has the custom iterators from this lesson, along
+// with the functions that the benchmark (in benches/loops.rs)
+// compares.
+// main.rs shows them in action.
+
+/// An iterator over the Fibonacci sequence: 0, 1, 1, 2, 3, 5, 8, ...
+///
+/// It stops once the next number would be too big for a u64.
+pub struct Fibonacci {
+    /// The next number to return.
+    /// This is None once we've run out of numbers.
+    current: Option<u64>,
+    /// The number after that.
+    /// This is None once it would overflow.
+    next: Option<u64>,
+}
+
+impl Fibonacci {
+    /// Creates an iterator that starts at 0.
+    pub fn new() -> Fibonacci {
+        Fibonacci {
+            current: Some(0),
+            next: Some(1),
+        }
+    }
+}
+
+// Clippy likes types with a new() function to also
+// implement Default.
+impl Default for Fibonacci {
+    fn default() -> Self {
+        Fibonacci::new()
+    }
+}
+
+// This is all it takes to make an iterator.
+// We say what type of item it produces, and write a next
+// function that returns Some(item), or None when it's done.
+// Every other iterator method (map, filter, take, sum, ...) is
+// built on top of next, so we get all of them for free.
+impl Iterator for Fibonacci {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        // If there's no current number, we're done.
+        // The ? returns None for us.
+        let current = self.current?;
+
+        // checked_add returns None on overflow.
+        // If there's no next number, there's nothing to add.
+        let after = self.next.and_then(|next| current.checked_add(next));
+
+        self.current = self.next;
+        self.next = after;
+
+        Some(current)
+    }
+}
+
+/// An iterator adapter that groups another iterator's items
+/// into Vecs of a fixed size.
+/// The last Vec might be shorter if there aren't enough items.
+///
+/// This is generic over I, so it works with any iterator.
+pub struct Chunked<I: Iterator> {
+    inner: I,
+    size: usize,
+}
+
+impl<I: Iterator> Iterator for Chunked<I> {
+    // The items are Vecs of whatever the inner iterator produces.
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // by_ref lets us take some items without taking
+        // ownership of the whole iterator.
+        let chunk: Vec<_> = self.inner.by_ref().take(self.size).collect();
+
+        if chunk.is_empty() {
+            None
+        } else {
+            Some(chunk)
+        }
+    }
+}
+
+// We'd like to write `iter.chunked(3)`, just like `iter.map(...)`.
+// We can't add methods to the Iterator trait, but we can make
+// our own trait and implement it for every iterator.
+// This is called an extension trait.
+
+/// Adds the `chunked` method to every iterator.
+pub trait ChunkedExt: Iterator + Sized {
+    /// Groups items into Vecs of `size` items each.
+    fn chunked(self, size: usize) -> Chunked<Self> {
+        assert!(size > 0, "chunk size must be at least 1");
+
+        Chunked { inner: self, size }
+    }
+}
+
+// "For every type I that's an Iterator, implement ChunkedExt."
+// This is called a blanket implementation.
+impl<I: Iterator> ChunkedExt for I {}
+
+/// Adds up the squares of the even numbers, using a loop with indices.
+//
+// Clippy would normally warn us that this loop should use an
+// iterator instead, which is exactly what this lesson is about!
+// We turn that warning off here, since we want to compare them.
+#[allow(clippy::needless_range_loop)]
+pub fn sum_even_squares_loop(numbers: &[u64]) -> u64 {
+    let mut total = 0;
+
+    for i in 0..numbers.len() {
+        // Every numbers[i] checks that i is in bounds.
+        if numbers[i].is_multiple_of(2) {
+            total += numbers[i] * numbers[i];
+        }
+    }
+
+    total
+}
+
+/// Adds up the squares of the even numbers, using an iterator chain.
+pub fn sum_even_squares_iter(numbers: &[u64]) -> u64 {
+    numbers.iter().filter(|&&n| n.is_multiple_of(2)).map(|&n| n * n).sum()
+}
+
+/// Finds the length of the longest word, using a loop with indices.
+#[allow(clippy::needless_range_loop)]
+pub fn longest_word_loop(words: &[&str]) -> usize {
+    let mut longest = 0;
+
+    for i in 0..words.len() {
+        if words[i].len() > longest {
+            longest = words[i].len();
+        }
+    }
+
+    longest
+}
+
+/// Finds the length of the longest word, using an iterator chain.
+pub fn longest_word_iter(words: &[&str]) -> usize {
+    words.iter().map(|word| word.len()).max().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fibonacci_starts_at_zero() {
+        let first: Vec<u64> = Fibonacci::new().take(10).collect();
+
+        assert_eq!(first, [0, 1, 1, 2, 3, 5, 8, 13, 21, 34]);
+    }
+
+    #[test]
+    fn fibonacci_stops_before_overflowing() {
+        let mut fibonacci = Fibonacci::new();
+
+        // The 94th number is the last one that fits in a u64.
+        assert_eq!(fibonacci.by_ref().last(), Some(12_200_160_415_121_876_738));
+
+        // Once it's done, it stays done.
+        assert_eq!(fibonacci.next(), None);
+        assert_eq!(fibonacci.next(), None);
+        assert_eq!(Fibonacci::new().count(), 94);
+    }
+
+    #[test]
+    fn chunked_groups_items() {
+        let chunks: Vec<Vec<i32>> = (1..=7).chunked(3).collect();
+
+        // The last chunk gets whatever is left over.
+        assert_eq!(chunks, [vec![1, 2, 3], vec![4, 5, 6], vec![7]]);
+    }
+
+    #[test]
+    fn chunked_empty_iterator() {
+        let mut chunks = std::iter::empty::<i32>().chunked(2);
+
+        assert_eq!(chunks.next(), None);
+        assert_eq!(chunks.next(), None);
+    }
+
+    #[test]
+    fn chunked_stays_done() {
+        let mut chunks = [1, 2].into_iter().chunked(2);
+
+        assert_eq!(chunks.next(), Some(vec![1, 2]));
+        assert_eq!(chunks.next(), None);
+        assert_eq!(chunks.next(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "chunk size must be at least 1")]
+    fn chunked_rejects_zero() {
+        let _ = [1, 2, 3].into_iter().chunked(0);
+    }
+
+    #[test]
+    fn loops_and_iterators_agree() {
+        let numbers: Vec<u64> = (0..100).collect();
+        let words = ["iterators", "are", "zero", "cost"];
+
+        assert_eq!(sum_even_squares_loop(&numbers), sum_even_squares_iter(&numbers));
+        assert_eq!(longest_word_loop(&words), longest_word_iter(&words));
+        assert_eq!(longest_word_iter(&[]), 0);
+    }
+}
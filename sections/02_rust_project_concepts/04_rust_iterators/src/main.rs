@@ -0,0 +1,110 @@
+// This shows some examples of how iterators work in Rust.
+// The custom iterators (Fibonacci and Chunked) are in lib.rs.
+// Run `cargo bench` to compare iterators with index loops.
+
+use project::{ChunkedExt, Fibonacci};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+fn main() {
+    let numbers = vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3];
+
+    // An iterator is anything that can give us items one at a time.
+    // There are three ways to get one from a Vec:
+    // - iter() gives us references to each item (&i32).
+    // - iter_mut() gives us mutable references (&mut i32).
+    // - into_iter() gives us the items themselves (i32), and
+    //   uses up the Vec.
+    // A for loop calls into_iter() for us, so `for n in &numbers`
+    // is the same as `for n in numbers.iter()`.
+
+    // Iterators are lazy.
+    // This line doesn't do anything on its own: map just creates
+    // a new iterator that will double each item when asked.
+    let doubled = numbers.iter().map(|n| n * 2);
+
+    // collect() is what actually runs it.
+    // It can build lots of different collections, so we need
+    // to tell it which one we want.
+    let doubled: Vec<i32> = doubled.collect();
+    println!("Doubled: {doubled:?}");
+
+    // filter keeps the items where the closure returns true.
+    // Its closure gets a reference to each item, and since
+    // iter() already gives us references, we end up with &&i32.
+    // The pattern &&n in the closure's argument strips both away.
+    let big: Vec<_> = numbers.iter().filter(|&&n| n > 3).collect();
+    println!("Bigger than 3: {big:?}");
+
+    // For common ways of combining items, there are shortcuts.
+    // The ::<i32> tells sum what type to add up into.
+    println!("Sum: {}, Max: {:?}", numbers.iter().sum::<i32>(), numbers.iter().max());
+
+    // fold is the most general way to combine items.
+    // It starts with a value, and for each item, calls the
+    // closure with the value so far and the item.
+    // Here, we keep track of the smallest and largest numbers
+    // at the same time, so we only need to go through the list once.
+    let (min, max) = numbers.iter().fold((i32::MAX, i32::MIN), |(min, max), &n| (min.min(n), max.max(n)));
+    println!("Min: {min}, Max: {max}");
+
+    // Collecting into a HashSet removes duplicates.
+    let unique: HashSet<_> = numbers.iter().collect();
+    println!("Unique: {} of {} numbers", unique.len(), numbers.len());
+
+    // Collecting pairs (tuples) makes a map.
+    // enumerate gives us each item along with its index.
+    let positions: HashMap<_, _> = numbers.iter().enumerate().map(|(i, n)| (n, i)).collect();
+    println!("The last 5 is at index {}", positions[&5]);
+
+    // Collecting chars makes a String.
+    let shout: String = "hello".chars().map(|c| c.to_ascii_uppercase()).collect();
+    println!("{shout}");
+
+    // A word count, using a BTreeMap so the words come out sorted.
+    // split_whitespace is an iterator too.
+    let text = "the quick brown fox jumps over the lazy dog the end";
+    let mut counts = BTreeMap::new();
+    for word in text.split_whitespace() {
+        // entry gets the value for a key, or_insert sets it to 0
+        // if it isn't there yet, and we add 1 to whatever it is.
+        *counts.entry(word).or_insert(0) += 1;
+    }
+    println!("Word counts: {counts:?}");
+
+    // Collecting Results into a Result<Vec<_>, _> gives us either
+    // every value, or the first error.
+    let parsed: Result<Vec<i32>, _> = "1 2 3".split(' ').map(|s| s.parse::<i32>()).collect();
+    println!("Parsed: {parsed:?}");
+    let parsed: Result<Vec<i32>, _> = "1 two 3".split(' ').map(|s| s.parse::<i32>()).collect();
+    println!("Parsed: {parsed:?}");
+
+    // Adapters can be chained as much as we want.
+    // zip pairs up two iterators, skip and take cut off the
+    // start and end, and rev goes backwards.
+    let pairs: Vec<_> = numbers.iter().zip(numbers.iter().skip(1)).take(3).collect();
+    println!("Pairs: {pairs:?}");
+    let backwards: Vec<_> = numbers.iter().rev().take(3).collect();
+    println!("Last three, backwards: {backwards:?}");
+
+    // Our own iterators work with all of these, too.
+    // Fibonacci never runs out on its own (well, until it overflows),
+    // so we use take to only get the first 10 items.
+    let fib: Vec<_> = Fibonacci::new().take(10).collect();
+    println!("Fibonacci: {fib:?}");
+
+    // Find the first Fibonacci number over 1,000.
+    println!("First over 1,000: {:?}", Fibonacci::new().find(|&n| n > 1_000));
+
+    // Fibonacci stops right before a u64 would overflow.
+    println!("Fibonacci numbers that fit in a u64: {}", Fibonacci::new().count());
+
+    // chunked comes from our ChunkedExt trait, which we
+    // implemented for every iterator.
+    for chunk in numbers.iter().chunked(4) {
+        println!("Chunk: {chunk:?}");
+    }
+
+    // It works on any iterator, even other adapters.
+    let even_fib_chunks: Vec<_> = Fibonacci::new().filter(|n| n.is_multiple_of(2)).take(7).chunked(3).collect();
+    println!("Even Fibonacci numbers in threes: {even_fib_chunks:?}");
+}
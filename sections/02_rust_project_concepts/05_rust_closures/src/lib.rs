@@ -0,0 +1,214 @@
+//! Closures, and the three traits that describe how they can be called.
+//!
+//! This file has a few functions that return closures, and a small
+//! event registry that stores them.
+//! main.rs shows them in action.
+//!
+//! Some of the doc comments have code blocks marked `compile_fail`.
+//! These are examples of mistakes that Rust won't let us make.
+//! `cargo test` checks that they really do fail to compile, so if
+//! one of them ever starts compiling, the test fails.
+//! The code after `compile_fail` (like `E0373`) is the error we
+//! expect, so a typo in the example can't make it pass by accident.
+//! (Only nightly Rust checks the code, though. Stable just checks
+//! that there's an error.)
+
+use std::collections::HashMap;
+
+/// Returns a closure that adds `amount` to whatever it's given.
+///
+/// The closure needs to keep `amount` around after this function
+/// returns, so it has to be a `move` closure.
+/// Without `move`, it would only borrow `amount`, which is a local
+/// variable that's about to go away:
+///
+/// ```compile_fail,E0373
+/// fn make_adder(amount: i32) -> impl Fn(i32) -> i32 {
+///     // error: closure may outlive the current function,
+///     // but it borrows `amount`
+///     |n| n + amount
+/// }
+/// ```
+///
+/// With `move`, it works:
+///
+/// ```
+/// let add_five = project::make_adder(5);
+/// assert_eq!(add_five(10), 15);
+/// ```
+pub fn make_adder(amount: i32) -> impl Fn(i32) -> i32 {
+    // Every closure has its own type, which doesn't have a name
+    // we can write.
+    // `impl Fn(i32) -> i32` means "some type that can be called
+    // like a function that takes an i32 and returns an i32".
+    move |n| n + amount
+}
+
+/// Returns a closure that counts up by one every time it's called.
+///
+/// This closure changes the `count` that it captured, so it's
+/// `FnMut` instead of `Fn`.
+/// To call it, the variable holding it needs to be `mut`:
+///
+/// ```compile_fail,E0596
+/// let counter = project::make_counter();
+/// // error: cannot borrow `counter` as mutable
+/// counter();
+/// ```
+///
+/// ```
+/// let mut counter = project::make_counter();
+/// assert_eq!(counter(), 1);
+/// assert_eq!(counter(), 2);
+/// ```
+pub fn make_counter() -> impl FnMut() -> u32 {
+    let mut count = 0;
+
+    move || {
+        count += 1;
+        count
+    }
+}
+
+/// Calls `f` twice.
+///
+/// Taking `impl Fn` means that `f` can be called as many times as
+/// we want, and it won't change anything when we do.
+/// A closure that changes what it captured is only `FnMut`, so it
+/// can't be passed in here:
+///
+/// ```compile_fail,E0594
+/// let mut total = 0;
+/// // error: cannot assign to `total`, as it is a captured
+/// // variable in a `Fn` closure
+/// project::call_twice(|| total += 1);
+/// ```
+pub fn call_twice(f: impl Fn()) {
+    f();
+    f();
+}
+
+/// Calls `f` once, and returns what it returned.
+///
+/// `FnOnce` is the most forgiving of the three traits, since we
+/// promise to only call `f` a single time.
+/// Every closure can be called at least once, so any closure can
+/// be passed in here, even one that gives away something it owns:
+///
+/// ```
+/// let name = String::from("Ferris");
+/// let greeting = project::call_once(move || name + " says hi");
+/// assert_eq!(greeting, "Ferris says hi");
+/// ```
+///
+/// A closure like that can only be called once.
+/// After the first call, `name` has been moved out of it, so
+/// there's nothing left for a second call to use:
+///
+/// ```compile_fail,E0382
+/// let name = String::from("Ferris");
+/// let take_name = move || name;
+/// let first = take_name();
+/// // error: use of moved value: `take_name`
+/// let second = take_name();
+/// ```
+pub fn call_once<T>(f: impl FnOnce() -> T) -> T {
+    f()
+}
+
+/// A callback that can run every time an event happens.
+///
+/// Each closure has its own type (and size), so to store
+/// different closures in the same Vec, we put them in a Box
+/// and use `dyn FnMut`, which means "any type that's FnMut".
+type Handler = Box<dyn FnMut(&str)>;
+
+/// A callback that only runs the next time an event happens.
+type OnceHandler = Box<dyn FnOnce(&str)>;
+
+/// Keeps track of callbacks for named events, and calls them
+/// when an event is emitted.
+///
+/// Handlers added with `on` run every time. They're `FnMut`, so
+/// they can change what they captured (like a counter).
+///
+/// Handlers added with `once` only run a single time, and are then
+/// removed. Since we'll never call them again, they only need to
+/// be `FnOnce`, so they're allowed to move things out of what they
+/// captured.
+///
+/// ```
+/// use std::cell::Cell;
+/// use std::rc::Rc;
+///
+/// let clicks = Rc::new(Cell::new(0));
+/// let counter = Rc::clone(&clicks);
+///
+/// let mut events = project::EventRegistry::new();
+/// events.on("click", move |_| counter.set(counter.get() + 1));
+///
+/// events.emit("click", "button");
+/// events.emit("click", "button");
+/// assert_eq!(clicks.get(), 2);
+/// ```
+///
+/// The `'static` in `on` and `once` means that handlers can't
+/// borrow local variables, since the registry might outlive them:
+///
+/// ```compile_fail,E0373
+/// let mut clicks = 0;
+/// let mut events = project::EventRegistry::new();
+/// // error: closure may outlive the current function,
+/// // but it borrows `clicks`
+/// events.on("click", |_| clicks += 1);
+/// ```
+#[derive(Default)]
+pub struct EventRegistry {
+    handlers: HashMap<String, Vec<Handler>>,
+    once_handlers: HashMap<String, Vec<OnceHandler>>,
+}
+
+impl EventRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> EventRegistry {
+        EventRegistry::default()
+    }
+
+    /// Adds a handler that runs every time `event` is emitted.
+    pub fn on(&mut self, event: &str, handler: impl FnMut(&str) + 'static) {
+        self.handlers.entry(event.to_string()).or_default().push(Box::new(handler));
+    }
+
+    /// Adds a handler that runs the next time `event` is emitted.
+    pub fn once(&mut self, event: &str, handler: impl FnOnce(&str) + 'static) {
+        self.once_handlers.entry(event.to_string()).or_default().push(Box::new(handler));
+    }
+
+    /// Calls every handler for `event`, passing `data` to each one.
+    /// Returns how many handlers were called.
+    pub fn emit(&mut self, event: &str, data: &str) -> usize {
+        let mut called = 0;
+
+        // Calling an FnMut needs a mutable reference to it,
+        // so we loop over the handlers with iter_mut.
+        if let Some(handlers) = self.handlers.get_mut(event) {
+            for handler in handlers.iter_mut() {
+                handler(data);
+                called += 1;
+            }
+        }
+
+        // Calling an FnOnce uses it up, so we need to own it.
+        // remove takes the Vec out of the map, and into_iter
+        // gives us each handler by value.
+        // Once they've all been called, they're gone for good.
+        if let Some(handlers) = self.once_handlers.remove(event) {
+            for handler in handlers {
+                handler(data);
+                called += 1;
+            }
+        }
+
+        called
+    }
+}
@@ -0,0 +1,113 @@
+// This shows some examples of how closures work in Rust.
+// The functions that take and return closures, along with the
+// event registry, are in lib.rs.
+// Run `cargo test` to check the compile_fail examples there.
+
+use project::{call_once, call_twice, make_adder, make_counter, EventRegistry};
+use std::thread;
+
+fn main() {
+    // A closure is a function that we can write inline.
+    // The arguments go between the pipes, and the body comes after.
+    // Types are usually figured out for us.
+    let square = |n: i32| n * n;
+    println!("5 squared is {}", square(5));
+
+    // Unlike regular functions, closures can use variables from
+    // the code around them. This is called capturing.
+    // Rust captures each variable in the least demanding way that
+    // works for what the closure does with it.
+
+    // This closure only reads greeting, so it captures a
+    // shared reference (&greeting).
+    // We can still use greeting ourselves while it exists.
+    let greeting = String::from("Hello");
+    let greet = |name: &str| println!("{greeting}, {name}!");
+    greet("Ferris");
+    println!("greeting is still usable: {greeting}");
+
+    // This closure changes total, so it captures a mutable
+    // reference (&mut total).
+    // The closure itself has to be mut to be called, and while
+    // it exists, nothing else can use total.
+    let mut total = 0;
+    let mut add = |n: i32| total += n;
+    add(3);
+    add(4);
+    // Now that we're done with add, we can use total again.
+    println!("Total: {total}");
+
+    // `move` makes a closure take ownership of what it captures,
+    // instead of borrowing it.
+    // This is needed when the closure might outlive the variables,
+    // like when it runs on another thread: the thread could keep
+    // running after this function has returned.
+    let text = String::from("closures are neat");
+    let handle = thread::spawn(move || text.to_uppercase());
+    // text has been moved into the closure, so we can't use it here.
+    println!("From a thread: {}", handle.join().unwrap());
+
+    // Closures can be returned from functions, too.
+    let add_five = make_adder(5);
+    let add_ten = make_adder(10);
+    println!("add_five(1) = {}, add_ten(1) = {}", add_five(1), add_ten(1));
+
+    // Each counter has its own count inside of it.
+    let mut first = make_counter();
+    let mut second = make_counter();
+    first();
+    first();
+    println!("first: {}, second: {}", first(), second());
+
+    // Lots of standard library functions take closures.
+    let mut names = vec!["Charlie", "al", "Bob"];
+    names.sort_by_key(|name| name.to_lowercase());
+    println!("Sorted: {names:?}");
+
+    // Every closure implements one or more of these traits:
+    // - Fn can be called any number of times, and only reads
+    //   what it captured.
+    // - FnMut can be called any number of times, and might
+    //   change what it captured.
+    // - FnOnce can be called at least once. Every closure is
+    //   FnOnce, but closures that give away something they
+    //   captured are ONLY FnOnce.
+    call_twice(|| println!("Called!"));
+
+    let name = String::from("Ferris");
+    // This closure gives name away (by returning it), so it
+    // can only ever be called once.
+    let message = call_once(move || name + " was here");
+    println!("{message}");
+
+    // The event registry stores closures to call later.
+    let mut events = EventRegistry::new();
+
+    // This handler prints every message.
+    events.on("message", |text| println!("  New message: {text}"));
+
+    // This handler has its own count, which it changes each time,
+    // so it's FnMut.
+    let mut seen = 0;
+    events.on("message", move |_| {
+        seen += 1;
+        println!("  That's {seen} message(s) so far.");
+    });
+
+    // This handler moves its String into a Vec, so it's FnOnce.
+    // That's fine, since once handlers only run once.
+    let farewell = String::from("Goodbye!");
+    events.once("message", move |_| {
+        let log = vec![farewell];
+        println!("  (only shown once) {log:?}");
+    });
+
+    for text in ["hi", "how are you?", "bye"] {
+        println!("Emitting \"{text}\":");
+        let called = events.emit("message", text);
+        println!("  {called} handler(s) called.");
+    }
+
+    // Nobody is listening for this event.
+    println!("Handlers for \"unknown\": {}", events.emit("unknown", ""));
+}
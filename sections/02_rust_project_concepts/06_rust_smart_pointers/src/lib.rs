@@ -0,0 +1,220 @@
+// This file has two small data structures that each need a
+// different kind of smart pointer:
+// - Expr, a math expression tree, which uses Box.
+// - Node, a folder tree where children can find their parent,
+//   which uses Rc, RefCell, and Weak.
+// main.rs shows them in action, along with Arc.
+
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+/// A math expression, like `(1 + 2) * 3`.
+///
+/// An Expr can contain other Exprs, so it's a recursive type.
+/// If we wrote `Add(Expr, Expr)`, an Expr would have to contain
+/// two whole Exprs, which would each contain two more, and so on
+/// forever, so Rust couldn't figure out how big an Expr is.
+/// A Box is just a pointer to some data on the heap, so it's
+/// always the same size, no matter what it points to.
+#[derive(Debug)]
+pub enum Expr {
+    Num(i64),
+    Add(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Works out the value of the expression.
+    pub fn eval(&self) -> i64 {
+        match self {
+            Expr::Num(n) => *n,
+            // A Box can be used just like the thing inside of it,
+            // so we can call eval on it directly.
+            Expr::Add(a, b) => a.eval() + b.eval(),
+            Expr::Mul(a, b) => a.eval() * b.eval(),
+        }
+    }
+}
+
+/// A folder in a tree of folders.
+///
+/// Each folder owns its children, and can also look up its parent.
+/// This needs a few different smart pointers:
+/// - Children are stored as `Rc<Node>`, so that both the parent and
+///   whoever else is using a folder can own it at the same time.
+/// - The parent is stored as a `Weak<Node>`. If children owned their
+///   parent with an Rc, and parents owned their children, the two
+///   would keep each other alive forever (a reference cycle), and
+///   neither would ever be freed.
+/// - Both are wrapped in a RefCell, since we need to change them
+///   after the Node has been created, but Rc only ever gives us
+///   shared (&) references.
+#[derive(Debug)]
+pub struct Node {
+    pub name: String,
+    parent: RefCell<Weak<Node>>,
+    children: RefCell<Vec<Rc<Node>>>,
+}
+
+impl Node {
+    /// Creates a folder with no parent and no children.
+    pub fn new(name: &str) -> Rc<Node> {
+        Rc::new(Node {
+            name: name.to_string(),
+            // Weak::new() is a Weak that doesn't point to anything.
+            parent: RefCell::new(Weak::new()),
+            children: RefCell::new(Vec::new()),
+        })
+    }
+
+    /// Adds `child` to this folder, and makes this folder its parent.
+    pub fn add_child(self: &Rc<Self>, child: Rc<Node>) {
+        // borrow_mut gives us a mutable reference to what's inside
+        // the RefCell.
+        // Rc::downgrade makes a Weak pointer, which doesn't
+        // keep this folder alive.
+        *child.parent.borrow_mut() = Rc::downgrade(self);
+
+        self.children.borrow_mut().push(child);
+    }
+
+    /// Returns this folder's parent, if it has one, and it still exists.
+    pub fn parent(&self) -> Option<Rc<Node>> {
+        // A Weak doesn't know if what it points to is still around,
+        // so we have to ask.
+        // upgrade gives us an Rc if it is, or None if it's been freed.
+        self.parent.borrow().upgrade()
+    }
+
+    /// Returns the children of this folder.
+    pub fn children(&self) -> Vec<Rc<Node>> {
+        // Cloning an Rc doesn't copy the Node, it just makes
+        // another pointer to it (and adds 1 to its count).
+        self.children.borrow().iter().map(Rc::clone).collect()
+    }
+
+    /// Returns the full path of this folder, like `/home/ferris`.
+    pub fn path(&self) -> String {
+        let mut names = vec![self.name.clone()];
+
+        let mut current = self.parent();
+        while let Some(node) = current {
+            names.push(node.name.clone());
+            current = node.parent();
+        }
+
+        names.reverse();
+        names.join("/")
+    }
+
+    /// Counts this folder and every folder under it.
+    pub fn count(&self) -> usize {
+        1 + self.children.borrow().iter().map(|child| child.count()).sum::<usize>()
+    }
+}
+
+// Rc::strong_count tells us how many Rcs point to a value,
+// and Rc::weak_count tells us how many Weaks do.
+// The value is freed once the strong count reaches 0, no matter
+// how many Weaks are left.
+// These tests check that the counts are what we expect.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expr_evaluates() {
+        // (1 + 2) * 3
+        let expr = Expr::Mul(
+            Box::new(Expr::Add(Box::new(Expr::Num(1)), Box::new(Expr::Num(2)))),
+            Box::new(Expr::Num(3)),
+        );
+
+        assert_eq!(expr.eval(), 9);
+    }
+
+    #[test]
+    fn new_node_has_one_owner() {
+        let root = Node::new("");
+
+        assert_eq!(Rc::strong_count(&root), 1);
+        assert_eq!(Rc::weak_count(&root), 0);
+        assert!(root.parent().is_none());
+    }
+
+    #[test]
+    fn children_are_shared() {
+        let root = Node::new("");
+        let home = Node::new("home");
+
+        // Cloning an Rc adds an owner.
+        root.add_child(Rc::clone(&home));
+        assert_eq!(Rc::strong_count(&home), 2);
+
+        // children() clones every Rc, so while the Vec
+        // exists, home has a third owner.
+        let children = root.children();
+        assert_eq!(Rc::strong_count(&home), 3);
+
+        // Dropping the Vec drops its Rcs.
+        drop(children);
+        assert_eq!(Rc::strong_count(&home), 2);
+    }
+
+    #[test]
+    fn parent_links_are_weak() {
+        let root = Node::new("");
+        let home = Node::new("home");
+        let ferris = Node::new("ferris");
+        root.add_child(Rc::clone(&home));
+        home.add_child(Rc::clone(&ferris));
+
+        // Each child points to its parent with a Weak,
+        // which doesn't count as an owner.
+        assert_eq!(Rc::strong_count(&root), 1);
+        assert_eq!(Rc::weak_count(&root), 1);
+        assert_eq!(Rc::strong_count(&home), 2);
+        assert_eq!(Rc::weak_count(&home), 1);
+
+        assert_eq!(ferris.path(), "/home/ferris");
+        assert_eq!(root.count(), 3);
+    }
+
+    #[test]
+    fn parent_can_be_freed() {
+        let home = Node::new("home");
+        let ferris = Node::new("ferris");
+        home.add_child(Rc::clone(&ferris));
+
+        // Keep a Weak to home, so we can check whether it's been freed.
+        let weak_home = Rc::downgrade(&home);
+        assert_eq!(ferris.parent().unwrap().name, "home");
+
+        // home was only owned by this variable.
+        // The Weak in ferris doesn't keep it alive, so this frees it,
+        // even though ferris still points back to it.
+        drop(home);
+        assert!(weak_home.upgrade().is_none());
+        assert!(ferris.parent().is_none());
+
+        // home owned one of the Rcs to ferris, and that Rc was
+        // dropped along with it.
+        assert_eq!(Rc::strong_count(&ferris), 1);
+        assert_eq!(ferris.path(), "ferris");
+    }
+
+    #[test]
+    fn upgrade_adds_an_owner() {
+        let home = Node::new("home");
+        let ferris = Node::new("ferris");
+        home.add_child(Rc::clone(&ferris));
+
+        // upgrade turns a Weak into an Rc, so while we're holding
+        // onto the parent, it has an extra owner.
+        let parent = ferris.parent().unwrap();
+        assert_eq!(Rc::strong_count(&home), 2);
+
+        drop(parent);
+        assert_eq!(Rc::strong_count(&home), 1);
+    }
+}
@@ -0,0 +1,100 @@
+// This shows each of Rust's most common smart pointers.
+// The data structures that use them are in lib.rs.
+// Run `cargo test` to see the tests that check reference counts.
+
+use project::{Expr, Node};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::thread;
+
+fn main() {
+    // Box puts a value on the heap.
+    // Its main use is for recursive types, like Expr, which
+    // couldn't exist without it.
+    // (1 + 2) * (3 + 4)
+    let expr = Expr::Mul(
+        Box::new(Expr::Add(Box::new(Expr::Num(1)), Box::new(Expr::Num(2)))),
+        Box::new(Expr::Add(Box::new(Expr::Num(3)), Box::new(Expr::Num(4)))),
+    );
+    println!("(1 + 2) * (3 + 4) = {}", expr.eval());
+
+    // Rc (Reference Counted) lets a value have more than one owner.
+    // It keeps a count of how many Rcs point to the value, and
+    // frees the value once the count reaches 0.
+    let root = Node::new("");
+    let home = Node::new("home");
+    let ferris = Node::new("ferris");
+    let projects = Node::new("projects");
+
+    // Rc::clone makes a new pointer to the same Node.
+    // Now both the home variable and root own the home Node.
+    root.add_child(Rc::clone(&home));
+    home.add_child(Rc::clone(&ferris));
+    ferris.add_child(Rc::clone(&projects));
+
+    println!("\nThere are {} folders.", root.count());
+    println!("projects is at {}", projects.path());
+    println!(
+        "home has {} owners, and {} Weak pointers to it.",
+        Rc::strong_count(&home),
+        Rc::weak_count(&home)
+    );
+
+    // Children point to their parents with Weak, which doesn't
+    // count as owning them.
+    // Dropping our home variable doesn't free the home Node,
+    // since root still owns it.
+    drop(home);
+    println!("home is still alive, since root owns it: {}", ferris.parent().is_some());
+
+    // Dropping root frees root, which drops its Rc to home,
+    // which frees home too, even though ferris still points
+    // back to it.
+    drop(root);
+    println!("After dropping root, ferris's parent exists: {}", ferris.parent().is_some());
+    println!("ferris is now at {}", ferris.path());
+
+    // RefCell lets us change something through a shared reference.
+    // It still enforces the borrowing rules (any number of readers,
+    // or one writer), but it checks them while the program runs,
+    // instead of at compile time.
+    let numbers = RefCell::new(vec![1, 2, 3]);
+    numbers.borrow_mut().push(4);
+
+    {
+        let reader = numbers.borrow();
+        // While reader exists, borrowing mutably would break the
+        // rules, so borrow_mut would panic.
+        // try_borrow_mut returns an error instead.
+        println!("\nReading {:?}, can we write? {}", *reader, numbers.try_borrow_mut().is_ok());
+    }
+
+    // Now that reader is gone, we can.
+    println!("After the reader is gone, can we write? {}", numbers.try_borrow_mut().is_ok());
+
+    // Arc (Atomic Reference Counted) is Rc for threads.
+    // Rc updates its count in a way that isn't safe if two threads
+    // do it at once, so Rust won't let us send an Rc to another thread.
+    // Arc uses atomic operations for its count, which is a bit
+    // slower, but safe.
+    let shared = Arc::new(expr);
+    let mut handles = Vec::new();
+
+    for i in 1..=3 {
+        let expr = Arc::clone(&shared);
+        handles.push(thread::spawn(move || expr.eval() * i));
+    }
+
+    // Arc only gives us shared references, so the threads can only
+    // read the expression.
+    // To change shared data from multiple threads, we'd wrap it in
+    // a Mutex as well: Arc<Mutex<T>> is the thread-safe version of
+    // Rc<RefCell<T>>.
+    for handle in handles {
+        println!("A thread got {}", handle.join().unwrap());
+    }
+
+    // Every thread has finished and dropped its Arc.
+    println!("Owners of the expression now: {}", Arc::strong_count(&shared));
+}
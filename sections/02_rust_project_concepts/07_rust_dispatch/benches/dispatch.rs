@@ -0,0 +1,45 @@
+// This benchmark compares static dispatch with dynamic dispatch.
+// Run it with `cargo bench`.
+// Both versions run the same plugins over the same numbers, so the
+// only difference is how the calls to apply are made.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use project::{run_dynamic, run_static, Add, Clamp, Multiply, Plugin, PluginHost};
+
+fn pipeline(c: &mut Criterion) {
+    let values: Vec<i64> = (0..10_000).collect();
+
+    let pipeline = Add(1).then(Multiply(3)).then(Clamp { min: 0, max: 20_000 });
+
+    let mut host = PluginHost::new();
+    host.register(Box::new(Add(1)));
+    host.register(Box::new(Multiply(3)));
+    host.register(Box::new(Clamp { min: 0, max: 20_000 }));
+
+    let mut group = c.benchmark_group("pipeline");
+
+    // black_box stops the compiler from calculating the answer
+    // ahead of time.
+    group.bench_function("static", |b| b.iter(|| run_static(black_box(&pipeline), black_box(&values))));
+    group.bench_function("dynamic", |b| b.iter(|| run_dynamic(black_box(&host), black_box(&values))));
+
+    group.finish();
+}
+
+fn single_plugin(c: &mut Criterion) {
+    let values: Vec<i64> = (0..10_000).collect();
+    let plugin = Multiply(3);
+
+    // The same plugin, called directly or through a &dyn Plugin.
+    let mut group = c.benchmark_group("single_plugin");
+
+    group.bench_function("static", |b| b.iter(|| run_static(black_box(&plugin), black_box(&values))));
+    group.bench_function("dynamic", |b| {
+        b.iter(|| run_dynamic(black_box(&plugin as &dyn Plugin), black_box(&values)))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, pipeline, single_plugin);
+criterion_main!(benches);
@@ -0,0 +1,205 @@
+//! A tiny plugin system, built two ways.
+//!
+//! Each plugin takes a number and gives back a new one.
+//! Plugins can be combined with static dispatch (generics, using
+//! `Plugin::then`), or with dynamic dispatch (trait objects, using
+//! `PluginHost`).
+//! main.rs shows both, and benches/dispatch.rs compares their speed.
+//!
+//! Not every trait can be turned into a trait object.
+//! A trait with a method that returns `Self` can't be, since the
+//! caller of a `dyn` method has no idea what type `Self` is, or
+//! how big it is:
+//!
+//! ```compile_fail,E0038
+//! trait Duplicate {
+//!     fn duplicate(&self) -> Self;
+//! }
+//!
+//! // error: the trait `Duplicate` is not dyn compatible
+//! let plugins: Vec<Box<dyn Duplicate>> = Vec::new();
+//! ```
+//!
+//! Neither can a trait with a generic method, since every type it
+//! could be called with would need its own entry in the vtable:
+//!
+//! ```compile_fail,E0038
+//! trait Logger {
+//!     fn log<T: std::fmt::Debug>(&self, value: T);
+//! }
+//!
+//! // error: the trait `Logger` is not dyn compatible
+//! let logger: Option<Box<dyn Logger>> = None;
+//! ```
+//!
+//! Adding `where Self: Sized` to a method leaves it out of the
+//! trait object, which makes the rest of the trait usable as one.
+//! That's how `Plugin::then` works.
+
+/// Something that transforms a number.
+pub trait Plugin {
+    /// The name of the plugin, for printing.
+    fn name(&self) -> String;
+
+    /// Transforms a number.
+    fn apply(&self, value: i64) -> i64;
+
+    /// Combines this plugin with another one, which runs after it.
+    ///
+    /// This is generic, and returns `Self`, so it would normally
+    /// stop us from using `dyn Plugin`.
+    /// `where Self: Sized` means that it can only be called on a
+    /// concrete type, so it isn't part of `dyn Plugin`, and the
+    /// rest of the trait still works as a trait object.
+    fn then<P: Plugin>(self, next: P) -> Then<Self, P>
+    where
+        Self: Sized,
+    {
+        Then { first: self, second: next }
+    }
+}
+
+/// Adds a fixed amount.
+pub struct Add(pub i64);
+
+impl Plugin for Add {
+    fn name(&self) -> String {
+        format!("add {}", self.0)
+    }
+
+    fn apply(&self, value: i64) -> i64 {
+        // wrapping_add wraps around instead of panicking on overflow.
+        value.wrapping_add(self.0)
+    }
+}
+
+/// Multiplies by a fixed amount.
+pub struct Multiply(pub i64);
+
+impl Plugin for Multiply {
+    fn name(&self) -> String {
+        format!("multiply by {}", self.0)
+    }
+
+    fn apply(&self, value: i64) -> i64 {
+        value.wrapping_mul(self.0)
+    }
+}
+
+/// Keeps the number between `min` and `max`.
+pub struct Clamp {
+    pub min: i64,
+    pub max: i64,
+}
+
+impl Plugin for Clamp {
+    fn name(&self) -> String {
+        format!("clamp to {}..={}", self.min, self.max)
+    }
+
+    fn apply(&self, value: i64) -> i64 {
+        value.clamp(self.min, self.max)
+    }
+}
+
+/// Two plugins, one after the other.
+///
+/// `Add(1).then(Multiply(2))` has the type `Then<Add, Multiply>`.
+/// Every combination of plugins is a different type, and the
+/// compiler makes a separate copy of `apply` for each one
+/// (this is called monomorphization).
+/// Since it knows exactly which plugins are inside, it can inline
+/// their code, as if we'd written `(value + 1) * 2` by hand.
+pub struct Then<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A: Plugin, B: Plugin> Plugin for Then<A, B> {
+    fn name(&self) -> String {
+        format!("{}, then {}", self.first.name(), self.second.name())
+    }
+
+    fn apply(&self, value: i64) -> i64 {
+        self.second.apply(self.first.apply(value))
+    }
+}
+
+/// Runs a list of plugins, one after the other.
+///
+/// The plugins are stored as `Box<dyn Plugin>`, so they can each be
+/// a different type, and the list can be decided while the program
+/// is running.
+/// The cost is that the compiler doesn't know which `apply` to call,
+/// so every call has to look it up in the plugin's vtable (a table
+/// of function pointers that every trait object carries around).
+#[derive(Default)]
+pub struct PluginHost {
+    plugins: Vec<Box<dyn Plugin>>,
+}
+
+impl PluginHost {
+    /// Creates a host with no plugins.
+    pub fn new() -> PluginHost {
+        PluginHost::default()
+    }
+
+    /// Adds a plugin to the end of the list.
+    pub fn register(&mut self, plugin: Box<dyn Plugin>) {
+        self.plugins.push(plugin);
+    }
+
+    /// Returns the names of every plugin, in order.
+    pub fn names(&self) -> Vec<String> {
+        self.plugins.iter().map(|plugin| plugin.name()).collect()
+    }
+}
+
+// PluginHost is a plugin too, so it can be used anywhere
+// a plugin can, even inside another PluginHost.
+impl Plugin for PluginHost {
+    fn name(&self) -> String {
+        self.names().join(", then ")
+    }
+
+    fn apply(&self, value: i64) -> i64 {
+        self.plugins.iter().fold(value, |value, plugin| plugin.apply(value))
+    }
+}
+
+/// Creates a plugin from a description like `add 5` or `clamp 0 100`.
+///
+/// The type of plugin isn't known until the program runs, so this
+/// has to return a trait object.
+/// A generic function couldn't do this: `impl Plugin` means one
+/// specific type, chosen when the program is compiled.
+pub fn parse_plugin(text: &str) -> Option<Box<dyn Plugin>> {
+    let mut parts = text.split_whitespace();
+    let kind = parts.next()?;
+    let args: Vec<i64> = parts.map(|part| part.parse().ok()).collect::<Option<_>>()?;
+
+    match (kind, args.as_slice()) {
+        ("add", &[amount]) => Some(Box::new(Add(amount))),
+        ("multiply", &[amount]) => Some(Box::new(Multiply(amount))),
+        ("clamp", &[min, max]) if min <= max => Some(Box::new(Clamp { min, max })),
+        _ => None,
+    }
+}
+
+/// Runs every value through a plugin and adds up the results,
+/// using static dispatch.
+///
+/// The compiler makes a copy of this function for every type `P`
+/// it's called with.
+pub fn run_static<P: Plugin>(plugin: &P, values: &[i64]) -> i64 {
+    values.iter().map(|&value| plugin.apply(value)).fold(0, i64::wrapping_add)
+}
+
+/// Runs every value through a plugin and adds up the results,
+/// using dynamic dispatch.
+///
+/// There's only one copy of this function, which works with every
+/// plugin by calling `apply` through the vtable.
+pub fn run_dynamic(plugin: &dyn Plugin, values: &[i64]) -> i64 {
+    values.iter().map(|&value| plugin.apply(value)).fold(0, i64::wrapping_add)
+}
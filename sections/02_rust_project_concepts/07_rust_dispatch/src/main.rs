@@ -0,0 +1,58 @@
+// This shows the same plugins being combined with static
+// dispatch (generics) and dynamic dispatch (trait objects).
+// The plugins themselves are in lib.rs.
+// Run `cargo bench` to compare the speed of the two.
+
+use project::{parse_plugin, run_dynamic, run_static, Add, Clamp, Multiply, Plugin, PluginHost};
+
+fn main() {
+    let values: Vec<i64> = (1..=5).collect();
+
+    // Static dispatch: the type of pipeline is
+    // Then<Then<Add, Multiply>, Clamp>, and the compiler knows
+    // exactly which apply functions to call.
+    let pipeline = Add(1).then(Multiply(10)).then(Clamp { min: 0, max: 45 });
+    println!("Static: {}", pipeline.name());
+    for value in &values {
+        println!("  {value} -> {}", pipeline.apply(*value));
+    }
+
+    // Dynamic dispatch: each plugin is a Box<dyn Plugin>, and
+    // the right apply function is looked up while the program runs.
+    let mut host = PluginHost::new();
+    host.register(Box::new(Add(1)));
+    host.register(Box::new(Multiply(10)));
+    host.register(Box::new(Clamp { min: 0, max: 45 }));
+    println!("Dynamic: {}", host.name());
+    for value in &values {
+        println!("  {value} -> {}", host.apply(*value));
+    }
+
+    // Both give the same answers.
+    println!("Sums: {} and {}", run_static(&pipeline, &values), run_dynamic(&host, &values));
+
+    // Where trait objects really shine is when we don't know which
+    // types we'll need until the program runs, like when plugins
+    // come from a config file or from the user.
+    let config = "multiply 3\nadd -2\nclamp 0 10\nexplode 9000";
+    let mut host = PluginHost::new();
+    for line in config.lines() {
+        match parse_plugin(line) {
+            Some(plugin) => host.register(plugin),
+            None => println!("Skipping unknown plugin: {line}"),
+        }
+    }
+    println!("From the config: {:?}", host.names());
+    println!("  7 -> {}", host.apply(7));
+
+    // The two approaches have different costs.
+    // Static dispatch makes a copy of the code for every type it's
+    // used with, so it can make programs bigger, but each copy is
+    // as fast as if we'd written it by hand.
+    // Dynamic dispatch has one copy of the code, but trait objects
+    // carry an extra pointer to their vtable, and every call has to
+    // go through it.
+    println!("\nSize of &Add: {} bytes", size_of::<&Add>());
+    println!("Size of &dyn Plugin: {} bytes (data pointer + vtable pointer)", size_of::<&dyn Plugin>());
+    println!("Size of the static pipeline: {} bytes (no pointers at all)", size_of_val(&pipeline));
+}
@@ -0,0 +1,206 @@
+//! A few useful macros, built with `macro_rules!`.
+//!
+//! `#[macro_export]` makes a macro available to other crates (and
+//! to our own main.rs and tests), as `project::name!`.
+//! main.rs shows each of them in action, and tests/macros.rs
+//! tests them.
+//!
+//! Macros are hygienic: a variable created inside a macro is
+//! separate from any variable with the same name outside of it,
+//! even if they're spelled the same.
+//! That means that a macro can't use a caller's variable unless
+//! it's passed in:
+//!
+//! ```compile_fail,E0425
+//! macro_rules! add_to_total {
+//!     ($amount:expr) => {
+//!         // This `total` belongs to the macro, not the caller.
+//!         total += $amount;
+//!     };
+//! }
+//!
+//! let mut total = 0;
+//! // error: cannot find value `total` in this scope
+//! add_to_total!(5);
+//! ```
+//!
+//! If the name is passed in, it refers to the caller's variable:
+//!
+//! ```
+//! macro_rules! add_to {
+//!     ($total:ident, $amount:expr) => {
+//!         $total += $amount;
+//!     };
+//! }
+//!
+//! let mut total = 0;
+//! add_to!(total, 5);
+//! assert_eq!(total, 5);
+//! ```
+
+/// Creates a HashMap from `key => value` pairs.
+///
+/// ```
+/// let ages = project::hashmap! {
+///     "Ferris" => 8,
+///     "Corro" => 3,
+/// };
+/// assert_eq!(ages["Ferris"], 8);
+/// ```
+///
+/// The macro just writes out `map.insert(...)` calls, so the
+/// compiler still checks that every value has the same type:
+///
+/// ```compile_fail,E0308
+/// let ages = project::hashmap! {
+///     "Ferris" => 8,
+///     // error: mismatched types
+///     "Corro" => "three",
+/// };
+/// ```
+///
+/// Each arm of a macro is a pattern, followed by the code to
+/// generate when it matches.
+/// `$key:expr` matches any expression, and calls it `$key`.
+/// `$( ... ),*` matches the part inside the brackets any number of
+/// times, separated by commas, and `$(,)?` allows an optional
+/// trailing comma.
+#[macro_export]
+macro_rules! hashmap {
+    // An empty map.
+    // Without this arm, `hashmap!{}` would still work, but Rust
+    // would warn that `map` doesn't need to be mut.
+    () => {
+        ::std::collections::HashMap::new()
+    };
+    ($($key:expr => $value:expr),+ $(,)?) => {{
+        // Macros can be used from anywhere, so we use the full path
+        // (starting with ::) in case the caller hasn't imported
+        // HashMap, or has something else with the same name.
+        let mut map = ::std::collections::HashMap::new();
+
+        // The same $( ... )* syntax repeats this line once for
+        // every pair that was matched.
+        $(
+            map.insert($key, $value);
+        )+
+
+        map
+    }};
+}
+
+/// Runs an expression that returns a Result until it succeeds,
+/// trying up to a certain number of times.
+///
+/// Returns the first `Ok`, or the last `Err` if every attempt failed.
+///
+/// ```
+/// let mut attempts = 0;
+/// let result: Result<u32, &str> = project::retry!(5, {
+///     attempts += 1;
+///     if attempts < 3 { Err("not yet") } else { Ok(attempts) }
+/// });
+/// assert_eq!(result, Ok(3));
+/// ```
+///
+/// The number of attempts is optional, and defaults to 3.
+/// A function could only do this by taking a closure, but a macro
+/// can take the code itself, and paste it into a loop.
+#[macro_export]
+macro_rules! retry {
+    // With no count, call the macro again with the default.
+    // Macros can call themselves (or other macros).
+    ($body:expr) => {
+        $crate::retry!(3, $body)
+    };
+    ($attempts:expr, $body:expr) => {{
+        // $crate refers to the crate that defines the macro, so the
+        // line above works even if the caller named us something else.
+        let attempts: u32 = $attempts;
+        assert!(attempts > 0, "retry! needs at least one attempt");
+
+        // Because of hygiene, this `attempt` can't clash with any
+        // variable that the caller uses in $body.
+        let mut attempt = 1;
+        loop {
+            match $body {
+                Ok(value) => break Ok(value),
+                Err(err) if attempt >= attempts => break Err(err),
+                Err(_) => attempt += 1,
+            }
+        }
+    }};
+}
+
+/// Defines a state machine: an enum of states, and the events that
+/// move between them.
+///
+/// ```
+/// project::state_machine! {
+///     Door { Closed, Open, Locked }
+///     Closed + "open" => Open,
+///     Open + "close" => Closed,
+///     Closed + "lock" => Locked,
+///     Locked + "unlock" => Closed,
+/// }
+///
+/// let door = Door::Closed.on("lock").unwrap();
+/// assert_eq!(door, Door::Locked);
+/// assert_eq!(door.on("open"), None);
+/// ```
+///
+/// This is a tiny domain-specific language (DSL).
+/// Macros don't have to look like Rust code, as long as every
+/// token can be matched by the pattern.
+/// Here, `+` and `=>` are just symbols that the pattern expects to
+/// see in those spots.
+#[macro_export]
+macro_rules! state_machine {
+    (
+        $name:ident { $($state:ident),+ $(,)? }
+        $($from:ident + $event:literal => $to:ident),* $(,)?
+    ) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum $name {
+            $($state),+
+        }
+
+        impl $name {
+            /// Every state, in the order they were listed.
+            pub const STATES: &'static [$name] = &[$($name::$state),+];
+
+            /// Returns the state that `event` leads to, or None if
+            /// this state doesn't handle it.
+            pub fn on(self, event: &str) -> Option<$name> {
+                // Every transition becomes one arm of this match.
+                match (self, event) {
+                    $(($name::$from, $event) => Some($name::$to),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+/// Squares a number.
+///
+/// In C, a macro like this is a common source of bugs, since it
+/// pastes in text: `SQUARE(1 + 2)` becomes `1 + 2 * 1 + 2`, which
+/// is 5.
+/// Rust macros work with expressions instead of text, so `$x`
+/// always acts like it's in parentheses:
+///
+/// ```
+/// assert_eq!(project::square!(1 + 2), 9);
+/// ```
+///
+/// The expression is still pasted in twice, though, so it runs
+/// twice.
+/// To only run it once, store it in a variable first, like
+/// `retry!` does with its count.
+#[macro_export]
+macro_rules! square {
+    ($x:expr) => {
+        $x * $x
+    };
+}
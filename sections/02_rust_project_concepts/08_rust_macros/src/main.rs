@@ -0,0 +1,69 @@
+// This shows the macros from lib.rs in action.
+// Run `cargo test` to run the tests in tests/macros.rs.
+//
+// To see what a macro turns into, you can install cargo-expand
+// (`cargo install cargo-expand`) and run `cargo expand`.
+
+use project::{hashmap, retry, square, state_machine};
+
+// Macros can create items, like enums and impl blocks.
+// This creates a VendingMachine enum, with an `on` method.
+state_machine! {
+    VendingMachine { Idle, Paid, Vending }
+    Idle + "coin" => Paid,
+    Paid + "refund" => Idle,
+    Paid + "button" => Vending,
+    Vending + "done" => Idle,
+}
+
+/// Pretends to connect to a server, failing the first few times.
+fn connect(attempt: &mut u32) -> Result<String, String> {
+    *attempt += 1;
+
+    if *attempt < 3 {
+        println!("  Attempt {attempt} failed.");
+        Err(format!("connection refused (attempt {attempt})"))
+    } else {
+        println!("  Attempt {attempt} worked!");
+        Ok("connected".to_string())
+    }
+}
+
+fn main() {
+    // hashmap! works like vec!, but for maps.
+    let prices = hashmap! {
+        "apple" => 3,
+        "banana" => 2,
+        "cherry" => 5,
+    };
+    let mut items: Vec<_> = prices.iter().collect();
+    items.sort();
+    println!("Prices: {items:?}");
+
+    // retry! keeps running its code until it gets an Ok.
+    println!("\nConnecting with 5 attempts:");
+    let mut attempt = 0;
+    let result = retry!(5, connect(&mut attempt));
+    println!("Result: {result:?}");
+
+    println!("\nConnecting with 2 attempts:");
+    let mut attempt = 0;
+    let result = retry!(2, connect(&mut attempt));
+    println!("Result: {result:?}");
+
+    // The state machine created by our DSL.
+    println!("\nStates: {:?}", VendingMachine::STATES);
+    let mut machine = VendingMachine::Idle;
+    for event in ["button", "coin", "button", "done"] {
+        match machine.on(event) {
+            Some(next) => {
+                println!("{machine:?} + {event} => {next:?}");
+                machine = next;
+            }
+            None => println!("{machine:?} ignores {event}"),
+        }
+    }
+
+    // Macros work with expressions, not text, so this is 9, not 5.
+    println!("\nsquare!(1 + 2) = {}", square!(1 + 2));
+}
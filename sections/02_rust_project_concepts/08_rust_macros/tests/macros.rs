@@ -0,0 +1,156 @@
+// These tests use our macros from outside the crate, the same
+// way any other program would.
+// Exported macros are imported with `use`, just like functions.
+
+use project::{hashmap, retry, square, state_machine};
+use std::collections::HashMap;
+
+state_machine! {
+    Light { Red, Green, Yellow }
+    Red + "timer" => Green,
+    Green + "timer" => Yellow,
+    Yellow + "timer" => Red,
+    Green + "emergency" => Red,
+    Yellow + "emergency" => Red,
+}
+
+#[test]
+fn hashmap_with_pairs() {
+    let map = hashmap! {
+        "one" => 1,
+        "two" => 2,
+    };
+
+    let mut expected = HashMap::new();
+    expected.insert("one", 1);
+    expected.insert("two", 2);
+
+    assert_eq!(map, expected);
+}
+
+#[test]
+fn hashmap_without_trailing_comma() {
+    let map = hashmap! { 1 => 'a', 2 => 'b' };
+
+    assert_eq!(map.len(), 2);
+    assert_eq!(map[&2], 'b');
+}
+
+#[test]
+fn hashmap_empty() {
+    let map: HashMap<String, i32> = hashmap! {};
+
+    assert!(map.is_empty());
+}
+
+#[test]
+fn hashmap_later_keys_win() {
+    // Each pair is a separate insert, in order.
+    let map = hashmap! { "key" => 1, "key" => 2 };
+
+    assert_eq!(map["key"], 2);
+}
+
+#[test]
+fn retry_returns_first_success() {
+    let mut calls = 0;
+    let result: Result<i32, ()> = retry!(5, {
+        calls += 1;
+        Ok(calls)
+    });
+
+    assert_eq!(result, Ok(1));
+    assert_eq!(calls, 1);
+}
+
+#[test]
+fn retry_gives_up() {
+    let mut calls = 0;
+    let result: Result<(), String> = retry!(4, {
+        calls += 1;
+        Err(format!("failure {calls}"))
+    });
+
+    // We get the last error.
+    assert_eq!(result, Err("failure 4".to_string()));
+    assert_eq!(calls, 4);
+}
+
+#[test]
+fn retry_defaults_to_three_attempts() {
+    let mut calls = 0;
+    let result: Result<(), ()> = retry!({
+        calls += 1;
+        Err(())
+    });
+
+    assert!(result.is_err());
+    assert_eq!(calls, 3);
+}
+
+#[test]
+fn retry_is_hygienic() {
+    // retry! has its own variable called `attempt`.
+    // Because of hygiene, it's a different variable from this one,
+    // so the macro's counting doesn't change ours, and ours doesn't
+    // confuse the macro.
+    let mut attempt = 100;
+    let result: Result<i32, ()> = retry!(3, {
+        attempt += 1;
+        if attempt == 103 {
+            Ok(attempt)
+        } else {
+            Err(())
+        }
+    });
+
+    assert_eq!(result, Ok(103));
+    assert_eq!(attempt, 103);
+}
+
+#[test]
+#[should_panic(expected = "at least one attempt")]
+fn retry_needs_an_attempt() {
+    let _: Result<(), ()> = retry!(0, Ok(()));
+}
+
+#[test]
+fn state_machine_follows_transitions() {
+    let mut light = Light::Red;
+
+    for expected in [Light::Green, Light::Yellow, Light::Red] {
+        light = light.on("timer").unwrap();
+        assert_eq!(light, expected);
+    }
+}
+
+#[test]
+fn state_machine_ignores_unknown_events() {
+    assert_eq!(Light::Red.on("emergency"), None);
+    assert_eq!(Light::Green.on("party"), None);
+}
+
+#[test]
+fn state_machine_lists_states() {
+    assert_eq!(Light::STATES, &[Light::Red, Light::Green, Light::Yellow]);
+}
+
+#[test]
+fn square_keeps_precedence() {
+    assert_eq!(square!(1 + 2), 9);
+    assert_eq!(square!(10 - 4) / 2, 18);
+}
+
+#[test]
+fn square_evaluates_twice() {
+    // The expression is pasted in twice, so it runs twice.
+    let mut calls = 0;
+    let mut next = || {
+        calls += 1;
+        calls
+    };
+
+    // 1 * 2, not 1 * 1.
+    assert_eq!(square!(next()), 2);
+    assert_eq!(calls, 2);
+}
@@ -0,0 +1,119 @@
+//! Procedural macros for this lesson.
+//!
+//! A procedural macro is a function that runs while your program is
+//! being compiled.
+//! It takes in some Rust code (as a stream of tokens), and returns
+//! new code to compile in its place.
+//!
+//! This crate has two of them:
+//! - `#[timed]`, an attribute macro that prints how long a function took.
+//! - `sql!`, a function-like macro that checks a SQL query at compile time.
+
+// proc_macro is the compiler's own crate for working with tokens.
+// It's only available inside proc-macro crates.
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, parse_quote, ItemFn, LitStr};
+
+mod sql;
+
+/// Prints how long a function took every time it's called.
+///
+/// ```ignore
+/// #[timed]
+/// fn slow() { /* ... */ }
+/// ```
+///
+/// The message uses the function's name by default, but a different
+/// label can be given with `#[timed("label")]`.
+///
+/// The time is printed even if the function returns early (with
+/// `return` or `?`), or panics.
+#[proc_macro_attribute]
+pub fn timed(attr: TokenStream, item: TokenStream) -> TokenStream {
+    // attr is whatever was in the brackets, like ("label").
+    // item is the function the attribute was put on.
+    let label = if attr.is_empty() {
+        None
+    } else {
+        // parse_macro_input! returns a compile error for us if
+        // the tokens aren't the type we asked for.
+        Some(parse_macro_input!(attr as LitStr).value())
+    };
+
+    let mut function = parse_macro_input!(item as ItemFn);
+
+    // Instant::now() can't be called at compile time, so this
+    // wouldn't work on a const fn.
+    // syn::Error lets us point the error at exactly the right spot
+    // in the user's code.
+    if let Some(constness) = &function.sig.constness {
+        return syn::Error::new_spanned(constness, "#[timed] can't be used on a const fn")
+            .to_compile_error()
+            .into();
+    }
+
+    let label = label.unwrap_or_else(|| function.sig.ident.to_string());
+    let body = &function.block;
+
+    // We replace the body with a new one that starts a timer, then
+    // runs the original body.
+    // The timer prints the time when it's dropped, which happens
+    // however the function ends, so we don't have to worry about
+    // every `return` in the original body.
+    //
+    // parse_quote! is like quote!, but parses the result into a syn
+    // type (here, a Block).
+    // #name pastes in a variable from our macro.
+    // We use unusual names, since the code we generate ends up in
+    // the user's function, and could clash with their names.
+    function.block = parse_quote!({
+        struct __TimedGuard(&'static str, ::std::time::Instant);
+
+        impl ::std::ops::Drop for __TimedGuard {
+            fn drop(&mut self) {
+                ::std::eprintln!("[timed] {} took {:?}", self.0, self.1.elapsed());
+            }
+        }
+
+        let __timed_guard = __TimedGuard(#label, ::std::time::Instant::now());
+
+        #body
+    });
+
+    // quote! turns the syntax tree back into tokens.
+    quote!(#function).into()
+}
+
+/// Checks a SQL query while the program is being compiled.
+///
+/// ```ignore
+/// let query: &str = sql!("SELECT name FROM users WHERE id = ?");
+/// ```
+///
+/// If the query is valid, this turns into the query as a string
+/// literal, with its whitespace tidied up.
+/// If it isn't, the program doesn't compile, and the error points
+/// at the query.
+/// It catches things like unknown statements, missing keywords,
+/// unclosed strings and parentheses, and DELETE or UPDATE without
+/// a WHERE.
+#[proc_macro]
+pub fn sql(input: TokenStream) -> TokenStream {
+    // A function-like macro gets everything between its brackets.
+    // We only accept a single string literal.
+    let query = parse_macro_input!(input as LitStr);
+
+    match sql::check(&query.value()) {
+        Ok(normalized) => {
+            // LitStr::new makes a new string literal, and using the
+            // original's span means errors about it will point at
+            // the user's query.
+            let literal = LitStr::new(&normalized, query.span());
+            quote!(#literal).into()
+        }
+        Err(message) => syn::Error::new(query.span(), format!("invalid SQL: {message}"))
+            .to_compile_error()
+            .into(),
+    }
+}
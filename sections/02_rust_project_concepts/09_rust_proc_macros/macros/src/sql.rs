@@ -0,0 +1,157 @@
+// This is the part of sql! that checks the query.
+// It's a plain function that works on a String, so it doesn't know
+// anything about macros, and can be tested like any other code.
+//
+// It only understands a tiny bit of SQL: it's meant to catch common
+// mistakes, not to be a real SQL parser.
+
+/// The statements that a query can start with, and the keywords
+/// that each one needs.
+const STATEMENTS: [(&str, &[&str]); 4] = [
+    ("SELECT", &["FROM"]),
+    ("INSERT", &["INTO", "VALUES"]),
+    ("UPDATE", &["SET", "WHERE"]),
+    ("DELETE", &["FROM", "WHERE"]),
+];
+
+/// Checks a query, and returns it with its whitespace tidied up.
+/// If something is wrong, returns a message describing the problem.
+pub fn check(query: &str) -> Result<String, String> {
+    let (normalized, words) = scan(query)?;
+
+    let first = words.first().ok_or("the query is empty")?;
+    let (statement, required) = STATEMENTS
+        .iter()
+        .find(|(statement, _)| statement == first)
+        .ok_or_else(|| format!("unknown statement `{first}`, expected SELECT, INSERT, UPDATE, or DELETE"))?;
+
+    for keyword in required.iter() {
+        if !words.iter().any(|word| word == keyword) {
+            // Forgetting the WHERE changes every row in the table,
+            // which deserves its own message.
+            return Err(if *keyword == "WHERE" {
+                format!("{statement} without WHERE would change every row; add a WHERE clause")
+            } else {
+                format!("{statement} is missing {keyword}")
+            });
+        }
+    }
+
+    Ok(normalized)
+}
+
+/// Goes through the query one character at a time.
+///
+/// Returns the query with every run of whitespace (outside of strings)
+/// turned into a single space, along with every word in it, in
+/// uppercase.
+/// Returns an error if a string or parenthesis isn't closed.
+fn scan(query: &str) -> Result<(String, Vec<String>), String> {
+    let mut normalized = String::new();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut depth = 0;
+    let mut chars = query.trim().chars().peekable();
+
+    while let Some(c) = chars.next() {
+        // Anything that isn't part of a word ends the current one.
+        if !is_word_char(c) && !word.is_empty() {
+            words.push(word.to_uppercase());
+            word.clear();
+        }
+
+        match c {
+            // Strings are copied exactly as they are.
+            // Inside a string, '' is an escaped quote.
+            '\'' => {
+                normalized.push(c);
+                loop {
+                    match chars.next() {
+                        Some('\'') if chars.peek() == Some(&'\'') => {
+                            normalized.push_str("''");
+                            chars.next();
+                        }
+                        Some('\'') => break,
+                        Some(c) => normalized.push(c),
+                        None => return Err("unterminated string".to_string()),
+                    }
+                }
+                normalized.push('\'');
+            }
+            c if c.is_whitespace() => {
+                // Skip the rest of the whitespace.
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                normalized.push(' ');
+            }
+            '(' => {
+                depth += 1;
+                normalized.push(c);
+            }
+            ')' => {
+                if depth == 0 {
+                    return Err("`)` without a matching `(`".to_string());
+                }
+                depth -= 1;
+                normalized.push(c);
+            }
+            c => {
+                if is_word_char(c) {
+                    word.push(c);
+                }
+                normalized.push(c);
+            }
+        }
+    }
+
+    if !word.is_empty() {
+        words.push(word.to_uppercase());
+    }
+
+    if depth > 0 {
+        return Err("`(` without a matching `)`".to_string());
+    }
+
+    Ok((normalized, words))
+}
+
+/// Returns whether `c` can be part of a word, like a keyword or name.
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tidies_whitespace() {
+        assert_eq!(
+            check("  SELECT name\n    FROM users   WHERE id = ?  "),
+            Ok("SELECT name FROM users WHERE id = ?".to_string())
+        );
+    }
+
+    #[test]
+    fn keeps_strings_as_they_are() {
+        assert_eq!(
+            check("select * from users where name = 'Ferris  the ''crab'''"),
+            Ok("select * from users where name = 'Ferris  the ''crab'''".to_string())
+        );
+    }
+
+    #[test]
+    fn keywords_inside_strings_dont_count() {
+        assert!(check("INSERT INTO notes (text) 'VALUES'").is_err());
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(check("   "), Err("the query is empty".to_string()));
+        assert!(check("DROP TABLE users").unwrap_err().starts_with("unknown statement"));
+        assert!(check("SELECT name users").unwrap_err().contains("missing FROM"));
+        assert!(check("DELETE FROM users").unwrap_err().contains("every row"));
+        assert!(check("SELECT * FROM users WHERE name = 'oops").unwrap_err().contains("unterminated"));
+        assert!(check("SELECT COUNT(* FROM users").unwrap_err().contains("`(`"));
+        assert!(check("SELECT COUNT*) FROM users").unwrap_err().contains("`)`"));
+    }
+}
@@ -0,0 +1,15 @@
+// These tests use trybuild to compile the programs in the ui folder.
+// Programs in ui/pass must compile and run successfully.
+// Programs in ui/fail must fail to compile, with exactly the error
+// messages in the matching .stderr file.
+//
+// To create or update the .stderr files after changing an error
+// message, run `TRYBUILD=overwrite cargo test`, and check that the
+// new messages look right.
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/pass/*.rs");
+    t.compile_fail("tests/ui/fail/*.rs");
+}
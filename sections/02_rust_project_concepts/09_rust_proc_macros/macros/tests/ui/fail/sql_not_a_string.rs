@@ -0,0 +1,6 @@
+use project_macros::sql;
+
+fn main() {
+    let table = "users";
+    let _ = sql!(table);
+}
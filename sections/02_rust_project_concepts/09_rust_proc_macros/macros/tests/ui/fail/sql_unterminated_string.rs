@@ -0,0 +1,5 @@
+use project_macros::sql;
+
+fn main() {
+    let _ = sql!("SELECT * FROM users WHERE name = 'Ferris");
+}
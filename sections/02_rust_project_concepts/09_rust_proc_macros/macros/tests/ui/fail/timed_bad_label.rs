@@ -0,0 +1,8 @@
+use project_macros::timed;
+
+#[timed(label)]
+fn work() {}
+
+fn main() {
+    work();
+}
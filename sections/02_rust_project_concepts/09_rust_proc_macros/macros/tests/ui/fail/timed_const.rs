@@ -0,0 +1,10 @@
+use project_macros::timed;
+
+#[timed]
+const fn answer() -> u32 {
+    42
+}
+
+fn main() {
+    answer();
+}
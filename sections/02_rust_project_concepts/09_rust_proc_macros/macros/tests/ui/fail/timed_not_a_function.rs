@@ -0,0 +1,9 @@
+use project_macros::timed;
+
+#[timed]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use project_macros::sql;
+
+fn main() {
+    let select: &str = sql!("SELECT name FROM users WHERE id = ?");
+    assert_eq!(select, "SELECT name FROM users WHERE id = ?");
+
+    // Whitespace is tidied up, but strings are left alone.
+    let insert = sql!("
+        insert into users (name, bio)
+        values ('Ferris', 'A  crab')
+    ");
+    assert_eq!(insert, "insert into users (name, bio) values ('Ferris', 'A  crab')");
+
+    // Since sql! turns into a string literal, it can even be used
+    // in a const.
+    const DELETE: &str = sql!("DELETE FROM users WHERE id = ?");
+    assert!(DELETE.starts_with("DELETE"));
+}
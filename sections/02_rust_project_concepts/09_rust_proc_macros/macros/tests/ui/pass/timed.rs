@@ -0,0 +1,38 @@
+use project_macros::timed;
+
+#[timed]
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[timed("parsing a number")]
+fn parse(text: &str) -> Result<i32, std::num::ParseIntError> {
+    // ? and return still work, since the body is left as it is.
+    let number = text.parse::<i32>()?;
+    if number < 0 {
+        return Ok(0);
+    }
+    Ok(number)
+}
+
+struct Counter(u32);
+
+impl Counter {
+    // Methods work too, including ones that take self.
+    #[timed]
+    fn bump(&mut self) -> u32 {
+        self.0 += 1;
+        self.0
+    }
+}
+
+fn main() {
+    assert_eq!(add(1, 2), 3);
+    assert_eq!(parse("42"), Ok(42));
+    assert_eq!(parse("-5"), Ok(0));
+    assert!(parse("nope").is_err());
+
+    let mut counter = Counter(0);
+    counter.bump();
+    assert_eq!(counter.bump(), 2);
+}
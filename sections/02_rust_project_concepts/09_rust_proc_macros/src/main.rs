@@ -0,0 +1,54 @@
+// This program uses the procedural macros from the macros folder.
+// The macros themselves are in macros/src/lib.rs.
+// Run `cargo test --workspace` to run the trybuild tests in
+// macros/tests, which check that the macros work (and fail)
+// the way they should.
+
+use project_macros::{sql, timed};
+
+/// Counts the prime numbers below `limit`, slowly.
+// #[timed] rewrites this function so that it prints how long
+// it took every time it's called.
+#[timed]
+fn count_primes(limit: u64) -> usize {
+    (2..limit).filter(|&n| (2..n).take_while(|d| d * d <= n).all(|d| n % d != 0)).count()
+}
+
+/// Parses a list of numbers, stopping at the first bad one.
+// A custom label, and an early return with ?.
+// The time is still printed, even when this returns an error.
+#[timed("parsing numbers")]
+fn parse_all(text: &str) -> Result<Vec<i64>, std::num::ParseIntError> {
+    let mut numbers = Vec::new();
+    for part in text.split(',') {
+        numbers.push(part.trim().parse()?);
+    }
+    Ok(numbers)
+}
+
+// sql! turns into a string literal, so it can be used in a const.
+// If the query had a mistake in it, this program wouldn't compile.
+const FIND_USER: &str = sql!("SELECT id, name FROM users WHERE name = ?");
+
+fn main() {
+    println!("There are {} primes below 100,000.", count_primes(100_000));
+
+    println!("{:?}", parse_all("1, 2, 3"));
+    println!("{:?}", parse_all("1, two, 3"));
+
+    println!("\nChecked queries:");
+    println!("  {FIND_USER}");
+
+    // The query can be spread over multiple lines, and sql! will
+    // tidy it up into one.
+    let insert = sql!(
+        "INSERT INTO users (name, bio)
+         VALUES (?, 'Likes   crabs')"
+    );
+    println!("  {insert}");
+
+    // Try uncommenting these to see the errors:
+    // sql!("DELETE FROM users");
+    // sql!("SELECT name FROM users WHERE bio = 'oops");
+    // sql!("REMOVE EVERYTHING");
+}
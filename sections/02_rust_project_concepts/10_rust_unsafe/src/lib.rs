@@ -0,0 +1,368 @@
+//! A simplified version of Vec, built with unsafe code.
+//!
+//! Unsafe code is needed here because we manage memory ourselves:
+//! we ask for a block of memory, keep track of which parts of it
+//! hold values, and give it back when we're done.
+//! The compiler can't check any of that for us, so it's up to us
+//! to get it right.
+//!
+//! Every `unsafe` block has a `// SAFETY:` comment explaining why
+//! it's correct.
+//! This is a common convention in Rust, and it's a good habit:
+//! if you can't write the comment, the code probably isn't safe.
+//!
+//! main.rs shows MyVec in action.
+//! The tests at the bottom can be run with Miri, which checks for
+//! undefined behavior and memory leaks:
+//! `cargo +nightly miri test`
+
+use std::alloc::{self, Layout};
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::ptr::{self, NonNull};
+use std::slice;
+
+/// A growable list of values, like Vec.
+///
+/// MyVec relies on these invariants.
+/// Every method can assume they're true when it's called, and must
+/// make sure they're true again before it returns:
+///
+/// 1. If `cap` is 0, or T is zero-sized, `ptr` is dangling (not
+///    allocated), but still non-null and aligned.
+///    Otherwise, `ptr` points to memory we allocated with the layout
+///    `Layout::array::<T>(cap)`.
+/// 2. `len <= cap`.
+/// 3. The first `len` slots hold valid, initialized values of T, and
+///    we own them.
+///    The slots after that are uninitialized, and must never be read.
+pub struct MyVec<T> {
+    ptr: NonNull<T>,
+    cap: usize,
+    len: usize,
+    // NonNull<T> is just a pointer, so on its own, it doesn't tell
+    // the compiler that we own the T values it points to.
+    // PhantomData<T> takes up no space, but says "act as if this
+    // struct contains a T", which makes the compiler's drop checking
+    // treat MyVec<T> the same way it treats Vec<T>.
+    _owns: PhantomData<T>,
+}
+
+// Raw pointers aren't Send or Sync, so MyVec wouldn't be either.
+// But MyVec owns its values, just like Vec, so it's safe to send to
+// another thread (or share with one) whenever T is.
+// The compiler can't figure this out for us, so implementing these
+// traits is unsafe: we're promising that it's true.
+
+// SAFETY: MyVec owns its values and nothing else points to them, so
+// sending it sends the values, which is fine if T: Send.
+unsafe impl<T: Send> Send for MyVec<T> {}
+// SAFETY: &MyVec only gives out &T, which is fine to share if T: Sync.
+unsafe impl<T: Sync> Sync for MyVec<T> {}
+
+impl<T> MyVec<T> {
+    /// Whether T takes up no space, like `()`.
+    /// We never need to allocate memory for these.
+    const IS_ZST: bool = mem::size_of::<T>() == 0;
+
+    /// Creates an empty MyVec.
+    /// This doesn't allocate any memory until the first push.
+    pub fn new() -> MyVec<T> {
+        MyVec {
+            // A pointer that isn't null and is aligned for T, but
+            // doesn't point to anything.
+            // It's fine to have one, as long as we never read
+            // or write through it (invariant 1).
+            ptr: NonNull::dangling(),
+            // A zero-sized value can be "stored" without any memory,
+            // so we can fit as many as we want.
+            cap: if Self::IS_ZST { usize::MAX } else { 0 },
+            len: 0,
+            _owns: PhantomData,
+        }
+    }
+
+    /// Returns how many values can be stored before more memory
+    /// needs to be allocated.
+    pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    /// Adds a value to the end.
+    pub fn push(&mut self, value: T) {
+        if self.len == self.cap {
+            self.grow();
+        }
+
+        // SAFETY: grow made sure that len < cap, so slot `len` is
+        // inside our allocation (invariant 1), and it's uninitialized
+        // (invariant 3), so there's no old value that we'd be
+        // overwriting without dropping.
+        // ptr::write moves value in without reading or dropping
+        // whatever garbage was there before.
+        unsafe {
+            ptr::write(self.ptr.as_ptr().add(self.len), value);
+        }
+
+        // Slot `len` is now initialized, so invariant 3 still holds.
+        self.len += 1;
+    }
+
+    /// Removes the last value and returns it, or returns None if
+    /// there aren't any.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        // Shrink len first, so slot `len` is no longer counted as
+        // initialized.
+        self.len -= 1;
+
+        // SAFETY: slot `len` was initialized before we shrank len
+        // (invariant 3).
+        // ptr::read copies the value out, giving us ownership of it.
+        // The copy left behind in the slot is now treated as
+        // uninitialized, so it'll never be read or dropped again.
+        unsafe { Some(ptr::read(self.ptr.as_ptr().add(self.len))) }
+    }
+
+    /// Removes every value.
+    pub fn clear(&mut self) {
+        // Once pop is correct, we can build on top of it without any
+        // unsafe code at all.
+        while self.pop().is_some() {}
+    }
+
+    /// Allocates more memory, doubling the capacity.
+    fn grow(&mut self) {
+        // Zero-sized types start with a capacity of usize::MAX, so
+        // the only way to get here is to have that many values.
+        assert!(!Self::IS_ZST, "capacity overflow");
+
+        let new_cap = if self.cap == 0 { 4 } else { self.cap.checked_mul(2).expect("capacity overflow") };
+
+        // Layout describes a block of memory: its size and alignment.
+        // This fails if the size would be more than isize::MAX bytes,
+        // which is the most that Rust allows in a single allocation.
+        let new_layout = Layout::array::<T>(new_cap).expect("capacity overflow");
+
+        let new_ptr = if self.cap == 0 {
+            // SAFETY: new_layout has a non-zero size, since T isn't
+            // zero-sized and new_cap is at least 4.
+            unsafe { alloc::alloc(new_layout) }
+        } else {
+            let old_layout = Layout::array::<T>(self.cap).unwrap();
+
+            // SAFETY: ptr was allocated with old_layout (invariant 1),
+            // and the new size is non-zero and fits in an isize
+            // (Layout::array checked that).
+            // realloc copies the existing values over if it needs to
+            // move them, so the first len slots stay initialized.
+            unsafe { alloc::realloc(self.ptr.as_ptr().cast(), old_layout, new_layout.size()) }
+        };
+
+        // The allocator returns null if it ran out of memory.
+        // handle_alloc_error reports that and stops the program.
+        self.ptr = match NonNull::new(new_ptr.cast()) {
+            Some(ptr) => ptr,
+            None => alloc::handle_alloc_error(new_layout),
+        };
+        self.cap = new_cap;
+    }
+}
+
+impl<T> Default for MyVec<T> {
+    fn default() -> Self {
+        MyVec::new()
+    }
+}
+
+impl<T> Drop for MyVec<T> {
+    fn drop(&mut self) {
+        // First, drop every value that we own.
+        // SAFETY: the first len slots are initialized (invariant 3),
+        // and after this, nothing will use them again.
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.ptr.as_ptr(), self.len));
+        }
+
+        // Then, give the memory back, if we allocated any.
+        if self.cap != 0 && !Self::IS_ZST {
+            let layout = Layout::array::<T>(self.cap).unwrap();
+
+            // SAFETY: ptr was allocated with this layout (invariant 1),
+            // and this MyVec is going away, so it'll never be used again.
+            unsafe {
+                alloc::dealloc(self.ptr.as_ptr().cast(), layout);
+            }
+        }
+    }
+}
+
+// Deref lets a MyVec be used anywhere a slice can.
+// That gives us len, is_empty, iter, indexing, sort, and every
+// other slice method, without writing them ourselves.
+impl<T> Deref for MyVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        // SAFETY: ptr is non-null and aligned (even when dangling,
+        // which is allowed for an empty slice or zero-sized T), and
+        // the first len slots are initialized (invariant 3).
+        // The slice borrows self, so the values can't be changed or
+        // freed while it exists.
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T> DerefMut for MyVec<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        // SAFETY: the same as deref, and we have &mut self, so nothing
+        // else can be using the values.
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for MyVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Thanks to Deref, we can just format the slice.
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+// These tests are written to work well under Miri.
+// They use types that own heap memory (like String and Rc), so
+// Miri can tell us if a value is dropped twice, never dropped,
+// or used after it's been freed.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn push_and_pop() {
+        let mut v = MyVec::new();
+        v.push(1);
+        v.push(2);
+        v.push(3);
+
+        assert_eq!(v.len(), 3);
+        assert_eq!(v.pop(), Some(3));
+        assert_eq!(v.pop(), Some(2));
+        assert_eq!(v.pop(), Some(1));
+        assert_eq!(v.pop(), None);
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn grows_by_doubling() {
+        let mut v = MyVec::new();
+        assert_eq!(v.capacity(), 0);
+
+        for i in 0..9 {
+            v.push(i);
+        }
+
+        // 4, then 8, then 16.
+        assert_eq!(v.capacity(), 16);
+        assert_eq!(*v, [0, 1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn works_as_a_slice() {
+        let mut v = MyVec::new();
+        for word in ["pear", "apple", "fig"] {
+            v.push(word.to_string());
+        }
+
+        // sort and iter_mut come from DerefMut.
+        v.sort();
+        for word in v.iter_mut() {
+            word.push('!');
+        }
+
+        assert_eq!(v[0], "apple!");
+        assert_eq!(v.join(" "), "apple! fig! pear!");
+        assert_eq!(format!("{v:?}"), r#"["apple!", "fig!", "pear!"]"#);
+    }
+
+    #[test]
+    fn pop_gives_ownership() {
+        let shared = Rc::new(());
+        let mut v = MyVec::new();
+        v.push(Rc::clone(&shared));
+
+        let popped = v.pop().unwrap();
+        // The value was moved out, not copied, so there are
+        // still only two Rcs.
+        assert_eq!(Rc::strong_count(&shared), 2);
+
+        drop(popped);
+        assert_eq!(Rc::strong_count(&shared), 1);
+    }
+
+    #[test]
+    fn drop_drops_every_value_once() {
+        let shared = Rc::new(());
+        let mut v = MyVec::new();
+        for _ in 0..10 {
+            v.push(Rc::clone(&shared));
+        }
+        assert_eq!(Rc::strong_count(&shared), 11);
+
+        // If Drop missed a value, the count would be too high.
+        // If it dropped one twice, the count would be too low (and
+        // Miri would report it).
+        drop(v);
+        assert_eq!(Rc::strong_count(&shared), 1);
+    }
+
+    #[test]
+    fn clear_drops_values() {
+        let shared = Rc::new(());
+        let mut v = MyVec::new();
+        v.push(Rc::clone(&shared));
+        v.push(Rc::clone(&shared));
+
+        v.clear();
+        assert!(v.is_empty());
+        assert_eq!(Rc::strong_count(&shared), 1);
+
+        // The memory is kept, so it can be reused.
+        assert_eq!(v.capacity(), 4);
+        v.push(Rc::clone(&shared));
+        assert_eq!(v.len(), 1);
+    }
+
+    #[test]
+    fn empty_vec_drops_cleanly() {
+        // Nothing was allocated, so nothing should be freed.
+        let v: MyVec<String> = MyVec::new();
+        drop(v);
+    }
+
+    #[test]
+    fn zero_sized_types() {
+        let mut v = MyVec::new();
+        for _ in 0..1_000 {
+            v.push(());
+        }
+
+        assert_eq!(v.len(), 1_000);
+        assert_eq!(v.capacity(), usize::MAX);
+        assert_eq!(v.pop(), Some(()));
+        assert_eq!(v.len(), 999);
+    }
+
+    #[test]
+    fn can_be_sent_to_threads() {
+        let mut v = MyVec::new();
+        v.push("moved to another thread".to_string());
+
+        let handle = std::thread::spawn(move || v.pop());
+        assert_eq!(handle.join().unwrap().as_deref(), Some("moved to another thread"));
+    }
+}
@@ -0,0 +1,48 @@
+// This uses MyVec, our own version of Vec, from lib.rs.
+// MyVec is built with unsafe code inside, but everything it gives
+// us is safe to use: there's no way to misuse it from here without
+// writing `unsafe` ourselves.
+// That's the goal of most unsafe code: a small, carefully checked
+// piece of unsafe, wrapped in a safe interface.
+
+use project::MyVec;
+
+fn main() {
+    let mut numbers = MyVec::new();
+    println!("Empty: {numbers:?} (capacity {})", numbers.capacity());
+
+    // Watch the capacity double as we push.
+    for n in 1..=10 {
+        numbers.push(n * n);
+        println!("Pushed {:>3}: length {:>2}, capacity {:>2}", n * n, numbers.len(), numbers.capacity());
+    }
+
+    // MyVec derefs to a slice, so every slice method works on it.
+    println!("\nContents: {numbers:?}");
+    println!("Sum: {}", numbers.iter().sum::<i32>());
+    println!("Third: {}", numbers[2]);
+    println!("Contains 49? {}", numbers.contains(&49));
+
+    numbers.reverse();
+    println!("Reversed: {numbers:?}");
+
+    println!("\nPopped: {:?}", numbers.pop());
+    println!("Popped: {:?}", numbers.pop());
+
+    // Values that own memory (like String) work too.
+    // When words goes out of scope, Drop frees each String,
+    // and then the memory that held them.
+    let mut words = MyVec::new();
+    for word in "unsafe code needs careful thought".split(' ') {
+        words.push(word.to_uppercase());
+    }
+    words.sort();
+    println!("\nSorted words: {words:?}");
+
+    // Values that take up no space at all never need memory.
+    let mut nothing = MyVec::new();
+    for _ in 0..5 {
+        nothing.push(());
+    }
+    println!("{} empty values, capacity {}", nothing.len(), nothing.capacity());
+}
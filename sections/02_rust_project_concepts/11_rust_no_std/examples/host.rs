@@ -0,0 +1,26 @@
+// A pretend sensor, running on your computer.
+// Run it with `cargo run --example host`.
+//
+// Examples are separate programs that use the crate, and they can use
+// std (for things like println!), even though the crate itself can't.
+
+use project::{average, RingBuffer};
+
+fn main() {
+    // Keep the last 5 readings.
+    let mut readings: RingBuffer<u16, 5> = RingBuffer::new();
+
+    // Some made-up temperatures, in tenths of a degree.
+    let samples = [201, 203, 204, 210, 215, 221, 219, 230, 228, 226];
+
+    for sample in samples {
+        if let Some(oldest) = readings.push_overwrite(sample) {
+            println!("Forgot {oldest}");
+        }
+
+        // average only returns None for an empty buffer, and we just
+        // added a value.
+        let average = average(&readings).unwrap_or_default();
+        println!("Read {sample}, last {} readings: {readings:?}, average {average}", readings.len());
+    }
+}
@@ -0,0 +1,223 @@
+//! A fixed-capacity ring buffer that works without the standard library.
+//!
+//! ```
+//! use project::RingBuffer;
+//!
+//! let mut readings: RingBuffer<u16, 4> = RingBuffer::new();
+//! readings.push(512).unwrap();
+//! readings.push(600).unwrap();
+//! assert_eq!(readings.pop(), Some(512));
+//! ```
+
+// #![no_std] means this crate only uses `core`, the part of the
+// standard library that works everywhere: no heap (so no Vec, String,
+// or Box), no files, no threads, and no printing.
+// That's what lets it run on a microcontroller with no operating
+// system.
+//
+// cfg_attr only applies no_std when we're not running unit tests,
+// since the test runner itself needs the standard library.
+// Integration tests (in tests/) and doc tests are separate crates,
+// so they can use std however they like while still testing this
+// no_std code.
+#![cfg_attr(not(test), no_std)]
+
+// The panic handler is only needed when there's no std to provide
+// one (see panic.rs).
+// Unit tests always have std, so it's left out of them.
+#[cfg(all(not(test), feature = "panic-handler"))]
+mod panic;
+
+// Only `core` is available, so everything is imported from there.
+// Most of std is really core under a different name:
+// std::mem is core::mem, std::fmt is core::fmt, and so on.
+use core::fmt;
+
+/// A queue that holds up to `N` values, without allocating.
+///
+/// Values are added at the back and removed from the front.
+/// The storage is an array that's part of the struct itself, so a
+/// RingBuffer can live on the stack, or in a `static`, and never
+/// needs a heap.
+///
+/// "Ring" refers to how it uses its array: when it reaches the end,
+/// it wraps around to the start, so it never has to move values.
+pub struct RingBuffer<T, const N: usize> {
+    /// The slots. Some(value) holds a value, None is empty.
+    slots: [Option<T>; N],
+    /// The index of the front (oldest) value.
+    head: usize,
+    /// How many values are stored.
+    len: usize,
+}
+
+impl<T, const N: usize> RingBuffer<T, N> {
+    /// Creates an empty buffer.
+    ///
+    /// This is a `const fn`, so it can be used to set up a `static`,
+    /// which is how buffers are often stored on embedded devices.
+    pub const fn new() -> Self {
+        RingBuffer {
+            // `[None; N]` would need T to be Copy.
+            // An inline const block works for any T, since the
+            // compiler creates a fresh None for every slot.
+            slots: [const { None }; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// How many values the buffer can hold.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// How many values are in the buffer.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if there are no values in the buffer.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns true if there's no room for another value.
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Turns a position (0 is the front) into an index in `slots`.
+    /// Only call this when N isn't 0.
+    fn index(&self, position: usize) -> usize {
+        (self.head + position) % N
+    }
+
+    /// Adds a value to the back.
+    ///
+    /// If the buffer is full, the value is given back as an error.
+    /// Without a heap, there's no way to make more room, so the
+    /// caller has to decide what to do.
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(value);
+        }
+
+        let index = self.index(self.len);
+        self.slots[index] = Some(value);
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Adds a value to the back, removing the oldest value to make
+    /// room if the buffer is full.
+    ///
+    /// Returns the value that was removed, if there was one.
+    /// This is handy for things like keeping the last few sensor
+    /// readings.
+    pub fn push_overwrite(&mut self, value: T) -> Option<T> {
+        if N == 0 {
+            // There's nowhere to put it.
+            return Some(value);
+        }
+
+        let removed = if self.is_full() { self.pop() } else { None };
+
+        // There's room now, so this always succeeds.
+        // Using ok() instead of unwrap() keeps the panic (and its
+        // message formatting) out of the compiled code.
+        self.push(value).ok();
+
+        removed
+    }
+
+    /// Removes the front (oldest) value and returns it.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        // take() swaps the slot's value for None, and gives us the value.
+        let value = self.slots[self.head].take();
+        self.head = self.index(1);
+        self.len -= 1;
+
+        value
+    }
+
+    /// Returns the front (oldest) value, without removing it.
+    pub fn peek(&self) -> Option<&T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        self.slots[self.head].as_ref()
+    }
+
+    /// Removes every value.
+    pub fn clear(&mut self) {
+        while self.pop().is_some() {}
+    }
+
+    /// Returns an iterator over the values, from oldest to newest.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        // Iterators are part of core, so they work without std.
+        (0..self.len).filter_map(move |position| self.slots[self.index(position)].as_ref())
+    }
+}
+
+impl<T, const N: usize> Default for RingBuffer<T, N> {
+    fn default() -> Self {
+        RingBuffer::new()
+    }
+}
+
+// core::fmt is available without std, so Debug works.
+// Formatting does add a lot of code to a program, though, which
+// matters on devices with very little space.
+impl<T: fmt::Debug, const N: usize> fmt::Debug for RingBuffer<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// Returns the average of the values in a buffer, or None if it's
+/// empty.
+///
+/// Embedded code often avoids floating point (many small chips can't
+/// do it in hardware), so this works with whole numbers.
+pub fn average<const N: usize>(buffer: &RingBuffer<u16, N>) -> Option<u16> {
+    if buffer.is_empty() {
+        return None;
+    }
+
+    // Adding up in a u64 means that the sum can't overflow. It would
+    // take more than 2^48 values of u16::MAX, and a buffer that big
+    // wouldn't fit in memory. (A u32 would overflow after just 65,537.)
+    let sum: u64 = buffer.iter().map(|&value| u64::from(value)).sum();
+
+    // The average of u16s always fits in a u16.
+    Some((sum / buffer.len() as u64) as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Unit tests can see private things, like the slots themselves.
+    #[test]
+    fn wraps_around_the_array() {
+        let mut buffer: RingBuffer<u8, 3> = RingBuffer::new();
+        buffer.push(1).unwrap();
+        buffer.push(2).unwrap();
+        buffer.pop();
+        buffer.pop();
+        buffer.push(3).unwrap();
+        buffer.push(4).unwrap();
+
+        // 3 went into the last slot, and 4 wrapped around to the first.
+        assert_eq!(buffer.head, 2);
+        assert_eq!(buffer.slots, [Some(4), None, Some(3)]);
+    }
+}
@@ -0,0 +1,30 @@
+// Every Rust program needs to know what to do when it panics.
+// Normally, the standard library handles this: it prints the
+// message and exits.
+// Without std, there's nothing to print to, and nothing to exit
+// to, so the program has to provide its own panic handler.
+//
+// This module is only included with the "panic-handler" feature,
+// since there can only be one panic handler in a program, and on
+// a normal computer, std already provides it.
+
+use core::panic::PanicInfo;
+
+/// Called when the program panics.
+///
+/// The `!` return type means that it never returns: after a panic,
+/// there's no safe way to carry on.
+///
+/// This handler doesn't look at the panic message at all.
+/// Formatting the message would pull all of core::fmt into the
+/// program, which can take up several kilobytes, a lot on a chip
+/// with 32KB of space.
+/// Real firmware might instead turn on an LED, or reset the chip.
+#[panic_handler]
+fn panic(_info: &PanicInfo) -> ! {
+    loop {
+        // Tells the processor that we're waiting, so it can
+        // save power.
+        core::hint::spin_loop();
+    }
+}
@@ -0,0 +1,147 @@
+// These tests run on your computer, with the standard library, as
+// a normal program.
+// They use the crate exactly like firmware would, so if the ring
+// buffer works here, it works on a microcontroller too (the logic
+// doesn't depend on where it runs).
+
+use project::{average, RingBuffer};
+
+#[test]
+fn starts_empty() {
+    let buffer: RingBuffer<i32, 4> = RingBuffer::new();
+
+    assert!(buffer.is_empty());
+    assert!(!buffer.is_full());
+    assert_eq!(buffer.len(), 0);
+    assert_eq!(buffer.capacity(), 4);
+    assert_eq!(buffer.peek(), None);
+}
+
+#[test]
+fn first_in_first_out() {
+    let mut buffer: RingBuffer<i32, 4> = RingBuffer::new();
+    buffer.push(1).unwrap();
+    buffer.push(2).unwrap();
+    buffer.push(3).unwrap();
+
+    assert_eq!(buffer.peek(), Some(&1));
+    assert_eq!(buffer.pop(), Some(1));
+    assert_eq!(buffer.pop(), Some(2));
+    assert_eq!(buffer.pop(), Some(3));
+    assert_eq!(buffer.pop(), None);
+}
+
+#[test]
+fn push_fails_when_full() {
+    let mut buffer: RingBuffer<i32, 2> = RingBuffer::new();
+    buffer.push(1).unwrap();
+    buffer.push(2).unwrap();
+
+    assert!(buffer.is_full());
+    // The value comes back, so it isn't lost.
+    assert_eq!(buffer.push(3), Err(3));
+    assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), [1, 2]);
+}
+
+#[test]
+fn push_overwrite_replaces_the_oldest() {
+    let mut buffer: RingBuffer<i32, 3> = RingBuffer::new();
+
+    for value in 1..=3 {
+        assert_eq!(buffer.push_overwrite(value), None);
+    }
+    assert_eq!(buffer.push_overwrite(4), Some(1));
+    assert_eq!(buffer.push_overwrite(5), Some(2));
+
+    assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), [3, 4, 5]);
+}
+
+#[test]
+fn keeps_working_after_wrapping_many_times() {
+    let mut buffer: RingBuffer<usize, 3> = RingBuffer::new();
+
+    for value in 0..100 {
+        buffer.push(value).unwrap();
+        assert_eq!(buffer.pop(), Some(value));
+    }
+    assert!(buffer.is_empty());
+}
+
+#[test]
+fn zero_capacity() {
+    let mut buffer: RingBuffer<i32, 0> = RingBuffer::new();
+
+    assert!(buffer.is_full());
+    assert_eq!(buffer.push(1), Err(1));
+    assert_eq!(buffer.push_overwrite(2), Some(2));
+    assert_eq!(buffer.pop(), None);
+}
+
+#[test]
+fn drops_values_it_owns() {
+    use std::rc::Rc;
+
+    // Rc counts how many copies of it exist, so we can check that
+    // the buffer drops its values when it's cleared or dropped.
+    let value = Rc::new(());
+    let mut buffer: RingBuffer<Rc<()>, 4> = RingBuffer::new();
+
+    buffer.push(Rc::clone(&value)).unwrap();
+    buffer.push(Rc::clone(&value)).unwrap();
+    assert_eq!(Rc::strong_count(&value), 3);
+
+    buffer.clear();
+    assert!(buffer.is_empty());
+    assert_eq!(Rc::strong_count(&value), 1);
+
+    buffer.push(Rc::clone(&value)).unwrap();
+    drop(buffer);
+    assert_eq!(Rc::strong_count(&value), 1);
+}
+
+#[test]
+fn works_in_a_static() {
+    use std::sync::Mutex;
+
+    // new() is a const fn, so no setup code has to run.
+    static READINGS: Mutex<RingBuffer<u16, 8>> = Mutex::new(RingBuffer::new());
+
+    READINGS.lock().unwrap().push(42).unwrap();
+    assert_eq!(READINGS.lock().unwrap().pop(), Some(42));
+}
+
+#[test]
+fn debug_lists_oldest_first() {
+    let mut buffer: RingBuffer<i32, 3> = RingBuffer::new();
+    for value in 1..=5 {
+        buffer.push_overwrite(value);
+    }
+
+    assert_eq!(format!("{buffer:?}"), "[3, 4, 5]");
+}
+
+#[test]
+fn averages() {
+    let mut buffer: RingBuffer<u16, 4> = RingBuffer::new();
+    assert_eq!(average(&buffer), None);
+
+    buffer.push(10).unwrap();
+    buffer.push(20).unwrap();
+    buffer.push(33).unwrap();
+    // Whole number division rounds down.
+    assert_eq!(average(&buffer), Some(21));
+
+    // No overflow, even at the largest values.
+    let mut full: RingBuffer<u16, 4> = RingBuffer::new();
+    for _ in 0..4 {
+        full.push(u16::MAX).unwrap();
+    }
+    assert_eq!(average(&full), Some(u16::MAX));
+
+    // 70,000 of them would overflow a u32 sum.
+    let mut big: RingBuffer<u16, 70_000> = RingBuffer::new();
+    for _ in 0..70_000 {
+        big.push(u16::MAX).unwrap();
+    }
+    assert_eq!(average(&big), Some(u16::MAX));
+}
@@ -0,0 +1,123 @@
+// A backend is where a route sends its requests.
+//
+// In a config file, it can be written three ways:
+//
+//     backend = "http://localhost:3000"
+//     backend = ["http://10.0.0.1:8080", "http://10.0.0.2:8080"]
+//     backend = { urls = ["http://10.0.0.3:9000"], retries = 3 }
+//
+// In Rust, we don't want to deal with three shapes, so they all turn
+// into one struct. An untagged enum does the work of figuring out
+// which shape was used.
+//
+// Untagged enums only work in formats that describe themselves,
+// though: serde has to look at the data to see whether it's a
+// string, a list, or a table. Bincode is just bytes, with nothing
+// saying what they are, so it gets a simpler, fixed shape instead.
+// That means writing Serialize and Deserialize by hand.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A group of servers that requests are sent to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backend {
+    /// The servers. Requests take turns between them.
+    pub urls: Vec<String>,
+    /// How many times to try again if a request fails.
+    pub retries: u32,
+}
+
+impl Backend {
+    /// A backend with one server, and no retries.
+    pub fn new(url: impl Into<String>) -> Self {
+        Backend {
+            urls: vec![url.into()],
+            retries: 0,
+        }
+    }
+}
+
+/// The ways a backend can be written in a config file.
+///
+/// `untagged` means that there's nothing in the data saying which
+/// variant it is. Serde tries each variant in order, and uses the
+/// first one that fits.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BackendInput {
+    Url(String),
+    Urls(Vec<String>),
+    // Untagged enums can't use deny_unknown_fields (serde needs to be
+    // able to try a variant and back out), so a typo in here
+    // gives a less helpful error. That's one of their trade-offs.
+    Table {
+        urls: Vec<String>,
+        #[serde(default)]
+        retries: u32,
+    },
+}
+
+/// The shortest way to write a backend in a config file.
+///
+/// This borrows from the Backend, so serializing it doesn't copy
+/// anything.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum BackendOutput<'a> {
+    Url(&'a str),
+    Table {
+        urls: &'a [String],
+        // skip_serializing_if leaves the field out when the function
+        // returns true, so the default isn't written out.
+        // Leaving fields out only works in formats with field names:
+        // bincode would have no way of telling that it's missing.
+        #[serde(skip_serializing_if = "is_zero")]
+        retries: u32,
+    },
+}
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
+impl Serialize for Backend {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !serializer.is_human_readable() {
+            // For bincode: always the same shape, a list followed by a number.
+            return (&self.urls, self.retries).serialize(serializer);
+        }
+
+        let output = match self.urls.as_slice() {
+            [url] if self.retries == 0 => BackendOutput::Url(url),
+            urls => BackendOutput::Table {
+                urls,
+                retries: self.retries,
+            },
+        };
+
+        output.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Backend {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if !deserializer.is_human_readable() {
+            let (urls, retries) = <(Vec<String>, u32)>::deserialize(deserializer)?;
+            return Ok(Backend { urls, retries });
+        }
+
+        let backend = match BackendInput::deserialize(deserializer)? {
+            BackendInput::Url(url) => Backend::new(url),
+            BackendInput::Urls(urls) => Backend { urls, retries: 0 },
+            BackendInput::Table { urls, retries } => Backend { urls, retries },
+        };
+
+        // Deserialize is also a good place to check things that the
+        // types can't.
+        if backend.urls.is_empty() {
+            return Err(serde::de::Error::custom("a backend needs at least one URL"));
+        }
+
+        Ok(backend)
+    }
+}
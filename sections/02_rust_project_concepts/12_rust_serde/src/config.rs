@@ -0,0 +1,109 @@
+// The config for an (imaginary) web server.
+//
+// These types are written once, and the same derives work for JSON,
+// TOML, and bincode. The #[serde(...)] attributes change how they
+// look in those formats, without changing the Rust side at all.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::Backend;
+
+/// The server's config.
+// rename_all changes the name of every field: `log_level` in Rust is
+// `log-level` in the file, which is the usual style for TOML.
+//
+// deny_unknown_fields makes a typo like `log-levle` an error, instead
+// of being silently ignored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    /// A name for the server, used in logs.
+    pub name: String,
+
+    /// The address to listen on.
+    // rename changes the name of just this field.
+    // It's `address` in Rust, but `listen` reads better in a file.
+    #[serde(rename = "listen")]
+    pub address: String,
+
+    /// The port to listen on.
+    // default = "..." calls a function if the field is missing.
+    #[serde(default = "default_port")]
+    pub port: u16,
+
+    /// How much to log.
+    // A plain `default` uses the type's Default implementation.
+    #[serde(default)]
+    pub log_level: LogLevel,
+
+    /// How long to wait for a backend before giving up.
+    // with = "..." uses the serialize and deserialize functions in
+    // that module, instead of Duration's own.
+    #[serde(default = "default_request_timeout", with = "crate::duration")]
+    pub request_timeout: Duration,
+
+    /// Certificates for HTTPS, if it's turned on.
+    // TOML has no way to write None, so the toml crate leaves the field
+    // out. JSON writes `null`, and bincode writes a 0 byte.
+    #[serde(default)]
+    pub tls: Option<Tls>,
+
+    /// Which backend each path is sent to.
+    #[serde(default)]
+    pub routes: Vec<Route>,
+}
+
+fn default_port() -> u16 {
+    8080
+}
+
+fn default_request_timeout() -> Duration {
+    Duration::from_secs(30)
+}
+
+impl Config {
+    /// Creates a config with every optional setting left at its default.
+    pub fn new(name: impl Into<String>, address: impl Into<String>) -> Self {
+        Config {
+            name: name.into(),
+            address: address.into(),
+            port: default_port(),
+            log_level: LogLevel::default(),
+            request_timeout: default_request_timeout(),
+            tls: None,
+            routes: Vec::new(),
+        }
+    }
+}
+
+/// How much the server logs.
+// For enums, rename_all changes the variant names: `LogLevel::Warn`
+// is written as "warn".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+}
+
+/// Where to find the certificate and private key for HTTPS.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Tls {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
+/// Sends requests for a path to a backend.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Route {
+    pub path: String,
+    pub backend: Backend,
+}
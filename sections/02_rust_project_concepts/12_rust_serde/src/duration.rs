@@ -0,0 +1,174 @@
+// A custom serializer and deserializer for Duration.
+//
+// Fields use it with `#[serde(with = "crate::duration")]`, which
+// tells serde to call this module's `serialize` and `deserialize`
+// functions instead of Duration's own Serialize and Deserialize.
+//
+// In human-readable formats (JSON and TOML), a duration is a string
+// like "1m30s", which is much nicer to write in a config file than
+// serde's default of `{ "secs": 90, "nanos": 0 }`.
+// In binary formats (bincode), it's a number of milliseconds, which
+// is smaller and faster.
+
+use std::fmt;
+use std::time::Duration;
+
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serializer};
+
+/// The units that a duration can be written in, from largest to
+/// smallest, with how many milliseconds are in each.
+const UNITS: [(&str, u64); 4] = [("h", 3_600_000), ("m", 60_000), ("s", 1_000), ("ms", 1)];
+
+/// Writes a duration as a string, like "1m30s".
+/// Anything smaller than a millisecond is left out.
+pub fn format(duration: Duration) -> String {
+    let mut millis = duration.as_millis() as u64;
+
+    if millis == 0 {
+        return "0s".to_string();
+    }
+
+    let mut text = String::new();
+    for (unit, size) in UNITS {
+        if millis >= size {
+            text.push_str(&format!("{}{unit}", millis / size));
+            millis %= size;
+        }
+    }
+
+    text
+}
+
+/// Reads a string like "1m30s" or "250ms" as a duration.
+pub fn parse(text: &str) -> Result<Duration, String> {
+    let mut rest = text.trim();
+    let mut millis: u64 = 0;
+
+    if rest.is_empty() {
+        return Err("a duration can't be empty".to_string());
+    }
+
+    while !rest.is_empty() {
+        // Split off the number at the start...
+        let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        if digits == 0 {
+            return Err(format!("expected a number in `{text}`"));
+        }
+        let number: u64 = rest[..digits].parse().map_err(|_| format!("`{text}` is too long"))?;
+        rest = &rest[digits..];
+
+        // ...and then the unit after it.
+        let letters = rest.find(|c: char| c.is_ascii_digit()).unwrap_or(rest.len());
+        let unit = &rest[..letters];
+        rest = &rest[letters..];
+
+        if unit.is_empty() {
+            return Err(format!("`{text}` needs a unit after {number} (h, m, s, or ms)"));
+        }
+
+        let Some((_, size)) = UNITS.iter().find(|(name, _)| *name == unit) else {
+            return Err(format!("unknown unit `{unit}` in `{text}` (expected h, m, s, or ms)"));
+        };
+
+        millis = number
+            .checked_mul(*size)
+            .and_then(|part| millis.checked_add(part))
+            .ok_or_else(|| format!("`{text}` is too long"))?;
+    }
+
+    Ok(Duration::from_millis(millis))
+}
+
+/// Serializes a duration.
+/// This is called by serde, because of `#[serde(with = ...)]`.
+pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    // is_human_readable() lets us pick a different shape for text
+    // formats and binary ones.
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&format(*duration))
+    } else {
+        serializer.serialize_u64(duration.as_millis() as u64)
+    }
+}
+
+/// Deserializes a duration.
+/// This is called by serde, because of `#[serde(with = ...)]`.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    if deserializer.is_human_readable() {
+        // deserialize_any asks the format what it has, and calls the
+        // matching method on our visitor.
+        // This only works in formats that describe themselves, like
+        // JSON and TOML.
+        deserializer.deserialize_any(DurationVisitor)
+    } else {
+        u64::deserialize(deserializer).map(Duration::from_millis)
+    }
+}
+
+/// Turns whatever the format found into a duration.
+/// A visitor only needs the methods for the types it accepts: the
+/// rest return an error, using `expecting` for the message.
+struct DurationVisitor;
+
+impl Visitor<'_> for DurationVisitor {
+    type Value = Duration;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a duration, like \"30s\" or \"1m30s\", or a number of seconds")
+    }
+
+    fn visit_str<E: de::Error>(self, text: &str) -> Result<Duration, E> {
+        parse(text).map_err(E::custom)
+    }
+
+    // A plain number is a number of seconds.
+    fn visit_u64<E: de::Error>(self, seconds: u64) -> Result<Duration, E> {
+        Ok(Duration::from_secs(seconds))
+    }
+
+    // TOML's numbers are always signed, so it calls this one instead.
+    fn visit_i64<E: de::Error>(self, seconds: i64) -> Result<Duration, E> {
+        let seconds = u64::try_from(seconds)
+            .map_err(|_| E::invalid_value(de::Unexpected::Signed(seconds), &"a positive number of seconds"))?;
+
+        self.visit_u64(seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats() {
+        assert_eq!(format(Duration::from_secs(90)), "1m30s");
+        assert_eq!(format(Duration::from_millis(250)), "250ms");
+        assert_eq!(format(Duration::from_secs(7_200)), "2h");
+        assert_eq!(format(Duration::ZERO), "0s");
+    }
+
+    #[test]
+    fn parses() {
+        assert_eq!(parse("1m30s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse("2h"), Ok(Duration::from_secs(7_200)));
+        assert_eq!(parse("1s500ms"), Ok(Duration::from_millis(1_500)));
+    }
+
+    #[test]
+    fn rejects_bad_durations() {
+        assert!(parse("").is_err());
+        assert!(parse("30").is_err());
+        assert!(parse("s").is_err());
+        assert!(parse("5 days").is_err());
+        assert!(parse("99999999999999999999h").is_err());
+    }
+
+    #[test]
+    fn format_and_parse_agree() {
+        for millis in [1, 999, 1_000, 61_001, 3_723_004] {
+            let duration = Duration::from_millis(millis);
+            assert_eq!(parse(&format(duration)), Ok(duration));
+        }
+    }
+}
@@ -0,0 +1,9 @@
+// The types are in a library, so that both main.rs and the tests in
+// tests/ can use them.
+
+pub mod backend;
+pub mod config;
+pub mod duration;
+
+pub use backend::Backend;
+pub use config::{Config, LogLevel, Route, Tls};
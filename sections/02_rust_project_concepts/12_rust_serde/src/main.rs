@@ -0,0 +1,49 @@
+use std::error::Error;
+use std::{env, fs};
+
+use project::Config;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    // Read the config from the file given on the command line,
+    // or config.toml if there isn't one.
+    let path = env::args().nth(1).unwrap_or_else(|| "config.toml".to_string());
+    let text = fs::read_to_string(&path)?;
+
+    // toml::from_str works just like serde_json::from_str.
+    // Every format has functions like these, and they all use the
+    // same Serialize and Deserialize implementations.
+    let config: Config = toml::from_str(&text)?;
+    println!("Loaded {path}:\n{config:#?}\n");
+
+    // Now save it in each format.
+    let json = serde_json::to_string_pretty(&config)?;
+    println!("As JSON ({} bytes):\n{json}\n", json.len());
+
+    let toml = toml::to_string_pretty(&config)?;
+    println!("As TOML ({} bytes):\n{toml}", toml.len());
+
+    let bytes = bincode::serialize(&config)?;
+    println!("As bincode ({} bytes):\n{}\n", bytes.len(), hex(&bytes));
+
+    // Reading each one back should give exactly the same config.
+    let from_json: Config = serde_json::from_str(&json)?;
+    let from_toml: Config = toml::from_str(&toml)?;
+    let from_bincode: Config = bincode::deserialize(&bytes)?;
+
+    if from_json == config && from_toml == config && from_bincode == config {
+        println!("All three formats read back the same config.");
+    } else {
+        println!("Something changed when reading a format back!");
+    }
+
+    Ok(())
+}
+
+/// Shows bytes in hexadecimal, 16 to a line.
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .chunks(16)
+        .map(|line| line.iter().map(|byte| format!("{byte:02x}")).collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
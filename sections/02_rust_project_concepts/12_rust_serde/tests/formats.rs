@@ -0,0 +1,153 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use project::{Backend, Config, LogLevel, Route, Tls};
+
+/// A config that uses every feature.
+fn full_config() -> Config {
+    let mut config = Config::new("test", "127.0.0.1");
+    config.port = 9000;
+    config.log_level = LogLevel::Warn;
+    config.request_timeout = Duration::from_millis(1_500);
+    config.tls = Some(Tls {
+        cert: PathBuf::from("cert.pem"),
+        key: PathBuf::from("key.pem"),
+    });
+    config.routes = vec![
+        Route {
+            path: "/".to_string(),
+            backend: Backend::new("http://localhost:3000"),
+        },
+        Route {
+            path: "/api".to_string(),
+            backend: Backend {
+                urls: vec!["http://a".to_string(), "http://b".to_string()],
+                retries: 2,
+            },
+        },
+    ];
+    config
+}
+
+#[test]
+fn json_round_trip() {
+    for config in [full_config(), Config::new("minimal", "localhost")] {
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(serde_json::from_str::<Config>(&json).unwrap(), config);
+    }
+}
+
+#[test]
+fn toml_round_trip() {
+    for config in [full_config(), Config::new("minimal", "localhost")] {
+        let toml = toml::to_string(&config).unwrap();
+        assert_eq!(toml::from_str::<Config>(&toml).unwrap(), config);
+    }
+}
+
+#[test]
+fn bincode_round_trip() {
+    for config in [full_config(), Config::new("minimal", "localhost")] {
+        let bytes = bincode::serialize(&config).unwrap();
+        assert_eq!(bincode::deserialize::<Config>(&bytes).unwrap(), config);
+    }
+}
+
+#[test]
+fn bincode_is_smaller() {
+    let config = full_config();
+    let json = serde_json::to_vec(&config).unwrap();
+    let bytes = bincode::serialize(&config).unwrap();
+
+    assert!(bytes.len() < json.len());
+}
+
+#[test]
+fn defaults_fill_in_missing_fields() {
+    let config: Config = toml::from_str(
+        r#"
+        name = "small"
+        listen = "localhost"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(config, Config::new("small", "localhost"));
+    assert_eq!(config.port, 8080);
+    assert_eq!(config.request_timeout, Duration::from_secs(30));
+}
+
+#[test]
+fn renamed_fields() {
+    let json = serde_json::to_value(full_config()).unwrap();
+
+    assert_eq!(json["listen"], "127.0.0.1");
+    assert_eq!(json["log-level"], "warn");
+    assert_eq!(json["request-timeout"], "1s500ms");
+    assert!(json.get("address").is_none());
+}
+
+#[test]
+fn unknown_fields_are_errors() {
+    let error = toml::from_str::<Config>(
+        r#"
+        name = "typo"
+        listen = "localhost"
+        log-levle = "debug"
+        "#,
+    )
+    .unwrap_err();
+
+    assert!(error.to_string().contains("log-levle"));
+}
+
+#[test]
+fn durations_can_be_seconds() {
+    let config: Config = serde_json::from_str(r#"{ "name": "n", "listen": "l", "request-timeout": 5 }"#).unwrap();
+
+    assert_eq!(config.request_timeout, Duration::from_secs(5));
+}
+
+#[test]
+fn bad_durations_are_errors() {
+    for timeout in [r#""5 days""#, "-5", "true"] {
+        let json = format!(r#"{{ "name": "n", "listen": "l", "request-timeout": {timeout} }}"#);
+        assert!(serde_json::from_str::<Config>(&json).is_err(), "{timeout}");
+    }
+}
+
+#[test]
+fn backend_shapes() {
+    let routes: Vec<Route> = serde_json::from_str(
+        r#"[
+            { "path": "/one", "backend": "http://a" },
+            { "path": "/many", "backend": ["http://a", "http://b"] },
+            { "path": "/table", "backend": { "urls": ["http://a"], "retries": 3 } }
+        ]"#,
+    )
+    .unwrap();
+
+    assert_eq!(routes[0].backend, Backend::new("http://a"));
+    assert_eq!(routes[1].backend.urls, ["http://a", "http://b"]);
+    assert_eq!(routes[1].backend.retries, 0);
+    assert_eq!(routes[2].backend.retries, 3);
+}
+
+#[test]
+fn backends_are_written_as_short_as_possible() {
+    let one = serde_json::to_string(&Backend::new("http://a")).unwrap();
+    assert_eq!(one, r#""http://a""#);
+
+    let many = Backend {
+        urls: vec!["http://a".to_string(), "http://b".to_string()],
+        retries: 0,
+    };
+    // retries is 0, so it's skipped.
+    assert_eq!(serde_json::to_string(&many).unwrap(), r#"{"urls":["http://a","http://b"]}"#);
+}
+
+#[test]
+fn backends_need_a_url() {
+    assert!(serde_json::from_str::<Backend>("[]").is_err());
+    assert!(serde_json::from_str::<Backend>("42").is_err());
+}
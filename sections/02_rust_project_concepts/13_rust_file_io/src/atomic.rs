@@ -0,0 +1,153 @@
+// Replacing a file's contents without ever leaving it half-written.
+//
+// If we wrote straight into the file, and the program crashed (or the
+// disk filled up) halfway through, the file would be left with half of
+// its new contents, and the old contents would be gone.
+//
+// Instead, we write to a temporary file next to it, and then rename
+// the temporary file over the real one. Renaming within the same
+// directory is atomic: anyone reading the file sees either all of the
+// old version, or all of the new one, and never anything in between.
+
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+
+/// How many temporary names to try before giving up.
+const ATTEMPTS: u32 = 100;
+
+/// Replaces the contents of `path` with whatever `write` writes.
+///
+/// If `write` returns an error, the original file is left untouched.
+pub fn write_atomically<F>(path: &Path, write: F) -> io::Result<()>
+where
+    F: FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+{
+    let (temp_path, file) = create_temp_file(path)?;
+
+    // Everything that can fail happens in here, so that if any of it
+    // does, we can clean up the temporary file in one place.
+    let result = (|| {
+        // BufWriter collects small writes into bigger ones, so that
+        // writing a line at a time doesn't mean asking the operating
+        // system to write a line at a time.
+        let mut writer = BufWriter::new(file);
+        write(&mut writer)?;
+
+        // into_inner writes out anything that's still buffered.
+        // If we just dropped the BufWriter, it would try to do that
+        // too, but any error would be silently ignored.
+        let file = writer.into_inner().map_err(|err| err.into_error())?;
+
+        // sync_all waits until the data is actually on the disk, not
+        // just handed to the operating system. Otherwise, a power cut
+        // right after the rename could leave an empty file behind.
+        file.sync_all()?;
+
+        fs::rename(&temp_path, path)
+    })();
+
+    if result.is_err() {
+        // If this fails too, there's not much we can do, and the
+        // original error is the more useful one to report.
+        let _ = fs::remove_file(&temp_path);
+    }
+
+    result
+}
+
+/// Creates a new, empty temporary file in the same directory as
+/// `path`, and returns where it is.
+///
+/// It has to be in the same directory, since renaming a file onto a
+/// different disk isn't atomic (or even possible).
+fn create_temp_file(path: &Path) -> io::Result<(PathBuf, File)> {
+    let Some(name) = path.file_name() else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is not a file", path.display()),
+        ));
+    };
+
+    for attempt in 0..ATTEMPTS {
+        // app.log becomes .app.log.tmp0, .app.log.tmp1, and so on.
+        // File names are OsStrings (not Strings), since they don't
+        // have to be valid UTF-8.
+        let mut temp_name = OsString::from(".");
+        temp_name.push(name);
+        temp_name.push(format!(".tmp{attempt}"));
+
+        // with_file_name keeps the directory and swaps the name.
+        let temp_path = path.with_file_name(temp_name);
+
+        // create_new fails if the file already exists, instead of
+        // overwriting it. That way, two copies of this program running
+        // at once can't end up writing to the same temporary file.
+        match OpenOptions::new().write(true).create_new(true).open(&temp_path) {
+            Ok(file) => return Ok((temp_path, file)),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err),
+        }
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        format!("could not find a free temporary file name for {}", path.display()),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn replaces_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        fs::write(&path, "old").unwrap();
+
+        write_atomically(&path, |writer| writer.write_all(b"new")).unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        // The temporary file is gone.
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn keeps_the_original_on_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        fs::write(&path, "old").unwrap();
+
+        let result = write_atomically(&path, |writer| {
+            writer.write_all(b"half of the new")?;
+            Err(io::Error::other("something went wrong"))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn skips_temporary_names_that_are_taken() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        // Pretend that another copy of the program is using the first name.
+        fs::write(dir.path().join(".app.log.tmp0"), "someone else's").unwrap();
+
+        write_atomically(&path, |writer| writer.write_all(b"new")).unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(fs::read_to_string(dir.path().join(".app.log.tmp0")).unwrap(), "someone else's");
+    }
+
+    #[test]
+    fn needs_a_file_name() {
+        let error = write_atomically(Path::new("/"), |_| Ok(())).unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,192 @@
+// A small tool for looking after log files.
+// Try it with:
+//     cargo run -- generate app.log --lines 1000000
+//     cargo run -- stats app.log
+//     cargo run -- filter app.log --min-level warn
+//     cargo run -- rotate app.log --keep 3 --max-bytes 1000
+
+mod atomic;
+mod rotate;
+mod scan;
+
+use clap::{Parser, Subcommand};
+use scan::Level;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Looks after log files.
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    /// The command to run.
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Every command that the tool supports.
+#[derive(Subcommand)]
+enum Command {
+    /// Counts the lines in a log, by level.
+    Stats {
+        /// The log file, or - for standard input.
+        path: PathBuf,
+    },
+    /// Removes unimportant lines from a log.
+    Filter {
+        /// The log file.
+        path: PathBuf,
+        /// The least important level to keep.
+        #[arg(short, long, value_parser = parse_level)]
+        min_level: Level,
+    },
+    /// Moves a log to log.1 (and log.1 to log.2, and so on), leaving
+    /// an empty log behind.
+    Rotate {
+        /// The log file.
+        path: PathBuf,
+        /// How many old copies to keep.
+        #[arg(short, long, default_value_t = 5)]
+        keep: u32,
+        /// Only rotate if the log is bigger than this.
+        #[arg(long, default_value_t = 0)]
+        max_bytes: u64,
+    },
+    /// Writes a made-up log, to try the other commands on.
+    Generate {
+        /// Where to write it.
+        path: PathBuf,
+        /// How many lines to write.
+        #[arg(short, long, default_value_t = 10_000)]
+        lines: u64,
+    },
+}
+
+fn parse_level(text: &str) -> Result<Level, String> {
+    Level::parse(text).ok_or_else(|| format!("unknown level `{text}` (expected debug, info, warn, or error)"))
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let (path, result) = match &cli.command {
+        Command::Stats { path } => (path, stats(path)),
+        Command::Filter { path, min_level } => (path, filter(path, *min_level)),
+        Command::Rotate { path, keep, max_bytes } => (path, rotate(path, *keep, *max_bytes)),
+        Command::Generate { path, lines } => (path, generate(path, *lines)),
+    };
+
+    let Err(err) = result else {
+        return ExitCode::SUCCESS;
+    };
+
+    // io::Error has a kind(), which says what sort of problem it was.
+    // Matching on it lets us give a more helpful message for the
+    // problems that people are likely to run into.
+    let path = path.display();
+    match err.kind() {
+        io::ErrorKind::NotFound => eprintln!("{path} does not exist"),
+        io::ErrorKind::PermissionDenied => {
+            eprintln!("not allowed to access {path} (or its directory): try running as its owner")
+        }
+        io::ErrorKind::InvalidData => {
+            eprintln!("{path} contains a line that isn't valid UTF-8, so it was left unchanged")
+        }
+        _ => eprintln!("error with {path}: {err}"),
+    }
+
+    ExitCode::FAILURE
+}
+
+/// Opens a log for reading, through a BufReader.
+/// A path of - means standard input, which is a common convention for
+/// command-line tools.
+fn open(path: &Path) -> io::Result<Box<dyn BufRead>> {
+    if path == Path::new("-") {
+        Ok(Box::new(io::stdin().lock()))
+    } else {
+        Ok(Box::new(BufReader::new(File::open(path)?)))
+    }
+}
+
+fn stats(path: &Path) -> io::Result<()> {
+    let stats = scan::scan(open(path)?)?;
+
+    println!("{} lines, {} bytes", stats.lines, stats.bytes);
+    for level in Level::ALL {
+        println!("{:>7} {level}", stats.count(level));
+    }
+    println!("{:>7} with no level", stats.unknown);
+
+    if stats.invalid > 0 {
+        println!("{:>7} not valid UTF-8", stats.invalid);
+    }
+    println!("The longest line is {} bytes.", stats.longest);
+
+    Ok(())
+}
+
+fn filter(path: &Path, min_level: Level) -> io::Result<()> {
+    let reader = BufReader::new(File::open(path)?);
+
+    // The filtered log is written to a temporary file, and only
+    // replaces the real one if everything succeeds.
+    let mut dropped = 0;
+    atomic::write_atomically(path, |writer| {
+        dropped = scan::filter(reader, writer, min_level)?;
+        Ok(())
+    })?;
+
+    println!("Removed {dropped} lines below {min_level} from {}.", path.display());
+
+    Ok(())
+}
+
+fn rotate(path: &Path, keep: u32, max_bytes: u64) -> io::Result<()> {
+    if !rotate::needs_rotation(path, max_bytes)? {
+        println!("{} is not bigger than {max_bytes} bytes, so it wasn't rotated.", path.display());
+        return Ok(());
+    }
+
+    let rotation = rotate::rotate(path, keep)?;
+
+    println!("Rotated {} ({} files moved).", path.display(), rotation.moved);
+    for deleted in rotation.deleted {
+        println!("Deleted {}.", deleted.display());
+    }
+
+    Ok(())
+}
+
+fn generate(path: &Path, lines: u64) -> io::Result<()> {
+    const MESSAGES: [(Level, &str); 4] = [
+        (Level::Debug, "cache hit"),
+        (Level::Info, "handled request"),
+        (Level::Warn, "slow request"),
+        (Level::Error, "backend timed out"),
+    ];
+
+    // Without the BufWriter, every writeln! would be a separate
+    // request to the operating system, which is very slow.
+    let mut writer = BufWriter::new(File::create(path)?);
+
+    for i in 0..lines {
+        // A made-up mix of levels: mostly DEBUG and INFO, with the
+        // occasional problem.
+        let (level, message) = match i * 7_919 % 100 {
+            0..=49 => MESSAGES[0],
+            50..=89 => MESSAGES[1],
+            90..=97 => MESSAGES[2],
+            _ => MESSAGES[3],
+        };
+        writeln!(writer, "2024-05-01T12:{:02}:{:02}Z {level} {message} #{i}", i / 60 % 60, i % 60)?;
+    }
+
+    // Like in atomic.rs, flushing ourselves means we find out if the
+    // last few lines couldn't be written.
+    writer.flush()?;
+    println!("Wrote {lines} lines to {}.", path.display());
+
+    Ok(())
+}
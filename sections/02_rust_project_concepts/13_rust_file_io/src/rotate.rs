@@ -0,0 +1,225 @@
+// Rotating a log file: app.log becomes app.log.1, app.log.1 becomes
+// app.log.2, and so on, with the oldest one being deleted.
+// That way, logs never take up more than a fixed amount of space.
+
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Returns the path of the nth rotated copy of a log,
+/// like `logs/app.log.2`.
+pub fn rotated_path(path: &Path, n: u32) -> PathBuf {
+    // We can't use with_extension here: it would replace `.log`,
+    // giving `app.2` instead of `app.log.2`.
+    // Instead, we add to the end of the whole path, as an OsString.
+    let mut rotated = OsString::from(path.as_os_str());
+    rotated.push(format!(".{n}"));
+    PathBuf::from(rotated)
+}
+
+/// If `file_name` is a rotated copy of `log_name` (like `app.log.3`
+/// for `app.log`), returns its number.
+fn rotation_number(file_name: &str, log_name: &str) -> Option<u32> {
+    file_name.strip_prefix(log_name)?.strip_prefix('.')?.parse().ok()
+}
+
+/// Returns true if a log is bigger than `max_bytes`.
+/// A log that doesn't exist yet doesn't need rotating.
+pub fn needs_rotation(path: &Path, max_bytes: u64) -> io::Result<bool> {
+    // metadata gets information about a file, like its size, without
+    // opening it.
+    match fs::metadata(path) {
+        Ok(metadata) => Ok(metadata.len() > max_bytes),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/// What happened during a rotation.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Rotation {
+    /// Copies that were renamed to make room, including the log itself.
+    pub moved: u32,
+    /// Old copies that were deleted.
+    pub deleted: Vec<PathBuf>,
+}
+
+/// Rotates a log, keeping at most `keep` old copies, and leaves an
+/// empty log in its place.
+///
+/// With `keep` set to 0, the log is just emptied.
+pub fn rotate(path: &Path, keep: u32) -> io::Result<Rotation> {
+    let mut rotation = Rotation::default();
+
+    // Make sure that the log is there, before we start moving
+    // anything around. This returns a NotFound error if it isn't.
+    fs::metadata(path)?;
+
+    // Delete every copy that would end up past `keep`. This includes
+    // any left over from when `keep` used to be bigger.
+    for (n, old) in rotated_files(path)? {
+        if n >= keep {
+            remove_if_exists(&old)?;
+            rotation.deleted.push(old);
+        }
+    }
+
+    if keep > 0 {
+        // Move each copy up by one, starting from the oldest, so that
+        // nothing gets overwritten.
+        for n in (1..keep).rev() {
+            if rename_if_exists(&rotated_path(path, n), &rotated_path(path, n + 1))? {
+                rotation.moved += 1;
+            }
+        }
+
+        fs::rename(path, rotated_path(path, 1))?;
+        rotation.moved += 1;
+    }
+
+    // File::create makes a new, empty file, or empties an existing one.
+    File::create(path)?;
+
+    Ok(rotation)
+}
+
+/// Finds the rotated copies of a log that are on disk, and their
+/// numbers, sorted from newest to oldest.
+pub fn rotated_files(path: &Path) -> io::Result<Vec<(u32, PathBuf)>> {
+    // parent() is None for a path like "/", and Some("") for a bare
+    // file name like "app.log", which means the current directory.
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+
+    // A log name that isn't valid UTF-8 can't have any copies that
+    // match it as a &str, so there's nothing to find.
+    let Some(log_name) = path.file_name().and_then(|name| name.to_str()) else {
+        return Ok(Vec::new());
+    };
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+
+        // The same goes for other files, so they're skipped.
+        let Some(file_name) = entry.file_name().to_str().map(str::to_owned) else {
+            continue;
+        };
+
+        if let Some(n) = rotation_number(&file_name, log_name) {
+            files.push((n, path.with_file_name(file_name)));
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Renames a file, and returns false (instead of an error) if it
+/// doesn't exist.
+fn rename_if_exists(from: &Path, to: &Path) -> io::Result<bool> {
+    match fs::rename(from, to) {
+        Ok(()) => Ok(true),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/// Deletes a file, and doesn't mind if it's already gone.
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Makes a log and `copies` rotated copies of it in `dir`, each
+    /// containing its own name.
+    fn make_logs(dir: &Path, copies: u32) -> PathBuf {
+        let path = dir.join("app.log");
+        fs::write(&path, "app.log").unwrap();
+        for n in 1..=copies {
+            fs::write(rotated_path(&path, n), format!("app.log.{n}")).unwrap();
+        }
+        path
+    }
+
+    fn read(path: PathBuf) -> String {
+        fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn paths() {
+        assert_eq!(rotated_path(Path::new("logs/app.log"), 2), Path::new("logs/app.log.2"));
+        assert_eq!(rotation_number("app.log.12", "app.log"), Some(12));
+        assert_eq!(rotation_number("app.log", "app.log"), None);
+        assert_eq!(rotation_number("app.log.old", "app.log"), None);
+        assert_eq!(rotation_number("other.log.1", "app.log"), None);
+    }
+
+    #[test]
+    fn rotates() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = make_logs(dir.path(), 2);
+
+        let rotation = rotate(&path, 3).unwrap();
+
+        assert_eq!(rotation.moved, 3);
+        assert!(rotation.deleted.is_empty());
+        assert_eq!(read(path.clone()), "");
+        assert_eq!(read(rotated_path(&path, 1)), "app.log");
+        assert_eq!(read(rotated_path(&path, 2)), "app.log.1");
+        assert_eq!(read(rotated_path(&path, 3)), "app.log.2");
+    }
+
+    #[test]
+    fn deletes_the_oldest() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = make_logs(dir.path(), 5);
+
+        let rotation = rotate(&path, 2).unwrap();
+
+        // .2 to .5 were past the limit. .1 became .2, and the log became .1.
+        assert_eq!(rotation.deleted.len(), 4);
+        assert_eq!(read(rotated_path(&path, 1)), "app.log");
+        assert_eq!(read(rotated_path(&path, 2)), "app.log.1");
+        assert_eq!(rotated_files(&path).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn keep_zero_empties_the_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = make_logs(dir.path(), 1);
+
+        rotate(&path, 0).unwrap();
+
+        assert_eq!(read(path.clone()), "");
+        assert!(rotated_files(&path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn missing_logs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("missing.log");
+
+        assert!(!needs_rotation(&path, 0).unwrap());
+        assert_eq!(rotate(&path, 3).unwrap_err().kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn checks_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        fs::write(&path, "12345").unwrap();
+
+        assert!(needs_rotation(&path, 4).unwrap());
+        assert!(!needs_rotation(&path, 5).unwrap());
+    }
+}
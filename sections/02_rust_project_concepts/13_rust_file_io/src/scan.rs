@@ -0,0 +1,221 @@
+// Reading a log file one line at a time.
+//
+// Log files can be gigabytes long, so reading the whole thing into a
+// String with fs::read_to_string could use up all of our memory.
+// Instead, we read it through a BufReader, which loads a small chunk
+// (8KB by default) at a time, and hands it out line by line.
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+/// How important a log line is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    /// Every level, from least to most important.
+    pub const ALL: [Level; 4] = [Level::Debug, Level::Info, Level::Warn, Level::Error];
+
+    /// Reads a level's name, like "WARN" or "warn".
+    pub fn parse(word: &str) -> Option<Level> {
+        Level::ALL
+            .into_iter()
+            .find(|level| level.name().eq_ignore_ascii_case(word))
+    }
+
+    /// The level's name, as it appears in a log line.
+    pub fn name(self) -> &'static str {
+        match self {
+            Level::Debug => "DEBUG",
+            Level::Info => "INFO",
+            Level::Warn => "WARN",
+            Level::Error => "ERROR",
+        }
+    }
+
+    /// Finds the level of a log line, like
+    /// `2024-05-01T12:00:00Z WARN disk is 90% full`.
+    /// It's looked for in the first few words, since different
+    /// programs put it in different places.
+    pub fn of_line(line: &str) -> Option<Level> {
+        line.split_whitespace().take(3).find_map(|word| {
+            // Some programs write [WARN] or WARN:.
+            Level::parse(word.trim_matches(|c: char| !c.is_ascii_alphabetic()))
+        })
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// What we found in a log file.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Stats {
+    /// How many lines there are.
+    pub lines: u64,
+    /// How many bytes there are.
+    pub bytes: u64,
+    /// How many lines there are at each level, in the same order as
+    /// `Level::ALL`.
+    pub levels: [u64; 4],
+    /// How many lines have no level.
+    pub unknown: u64,
+    /// How many lines aren't valid UTF-8 (usually from a program
+    /// that crashed halfway through writing something).
+    pub invalid: u64,
+    /// The length of the longest line, in bytes.
+    pub longest: usize,
+}
+
+impl Stats {
+    /// How many lines there are at a level.
+    pub fn count(&self, level: Level) -> u64 {
+        self.levels[level as usize]
+    }
+}
+
+/// Reads a log, and counts what's in it.
+///
+/// This takes anything that implements BufRead, not just a file, so
+/// it can be tested with a byte string, or used on standard input.
+pub fn scan(mut reader: impl BufRead) -> io::Result<Stats> {
+    let mut stats = Stats::default();
+
+    // We reuse the same buffer for every line, so reading a million
+    // lines doesn't mean allocating a million times.
+    let mut line = Vec::new();
+
+    loop {
+        line.clear();
+
+        // read_until reads up to (and including) the next newline.
+        // It returns how many bytes were read, and 0 means the end of
+        // the file.
+        // Unlike lines(), it works with bytes, so a line that isn't
+        // valid UTF-8 doesn't stop us.
+        let read = reader.read_until(b'\n', &mut line)?;
+        if read == 0 {
+            break;
+        }
+
+        stats.lines += 1;
+        stats.bytes += read as u64;
+
+        let text = trim_newline(&line);
+        stats.longest = stats.longest.max(text.len());
+
+        match std::str::from_utf8(text) {
+            Ok(text) => match Level::of_line(text) {
+                Some(level) => stats.levels[level as usize] += 1,
+                None => stats.unknown += 1,
+            },
+            Err(_) => stats.invalid += 1,
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Copies the lines that are at least `min` important from a log to
+/// `writer`, and returns how many lines were dropped.
+/// Lines with no level are kept, since they're often the rest of a
+/// multi-line message.
+pub fn filter(reader: impl BufRead, mut writer: impl Write, min: Level) -> io::Result<u64> {
+    let mut dropped = 0;
+
+    // lines() gives us each line as a String, without the newline.
+    // If a line isn't valid UTF-8, it returns an error with the kind
+    // InvalidData, and ? passes it on. Here, that's what we want: it's
+    // better to stop than to quietly mangle someone's logs.
+    for line in reader.lines() {
+        let line = line?;
+
+        if Level::of_line(&line).is_some_and(|level| level < min) {
+            dropped += 1;
+            continue;
+        }
+
+        writeln!(writer, "{line}")?;
+    }
+
+    Ok(dropped)
+}
+
+/// Removes the newline (\n or \r\n) from the end of a line.
+fn trim_newline(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A byte string works as a BufRead, since &[u8] implements it.
+    const LOG: &[u8] = b"2024-05-01T12:00:00Z INFO server started\n\
+        2024-05-01T12:00:01Z DEBUG accepted connection\n\
+        2024-05-01T12:00:02Z [WARN] slow request\r\n\
+        \tat handler.rs:42\n\
+        2024-05-01T12:00:03Z ERROR: disk full\n";
+
+    #[test]
+    fn finds_levels() {
+        assert_eq!(Level::of_line("12:00 INFO hi"), Some(Level::Info));
+        assert_eq!(Level::of_line("[error] oh no"), Some(Level::Error));
+        assert_eq!(Level::of_line("no level here"), None);
+        // Only the first few words count.
+        assert_eq!(Level::of_line("a b c d ERROR"), None);
+    }
+
+    #[test]
+    fn counts_lines() {
+        let stats = scan(LOG).unwrap();
+
+        assert_eq!(stats.lines, 5);
+        assert_eq!(stats.bytes, LOG.len() as u64);
+        assert_eq!(stats.count(Level::Debug), 1);
+        assert_eq!(stats.count(Level::Info), 1);
+        assert_eq!(stats.count(Level::Warn), 1);
+        assert_eq!(stats.count(Level::Error), 1);
+        assert_eq!(stats.unknown, 1);
+        assert_eq!(stats.longest, "2024-05-01T12:00:01Z DEBUG accepted connection".len());
+    }
+
+    #[test]
+    fn scan_counts_invalid_lines() {
+        let stats = scan(&b"INFO ok\n\xff\xfe broken\nno newline at the end"[..]).unwrap();
+
+        assert_eq!(stats.lines, 3);
+        assert_eq!(stats.invalid, 1);
+        assert_eq!(stats.unknown, 1);
+    }
+
+    #[test]
+    fn filters_lines() {
+        let mut output = Vec::new();
+        let dropped = filter(LOG, &mut output, Level::Warn).unwrap();
+
+        assert_eq!(dropped, 2);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "2024-05-01T12:00:02Z [WARN] slow request\n\
+            \tat handler.rs:42\n\
+            2024-05-01T12:00:03Z ERROR: disk full\n"
+        );
+    }
+
+    #[test]
+    fn filter_stops_at_invalid_lines() {
+        let error = filter(&b"INFO ok\n\xff\n"[..], io::sink(), Level::Debug).unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,10 @@
+// Three ways of sharing work between threads:
+// * queue.rs sends jobs to worker threads through a channel.
+// * sum.rs splits a slice between scoped threads.
+// * spinlock.rs builds a lock out of a single atomic bool.
+
+pub mod queue;
+pub mod spinlock;
+pub mod sum;
+
+mod sync;
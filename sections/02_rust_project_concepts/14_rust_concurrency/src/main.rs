@@ -0,0 +1,90 @@
+use project::queue::WorkQueue;
+use project::spinlock::SpinLock;
+use project::sum::{parallel_sum, parallel_sum_atomic};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Instant;
+
+fn main() {
+    work_queue();
+    sum();
+    spinlock();
+}
+
+/// Counts the primes below each of a few numbers, on a work queue.
+fn work_queue() {
+    println!("Work queue:");
+
+    let queue = WorkQueue::new(4, 8);
+
+    // A second channel brings the results back.
+    // Each job gets its own clone of the sender.
+    let (results, received) = mpsc::channel();
+
+    for limit in [10_000, 200_000, 50_000, 400_000, 100_000, 20_000] {
+        let results = results.clone();
+        queue.submit(move || {
+            let count = (2..limit).filter(|&n| is_prime(n)).count();
+            let worker = thread::current().name().unwrap_or("?").to_string();
+            // The receiver is still around (main is waiting on it), so
+            // this can't fail.
+            results.send((limit, count, worker)).unwrap();
+        });
+    }
+
+    // Drop our own sender. Otherwise, the loop below would never end,
+    // since the channel stays open while any sender is alive.
+    drop(results);
+
+    // Results arrive in the order that they finish, not the order that
+    // they were submitted.
+    for (limit, count, worker) in received {
+        println!("  {worker}: {count} primes below {limit}");
+    }
+    println!("  ({} workers)", queue.workers());
+}
+
+fn is_prime(n: u64) -> bool {
+    n >= 2 && (2..).take_while(|d| d * d <= n).all(|d| !n.is_multiple_of(d))
+}
+
+/// Adds up a lot of numbers on one thread, and then on several.
+fn sum() {
+    println!("\nSum:");
+
+    let numbers: Vec<u64> = (0..5_000_000).collect();
+    let threads = thread::available_parallelism().map_or(4, |n| n.get());
+
+    let start = Instant::now();
+    let total: u64 = numbers.iter().sum();
+    println!("  1 thread: {total} in {:?}", start.elapsed());
+
+    let start = Instant::now();
+    let total = parallel_sum(&numbers, threads);
+    println!("  {threads} threads: {total} in {:?}", start.elapsed());
+
+    let start = Instant::now();
+    let total = parallel_sum_atomic(&numbers, threads);
+    println!("  {threads} threads, atomic total: {total} in {:?}", start.elapsed());
+}
+
+/// Has several threads take turns adding to a number behind a spinlock.
+fn spinlock() {
+    println!("\nSpinlock:");
+
+    let counter = SpinLock::new(0);
+
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..100_000 {
+                    *counter.lock() += 1;
+                }
+            });
+        }
+    });
+
+    // Without the lock, some of the additions would be lost, and this
+    // would be less than 400000.
+    println!("  4 threads added 100000 each: {}", *counter.lock());
+}
@@ -0,0 +1,118 @@
+// A work queue: a fixed number of worker threads, and a channel that
+// jobs are sent through.
+//
+// Starting a thread takes a while (tens of microseconds), so for lots
+// of small jobs, it's much faster to start a few threads once, and
+// keep handing them work.
+
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+/// A job for a worker to run.
+///
+/// It's boxed, so that jobs of different types can go through the
+/// same channel. Send is needed to move it to a worker thread, and
+/// 'static because the workers could outlive whatever it borrows.
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// Runs jobs on a fixed number of worker threads.
+///
+/// Dropping the queue waits for every job that's already been
+/// submitted to finish.
+pub struct WorkQueue {
+    /// Where jobs are sent. It's an Option so that Drop can take it
+    /// (and drop it) before joining the workers.
+    sender: Option<SyncSender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl WorkQueue {
+    /// Starts `workers` worker threads.
+    ///
+    /// At most `capacity` jobs can be waiting at once. After that,
+    /// `submit` waits for a worker to take one. This is called
+    /// backpressure: it stops a fast producer from piling up more
+    /// work than the workers can ever get through.
+    pub fn new(workers: usize, capacity: usize) -> Self {
+        assert!(workers > 0, "a work queue needs at least one worker");
+
+        // mpsc stands for "multiple producer, single consumer": the
+        // Sender can be cloned, but there's only one Receiver.
+        // sync_channel is the bounded version of mpsc::channel.
+        let (sender, receiver) = mpsc::sync_channel::<Job>(capacity);
+
+        // Every worker needs to take jobs from the same receiver, so
+        // it's shared with an Arc, and a Mutex makes sure that only
+        // one worker takes from it at a time.
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        let workers = (0..workers)
+            .map(|id| {
+                let receiver = Arc::clone(&receiver);
+                thread::Builder::new()
+                    .name(format!("worker-{id}"))
+                    .spawn(move || work(&receiver))
+                    .expect("failed to start a worker thread")
+            })
+            .collect();
+
+        WorkQueue {
+            sender: Some(sender),
+            workers,
+        }
+    }
+
+    /// Adds a job to the queue.
+    pub fn submit<F>(&self, job: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.sender
+            .as_ref()
+            .expect("the sender is only taken when the queue is dropped")
+            .send(Box::new(job))
+            .expect("every worker has stopped");
+    }
+
+    /// How many worker threads there are.
+    pub fn workers(&self) -> usize {
+        self.workers.len()
+    }
+}
+
+/// The loop that each worker thread runs.
+fn work(receiver: &Mutex<Receiver<Job>>) {
+    loop {
+        // The lock is only held while taking a job, not while running
+        // it: the MutexGuard is a temporary, which is dropped at the
+        // end of this statement.
+        //
+        // Be careful with `while let Ok(job) = receiver.lock().unwrap().recv()`!
+        // In a while let, the guard lives until the end of the loop
+        // body, so only one worker could run a job at a time.
+        let job = receiver.lock().unwrap().recv();
+
+        match job {
+            Ok(job) => job(),
+            // recv() fails once every Sender has been dropped and the
+            // channel is empty, which means there's no more work.
+            Err(_) => break,
+        }
+    }
+}
+
+impl Drop for WorkQueue {
+    fn drop(&mut self) {
+        // Dropping the sender closes the channel. The workers finish
+        // the jobs that are left, and then their recv() fails.
+        drop(self.sender.take());
+
+        for worker in self.workers.drain(..) {
+            // If a job panicked, its worker stopped. There's nothing
+            // useful we can do about that here, and panicking in drop
+            // is best avoided, so the error is ignored.
+            let _ = worker.join();
+        }
+    }
+}
@@ -0,0 +1,132 @@
+// A lock built from a single atomic bool.
+//
+// std's Mutex is almost always the better choice: when it's locked,
+// waiting threads go to sleep, instead of burning CPU time like a
+// spinlock does. But a spinlock is small enough to understand
+// completely, which makes it a great way to see what atomics and
+// memory orderings are for.
+
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+use crate::sync::{spin_loop, AtomicBool, Ordering, UnsafeCell};
+
+/// A lock that protects a value of type T, by spinning (repeatedly
+/// checking) until it's free.
+pub struct SpinLock<T> {
+    /// true while someone holds the lock.
+    locked: AtomicBool,
+    /// The value. UnsafeCell is what allows us to change it through a
+    /// shared reference, which is the whole point of a lock.
+    value: UnsafeCell<T>,
+}
+
+// SAFETY: A SpinLock only lets one thread at a time use the value, so
+// sharing it between threads is safe, as long as the value itself can
+// be sent to another thread.
+// Without this, the UnsafeCell would make SpinLock !Sync, and it
+// couldn't be shared at all.
+unsafe impl<T: Send> Sync for SpinLock<T> {}
+
+impl<T> SpinLock<T> {
+    /// Creates an unlocked lock.
+    pub fn new(value: T) -> Self {
+        SpinLock {
+            locked: AtomicBool::new(false),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Waits until the lock is free, and then takes it.
+    /// It's released when the guard is dropped.
+    pub fn lock(&self) -> SpinLockGuard<'_, T> {
+        loop {
+            if let Some(guard) = self.try_lock() {
+                return guard;
+            }
+
+            // Wait for it to look free before trying again.
+            // A plain load is cheaper than compare_exchange, since it
+            // doesn't need the CPU core to take ownership of the
+            // memory, so this keeps the waiting threads from slowing
+            // down the one holding the lock.
+            while self.locked.load(Ordering::Relaxed) {
+                spin_loop();
+            }
+        }
+    }
+
+    /// Takes the lock if it's free, without waiting.
+    pub fn try_lock(&self) -> Option<SpinLockGuard<'_, T>> {
+        // compare_exchange changes locked from false to true, but only
+        // if it's currently false. It all happens as one step, so two
+        // threads can never both see false and both take the lock.
+        //
+        // Acquire means that everything the previous holder did
+        // before releasing the lock (with Release, below) is visible
+        // to us. Without it, we could see an old version of the value.
+        //
+        // If it fails, we didn't take the lock, and we aren't going to
+        // read the value, so Relaxed is enough.
+        self.locked
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .ok()
+            .map(|_| SpinLockGuard { lock: self })
+    }
+}
+
+impl<T: Default> Default for SpinLock<T> {
+    fn default() -> Self {
+        SpinLock::new(T::default())
+    }
+}
+
+impl<T> fmt::Debug for SpinLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Showing the value would mean taking the lock, which could
+        // wait forever if the caller is already holding it.
+        f.debug_struct("SpinLock")
+            .field("locked", &self.locked.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+/// Proof that the lock is held. Dereference it to use the value.
+pub struct SpinLockGuard<'a, T> {
+    lock: &'a SpinLock<T>,
+}
+
+// SAFETY: Sharing a guard between threads lets them all read the
+// value, which is safe if the value can be shared.
+// (The automatic implementation would only need SpinLock<T>: Sync,
+// which is true for any T: Send, and that isn't enough here.)
+unsafe impl<T: Sync> Sync for SpinLockGuard<'_, T> {}
+
+impl<T> Deref for SpinLockGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: The guard exists, so we hold the lock, and nothing
+        // else can be using the value.
+        self.lock.value.with(|value| unsafe { &*value })
+    }
+}
+
+impl<T> DerefMut for SpinLockGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: As above. We also have the only (mutable) reference
+        // to the guard, so this is the only reference to the value.
+        self.lock.value.with_mut(|value| unsafe { &mut *value })
+    }
+}
+
+impl<T> Drop for SpinLockGuard<'_, T> {
+    fn drop(&mut self) {
+        // Release is the other half of the Acquire in try_lock.
+        // Everything we did to the value happens before this store,
+        // and whoever takes the lock next is guaranteed to see it.
+        // With Relaxed, the CPU (or compiler) would be allowed to let
+        // the next thread in before our changes were visible.
+        self.lock.locked.store(false, Ordering::Release);
+    }
+}
@@ -0,0 +1,60 @@
+// Adding up a slice of numbers on several threads.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+
+/// Adds up `numbers`, splitting the work between `threads` threads.
+pub fn parallel_sum(numbers: &[u64], threads: usize) -> u64 {
+    // div_ceil rounds up, so that every number ends up in a chunk.
+    // max(1) stops chunks() from panicking on an empty slice.
+    let chunk_size = numbers.len().div_ceil(threads.max(1)).max(1);
+
+    // thread::spawn needs everything that it uses to be 'static,
+    // since the thread could keep running after this function
+    // returns. That would rule out borrowing `numbers`.
+    //
+    // thread::scope fixes that: every thread spawned in the scope is
+    // joined before scope() returns, so they can borrow anything that
+    // outlives the scope.
+    thread::scope(|scope| {
+        let handles: Vec<_> = numbers
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || chunk.iter().sum::<u64>()))
+            .collect();
+
+        // join() waits for a thread and gives back what it returned.
+        // It's an error if the thread panicked, in which case we
+        // panic too.
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("a summing thread panicked"))
+            .sum()
+    })
+}
+
+/// Like `parallel_sum`, but every thread adds its part to one shared
+/// atomic total, instead of returning it.
+pub fn parallel_sum_atomic(numbers: &[u64], threads: usize) -> u64 {
+    let chunk_size = numbers.len().div_ceil(threads.max(1)).max(1);
+    let total = AtomicU64::new(0);
+
+    thread::scope(|scope| {
+        for chunk in numbers.chunks(chunk_size) {
+            // Borrowing `total` (instead of moving it) is fine, since
+            // the scope outlives the threads.
+            let total = &total;
+            scope.spawn(move || {
+                // Relaxed is enough here: fetch_add is always atomic,
+                // so no additions are lost, whatever the ordering.
+                // Orderings only matter when one value is used to
+                // signal that *other* memory is ready, and here, the
+                // total is the only thing being shared.
+                total.fetch_add(chunk.iter().sum(), Ordering::Relaxed);
+            });
+        }
+    });
+
+    // The scope joined every thread, and joining also makes
+    // everything that the thread did visible to us.
+    total.into_inner()
+}
@@ -0,0 +1,45 @@
+// The atomics and cells used by spinlock.rs.
+//
+// Normally, they're the ones from std. When testing with loom
+// (`RUSTFLAGS="--cfg loom" cargo test --test loom`), they're loom's
+// versions instead, which let loom try every way that threads could
+// interleave. Everything else in the crate uses std directly, since
+// loom only needs to see the code that we want it to check.
+
+#[cfg(loom)]
+pub(crate) use loom::{
+    cell::UnsafeCell,
+    hint::spin_loop,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+#[cfg(not(loom))]
+pub(crate) use std::{
+    hint::spin_loop,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// A std UnsafeCell, with the same methods as loom's.
+///
+/// loom's UnsafeCell doesn't hand out raw pointers directly. Instead,
+/// the pointer is passed to a closure, so that loom knows exactly when
+/// the cell is being used, and can check that nothing else is using
+/// it at the same time.
+#[cfg(not(loom))]
+#[derive(Debug)]
+pub(crate) struct UnsafeCell<T>(std::cell::UnsafeCell<T>);
+
+#[cfg(not(loom))]
+impl<T> UnsafeCell<T> {
+    pub(crate) const fn new(value: T) -> Self {
+        UnsafeCell(std::cell::UnsafeCell::new(value))
+    }
+
+    pub(crate) fn with<R>(&self, f: impl FnOnce(*const T) -> R) -> R {
+        f(self.0.get())
+    }
+
+    pub(crate) fn with_mut<R>(&self, f: impl FnOnce(*mut T) -> R) -> R {
+        f(self.0.get())
+    }
+}
@@ -0,0 +1,120 @@
+// These tests use the real std atomics, so they're left out when
+// testing with loom (see tests/loom.rs).
+#![cfg(not(loom))]
+
+use project::queue::WorkQueue;
+use project::spinlock::SpinLock;
+use project::sum::{parallel_sum, parallel_sum_atomic};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Barrier};
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn runs_every_job() {
+    let done = Arc::new(AtomicUsize::new(0));
+
+    // A small capacity means that submit has to wait for the workers.
+    let queue = WorkQueue::new(3, 2);
+    for _ in 0..100 {
+        let done = Arc::clone(&done);
+        queue.submit(move || {
+            done.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+
+    // Dropping the queue waits for the jobs to finish.
+    drop(queue);
+    assert_eq!(done.load(Ordering::Relaxed), 100);
+}
+
+#[test]
+fn jobs_run_at_the_same_time() {
+    const WORKERS: usize = 4;
+
+    // A Barrier makes every thread wait until WORKERS threads have
+    // reached it. If the workers couldn't run jobs at the same time
+    // (for example, if they held the receiver's lock while running
+    // one), this would never finish.
+    let barrier = Arc::new(Barrier::new(WORKERS));
+    let (sender, receiver) = mpsc::channel();
+
+    let queue = WorkQueue::new(WORKERS, WORKERS);
+    for _ in 0..WORKERS {
+        let barrier = Arc::clone(&barrier);
+        let sender = sender.clone();
+        queue.submit(move || {
+            barrier.wait();
+            sender.send(()).unwrap();
+        });
+    }
+
+    for _ in 0..WORKERS {
+        receiver
+            .recv_timeout(Duration::from_secs(10))
+            .expect("jobs should run at the same time");
+    }
+}
+
+#[test]
+fn panicking_jobs_only_stop_their_worker() {
+    let queue = WorkQueue::new(2, 4);
+    let (sender, receiver) = mpsc::channel();
+
+    queue.submit(|| panic!("this job fails"));
+    queue.submit(move || sender.send(42).unwrap());
+
+    assert_eq!(receiver.recv_timeout(Duration::from_secs(10)), Ok(42));
+}
+
+#[test]
+fn sums() {
+    let numbers: Vec<u64> = (1..=10_000).collect();
+    let expected = 10_000 * 10_001 / 2;
+
+    for threads in [0, 1, 3, 8, 20_000] {
+        assert_eq!(parallel_sum(&numbers, threads), expected);
+        assert_eq!(parallel_sum_atomic(&numbers, threads), expected);
+    }
+
+    assert_eq!(parallel_sum(&[], 4), 0);
+    assert_eq!(parallel_sum_atomic(&[], 4), 0);
+}
+
+#[test]
+fn spinlock_counts_correctly() {
+    const THREADS: usize = 8;
+    const ADDS: usize = 10_000;
+
+    let counter = SpinLock::new(0);
+
+    thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..ADDS {
+                    // Reading and then writing is two steps, so
+                    // without the lock, two threads could read the same
+                    // number, and one of the additions would be lost.
+                    let mut value = counter.lock();
+                    let old = *value;
+                    *value = old + 1;
+                }
+            });
+        }
+    });
+
+    assert_eq!(*counter.lock(), THREADS * ADDS);
+}
+
+#[test]
+fn try_lock() {
+    let lock = SpinLock::new(String::from("hi"));
+
+    let mut guard = lock.try_lock().unwrap();
+    assert!(lock.try_lock().is_none());
+
+    guard.push('!');
+    drop(guard);
+
+    assert_eq!(*lock.try_lock().unwrap(), "hi!");
+}
@@ -0,0 +1,92 @@
+// Tests that loom runs under every possible interleaving of threads.
+// Run them with:
+//     RUSTFLAGS="--cfg loom" cargo test --release --test loom
+//
+// A normal test only sees whichever interleaving happened to occur,
+// and most bugs in concurrent code only show up in rare ones. loom
+// runs the closure passed to loom::model over and over, trying a
+// different order of atomic operations each time, and also simulating
+// the reorderings that weaker memory orderings allow.
+#![cfg(loom)]
+
+use loom::cell::UnsafeCell;
+use loom::sync::atomic::{AtomicBool, Ordering};
+use loom::sync::Arc;
+use loom::thread;
+use project::spinlock::SpinLock;
+
+#[test]
+fn spinlock_never_loses_an_update() {
+    loom::model(|| {
+        let counter = Arc::new(SpinLock::new(0));
+
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                let counter = Arc::clone(&counter);
+                thread::spawn(move || {
+                    let mut value = counter.lock();
+                    *value += 1;
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(*counter.lock(), 2);
+    });
+}
+
+/// One thread writes some data and then sets a flag. Another thread
+/// waits for the flag, and then reads the data.
+/// This is the same thing that a lock does: the flag is `locked`, and
+/// the data is the value.
+fn message_passing(store: Ordering, load: Ordering) {
+    loom::model(move || {
+        let data = Arc::new(UnsafeCell::new(0));
+        let ready = Arc::new(AtomicBool::new(false));
+
+        let writer = {
+            let data = Arc::clone(&data);
+            let ready = Arc::clone(&ready);
+            thread::spawn(move || {
+                // SAFETY: Nothing reads the data until `ready` is set,
+                // as long as the orderings are right. (loom checks this.)
+                data.with_mut(|data| unsafe { *data = 42 });
+                ready.store(true, store);
+            })
+        };
+
+        if ready.load(load) {
+            // SAFETY: See above.
+            let value = data.with(|data| unsafe { *data });
+            assert_eq!(value, 42);
+        }
+
+        writer.join().unwrap();
+    });
+}
+
+#[test]
+fn release_and_acquire_publish_the_data() {
+    // The Release store "happens before" any Acquire load that sees
+    // it, along with everything before the store. So if we see
+    // ready == true, we're guaranteed to see data == 42.
+    message_passing(Ordering::Release, Ordering::Acquire);
+}
+
+#[test]
+#[should_panic]
+fn relaxed_does_not_publish_the_data() {
+    // With Relaxed, seeing ready == true says nothing about the data.
+    // The reader could see the flag before the write to the data, so
+    // reading it is a data race, and loom finds the interleaving where
+    // that happens.
+    //
+    // On x86, this usually works anyway, since its CPUs don't reorder
+    // these particular operations. It can fail on ARM (phones, newer
+    // Macs), and the compiler is allowed to reorder them everywhere.
+    // That's why a normal test isn't enough to catch this.
+    message_passing(Ordering::Relaxed, Ordering::Relaxed);
+}
@@ -0,0 +1,54 @@
+// Compares the three ways of counting words.
+// Run it with `cargo bench`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use project::{corpus, count_rayon, count_sequential, count_threads};
+use std::thread;
+
+fn versions(c: &mut Criterion) {
+    // About 6MB of text.
+    let text = corpus::generate(1_000_000, 42);
+    let threads = thread::available_parallelism().map_or(4, |n| n.get());
+
+    let mut group = c.benchmark_group("word_count");
+    // Throughput makes criterion report MB/s as well as time.
+    group.throughput(Throughput::Bytes(text.len() as u64));
+    // Each run takes a while, so fewer samples keeps the benchmark
+    // from taking forever.
+    group.sample_size(20);
+
+    group.bench_function("sequential", |b| b.iter(|| count_sequential(black_box(&text))));
+    group.bench_function("rayon", |b| b.iter(|| count_rayon(black_box(&text))));
+    group.bench_function("threads", |b| b.iter(|| count_threads(black_box(&text), threads)));
+
+    group.finish();
+}
+
+fn rayon_threads(c: &mut Criterion) {
+    let text = corpus::generate(1_000_000, 42);
+    let max = thread::available_parallelism().map_or(4, |n| n.get());
+
+    let mut group = c.benchmark_group("rayon_threads");
+    group.throughput(Throughput::Bytes(text.len() as u64));
+    group.sample_size(20);
+
+    // rayon normally uses one thread per CPU core. A ThreadPool of our
+    // own lets us try other numbers, and see how well it scales.
+    let mut threads = 1;
+    while threads <= max {
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+
+        group.bench_with_input(BenchmarkId::from_parameter(threads), &threads, |b, _| {
+            // install runs the closure inside the pool, so that any
+            // rayon work in it uses the pool's threads.
+            b.iter(|| pool.install(|| count_rayon(black_box(&text))))
+        });
+
+        threads *= 2;
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, versions, rayon_threads);
+criterion_main!(benches);
@@ -0,0 +1,89 @@
+// Made-up text to count words in.
+//
+// Real text would work just as well (try downloading a book from
+// Project Gutenberg), but generating it means that we can make as much
+// as we like, and the benchmark always uses exactly the same text.
+
+/// The words to choose from. Earlier words are picked more often,
+/// which is roughly how real text works: "the" is far more common
+/// than "lighthouse".
+const WORDS: [&str; 64] = [
+    "the", "of", "and", "to", "a", "in", "is", "it", "you", "that", "he", "was", "for", "on", "are", "with",
+    "as", "his", "they", "be", "at", "one", "have", "this", "from", "or", "had", "by", "hot", "word", "but",
+    "what", "some", "we", "can", "out", "other", "were", "all", "there", "when", "up", "use", "your", "how",
+    "said", "an", "each", "she", "which", "do", "their", "time", "if", "will", "way", "about", "many",
+    "then", "them", "write", "would", "like", "lighthouse",
+];
+
+/// A small, fast random number generator (xorshift).
+/// It isn't good enough for anything important, but it's plenty for
+/// making up text, and it saves adding a dependency.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A random number from 0 up to (but not including) `max`.
+    fn below(&mut self, max: usize) -> usize {
+        (self.next() % max as u64) as usize
+    }
+}
+
+/// Generates text with about `words` words in it.
+/// The same seed always gives the same text.
+pub fn generate(words: usize, seed: u64) -> String {
+    // xorshift gets stuck on 0, so make sure the seed isn't 0.
+    let mut rng = Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1);
+    let mut text = String::with_capacity(words * 6);
+
+    let mut sentence_start = true;
+    for i in 0..words {
+        // Picking the smaller of two random indexes makes earlier
+        // words more likely.
+        let word = WORDS[rng.below(WORDS.len()).min(rng.below(WORDS.len()))];
+
+        if sentence_start {
+            // Capitalize the first letter.
+            let mut chars = word.chars();
+            if let Some(first) = chars.next() {
+                text.extend(first.to_uppercase());
+                text.push_str(chars.as_str());
+            }
+        } else {
+            text.push_str(word);
+        }
+
+        sentence_start = rng.below(10) == 0;
+        if sentence_start {
+            text.push('.');
+        } else if rng.below(15) == 0 {
+            text.push(',');
+        }
+
+        // Lines of 12 words.
+        text.push(if i % 12 == 11 { '\n' } else { ' ' });
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_text() {
+        assert_eq!(generate(1_000, 1), generate(1_000, 1));
+        assert_ne!(generate(1_000, 1), generate(1_000, 2));
+    }
+
+    #[test]
+    fn has_the_right_number_of_words() {
+        assert_eq!(crate::words(&generate(1_234, 5)).count(), 1_234);
+    }
+}
@@ -0,0 +1,213 @@
+// Counting how often each word appears in a piece of text, three ways:
+// * count_sequential uses one thread.
+// * count_rayon uses rayon's parallel iterators.
+// * count_threads splits the work between threads by hand.
+//
+// All three give exactly the same answer. The benchmark in
+// benches/word_count.rs compares how fast they are.
+
+pub mod corpus;
+
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// How many times each word appears.
+pub type Counts = HashMap<String, usize>;
+
+/// How big (in bytes) the pieces that count_threads hands out are.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Splits text into words.
+/// A word is a run of letters, numbers, and apostrophes (so "don't"
+/// is one word).
+pub fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(|word| word.trim_matches('\''))
+        .filter(|word| !word.is_empty())
+}
+
+/// Adds one to a word's count, ignoring upper and lower case.
+fn add_word(counts: &mut Counts, word: &str) {
+    // Most words are already lowercase, and looking them up with a
+    // &str means we only allocate a String for words that we haven't
+    // seen before. This makes a big difference to how fast this is.
+    if !word.chars().any(char::is_uppercase) {
+        if let Some(count) = counts.get_mut(word) {
+            *count += 1;
+        } else {
+            counts.insert(word.to_string(), 1);
+        }
+    } else {
+        *counts.entry(word.to_lowercase()).or_insert(0) += 1;
+    }
+}
+
+/// Counts the words in some text.
+fn count_into(counts: &mut Counts, text: &str) {
+    for word in words(text) {
+        add_word(counts, word);
+    }
+}
+
+/// Combines two sets of counts.
+fn merge(a: Counts, b: Counts) -> Counts {
+    // Adding the smaller map into the bigger one is less work.
+    let (mut into, from) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+
+    for (word, count) in from {
+        *into.entry(word).or_insert(0) += count;
+    }
+
+    into
+}
+
+/// Counts words on one thread.
+pub fn count_sequential(text: &str) -> Counts {
+    let mut counts = Counts::new();
+    count_into(&mut counts, text);
+    counts
+}
+
+/// Counts words with rayon.
+pub fn count_rayon(text: &str) -> Counts {
+    // par_lines is like lines(), but rayon splits the lines between
+    // its threads.
+    text.par_lines()
+        // Each thread builds up its own HashMap as it goes, so they
+        // never have to wait for each other.
+        // fold is given a function that creates an empty map, since
+        // every thread (actually, every piece of work) needs its own.
+        .fold(Counts::new, |mut counts, line| {
+            count_into(&mut counts, line);
+            counts
+        })
+        // Then, the maps are combined in pairs until there's only one.
+        .reduce(Counts::new, merge)
+}
+
+/// Splits text into pieces of about `size` bytes, at the ends of lines,
+/// so that no word is cut in half.
+pub fn chunks(text: &str, size: usize) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        // Go `size` bytes in, and then on to the end of that line.
+        let end = match rest.get(size.min(rest.len())..).and_then(|after| after.find('\n')) {
+            Some(newline) => size + newline + 1,
+            None => rest.len(),
+        };
+        // `size` might land in the middle of a character, in which
+        // case get() returns None and we take the rest. That's only
+        // a problem for enormous lines, so it's good enough here.
+
+        let (chunk, after) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = after;
+    }
+
+    chunks
+}
+
+/// Counts words on `threads` threads that we start ourselves.
+pub fn count_threads(text: &str, threads: usize) -> Counts {
+    let chunks = chunks(text, CHUNK_SIZE);
+
+    // Each thread takes the next chunk that nobody has started yet.
+    // Splitting the text into many small chunks (instead of one big
+    // one per thread) means that if one thread gets slowed down, the
+    // others pick up the slack.
+    let next = AtomicUsize::new(0);
+    let total = Mutex::new(Counts::new());
+
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            scope.spawn(|| {
+                let mut counts = Counts::new();
+
+                // fetch_add gives every thread a different index.
+                while let Some(chunk) = chunks.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    count_into(&mut counts, chunk);
+                }
+
+                // Add this thread's counts to the total, one thread at a time.
+                let mut total = total.lock().unwrap();
+                *total = merge(std::mem::take(&mut total), counts);
+            });
+        }
+    });
+
+    total.into_inner().unwrap()
+}
+
+/// Returns the `n` most common words, most common first.
+/// Words with the same count are sorted alphabetically, so that the
+/// result is always the same.
+pub fn top(counts: &Counts, n: usize) -> Vec<(&str, usize)> {
+    let mut words: Vec<_> = counts.iter().map(|(word, &count)| (word.as_str(), count)).collect();
+
+    // Sorting all of the words is the simplest way to do this.
+    // With lots of different words, select_nth_unstable_by would be faster.
+    words.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    words.truncate(n);
+
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "The cat sat on the mat.\nThe dog didn't.\n'Quoted' words, and the END\n";
+
+    #[test]
+    fn splits_words() {
+        let words: Vec<_> = words("Hello, world! It's 2024 -- 'quoted'").collect();
+
+        assert_eq!(words, ["Hello", "world", "It's", "2024", "quoted"]);
+    }
+
+    #[test]
+    fn counts() {
+        let counts = count_sequential(TEXT);
+
+        assert_eq!(counts["the"], 4);
+        assert_eq!(counts["didn't"], 1);
+        assert_eq!(counts["quoted"], 1);
+        assert_eq!(counts["end"], 1);
+        assert!(!counts.contains_key("The"));
+    }
+
+    #[test]
+    fn every_version_agrees() {
+        let text = corpus::generate(50_000, 7);
+        let expected = count_sequential(&text);
+
+        assert_eq!(count_rayon(&text), expected);
+        for threads in [0, 1, 2, 8] {
+            assert_eq!(count_threads(&text, threads), expected);
+        }
+    }
+
+    #[test]
+    fn chunks_end_at_lines() {
+        let text = corpus::generate(20_000, 3);
+        let chunks = chunks(&text, 1_000);
+
+        assert!(chunks.len() > 1);
+        assert_eq!(chunks.concat(), text);
+        // Only the last chunk can end partway through a line.
+        assert!(chunks[..chunks.len() - 1].iter().all(|chunk| chunk.ends_with('\n')));
+        assert!(super::chunks("", 10).is_empty());
+    }
+
+    #[test]
+    fn top_words() {
+        let counts = count_sequential(TEXT);
+
+        assert_eq!(top(&counts, 3), [("the", 4), ("and", 1), ("cat", 1)]);
+    }
+}
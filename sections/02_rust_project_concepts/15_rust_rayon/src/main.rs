@@ -0,0 +1,45 @@
+// Counts the words in a text file, or in some made-up text if no file
+// is given, and times each version.
+//     cargo run --release
+//     cargo run --release -- some-book.txt
+//
+// --release matters a lot here: without it, the compiler doesn't
+// optimize, and everything is many times slower.
+
+use project::{corpus, count_rayon, count_sequential, count_threads, top, Counts};
+use std::time::Instant;
+use std::{env, fs, thread};
+
+fn main() {
+    let text = match env::args().nth(1) {
+        Some(path) => fs::read_to_string(&path).unwrap_or_else(|err| {
+            eprintln!("Could not read {path}: {err}");
+            std::process::exit(1);
+        }),
+        None => corpus::generate(20_000_000, 42),
+    };
+    println!("Counting words in {} MB of text.\n", text.len() / 1_000_000);
+
+    let threads = thread::available_parallelism().map_or(4, |n| n.get());
+
+    let sequential = time("Sequential", || count_sequential(&text));
+    let rayon = time("Rayon", || count_rayon(&text));
+    let manual = time(&format!("{threads} threads"), || count_threads(&text, threads));
+
+    assert_eq!(sequential, rayon);
+    assert_eq!(sequential, manual);
+
+    println!("\n{} different words. The most common are:", sequential.len());
+    for (word, count) in top(&sequential, 10) {
+        println!("{count:>10} {word}");
+    }
+}
+
+/// Runs `count`, and prints how long it took.
+fn time(name: &str, count: impl FnOnce() -> Counts) -> Counts {
+    let start = Instant::now();
+    let counts = count();
+    println!("{name:>12}: {:?}", start.elapsed());
+
+    counts
+}
@@ -0,0 +1,109 @@
+// Error messages that point at the problem.
+//
+// nom lets parsers use any error type that implements its ParseError
+// trait. Ours keeps two things: the span where parsing failed (which,
+// thanks to nom_locate, knows its line and column), and what we were
+// expecting to find there, which comes from the context() combinator.
+//
+// Once parsing is done, SyntaxError is turned into an IniError, which
+// owns its data, and prints like this:
+//
+//     error on line 3, column 8: expected `]` to close the section name
+//       |
+//     3 | [server
+//       |        ^
+
+use std::fmt;
+
+use nom::error::{ContextError, ErrorKind, ParseError};
+
+use crate::parser::Span;
+
+/// The error that our parsers return while parsing.
+#[derive(Debug, PartialEq)]
+pub struct SyntaxError<'a> {
+    /// Where parsing failed. The fragment is the rest of the input from
+    /// that point.
+    pub span: Span<'a>,
+    /// What we were expecting, from the innermost context().
+    pub expected: Option<&'static str>,
+    /// Which nom parser failed, for when there's no context.
+    pub kind: ErrorKind,
+}
+
+impl<'a> ParseError<Span<'a>> for SyntaxError<'a> {
+    fn from_error_kind(input: Span<'a>, kind: ErrorKind) -> Self {
+        SyntaxError {
+            span: input,
+            expected: None,
+            kind,
+        }
+    }
+
+    /// Called when a combinator (like alt or many0) fails because a
+    /// parser inside it did. We keep the inner error, since it's the
+    /// most precise about where the problem is.
+    fn append(_input: Span<'a>, _kind: ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+impl<'a> ContextError<Span<'a>> for SyntaxError<'a> {
+    /// Called by context() when the parser inside it fails. Contexts
+    /// wrap each other (a "closing quote" inside a "value"), so we only
+    /// keep the first (innermost) one, which is the most specific.
+    fn add_context(_input: Span<'a>, context: &'static str, mut other: Self) -> Self {
+        other.expected.get_or_insert(context);
+        other
+    }
+}
+
+/// A syntax error in an INI file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IniError {
+    /// Both start at 1, like in a text editor.
+    pub line: u32,
+    pub column: usize,
+    pub expected: String,
+    /// The whole line that the error is on, to show under the message.
+    pub source_line: String,
+}
+
+impl IniError {
+    pub fn new(error: SyntaxError) -> IniError {
+        let span = error.span;
+
+        // get_line_beginning is everything on the line before the
+        // error, and the fragment is everything after it.
+        let before = String::from_utf8_lossy(span.get_line_beginning());
+        let after = span.fragment().lines().next().unwrap_or("");
+
+        IniError {
+            line: span.location_line(),
+            column: span.get_utf8_column(),
+            expected: match error.expected {
+                Some(expected) => expected.to_string(),
+                None => format!("{:?}", error.kind),
+            },
+            source_line: format!("{before}{after}"),
+        }
+    }
+}
+
+impl fmt::Display for IniError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Line numbers can be any width, so the margin has to match.
+        let margin = " ".repeat(self.line.to_string().len());
+
+        writeln!(
+            f,
+            "error on line {}, column {}: expected {}",
+            self.line, self.column, self.expected
+        )?;
+        writeln!(f, "{margin} |")?;
+        writeln!(f, "{} | {}", self.line, self.source_line)?;
+        write!(f, "{margin} | {}^", " ".repeat(self.column - 1))
+    }
+}
+
+impl std::error::Error for IniError {}
@@ -0,0 +1,57 @@
+// What an INI file turns into.
+//
+//     name = example
+//
+//     [server]
+//     host = localhost
+//     port = 8080
+//
+// An INI file is a list of sections, each with a list of `key = value`
+// entries. Entries before the first [section] go in a section with an
+// empty name.
+
+/// A parsed INI file.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Ini {
+    pub sections: Vec<Section>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    pub name: String,
+    pub entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub key: String,
+    pub value: String,
+    /// The line that the entry is on, for error messages like "port on
+    /// line 7 isn't a number".
+    pub line: u32,
+}
+
+impl Ini {
+    /// Finds a section by name. If there's more than one with the same
+    /// name, the first one is returned.
+    pub fn section(&self, name: &str) -> Option<&Section> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+
+    /// Looks up a value. Use "" for the section to get an entry from
+    /// before the first [section].
+    ///
+    /// If a key appears more than once, the last one wins, across every
+    /// section with that name.
+    pub fn get(&self, section: &str, key: &str) -> Option<&str> {
+        // Search backwards, so the first match is the last one in the
+        // file.
+        self.sections
+            .iter()
+            .rev()
+            .filter(|s| s.name == section)
+            .flat_map(|s| s.entries.iter().rev())
+            .find(|entry| entry.key == key)
+            .map(|entry| entry.value.as_str())
+    }
+}
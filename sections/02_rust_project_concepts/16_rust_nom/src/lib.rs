@@ -0,0 +1,13 @@
+// Parsing INI files with nom.
+//
+// * ini.rs has the types that a file is parsed into.
+// * parser.rs has the parsers, built out of nom's combinators.
+// * error.rs turns nom's errors into messages that point at the problem.
+
+pub mod error;
+pub mod ini;
+pub mod parser;
+
+pub use error::IniError;
+pub use ini::{Entry, Ini, Section};
+pub use parser::parse;
@@ -0,0 +1,37 @@
+// Parses an INI file, and prints what's in it.
+//     cargo run
+//     cargo run -- some-file.ini
+
+use std::{env, fs, process};
+
+fn main() {
+    let path = env::args().nth(1).unwrap_or_else(|| "config.ini".to_string());
+    let text = fs::read_to_string(&path).unwrap_or_else(|err| {
+        eprintln!("Could not read {path}: {err}");
+        process::exit(1);
+    });
+
+    let ini = match project::parse(&text) {
+        Ok(ini) => ini,
+        Err(err) => {
+            eprintln!("{path}: {err}");
+            process::exit(1);
+        }
+    };
+
+    for section in &ini.sections {
+        if !section.name.is_empty() {
+            println!("[{}]", section.name);
+        }
+        for entry in &section.entries {
+            println!("  {} = {:?} (line {})", entry.key, entry.value, entry.line);
+        }
+    }
+
+    // Looking up a value, and turning it into a number.
+    match ini.get("server", "port").map(str::parse::<u16>) {
+        Some(Ok(port)) => println!("\nThe server will listen on port {port}."),
+        Some(Err(_)) => println!("\nThe server's port isn't a valid port number."),
+        None => println!("\nThere's no server port set."),
+    }
+}
@@ -0,0 +1,199 @@
+// An INI parser built from nom's parser combinators.
+//
+// In nom, a parser is a function that takes some input, and returns
+// either an error, or what it parsed along with the rest of the input:
+//
+//     fn parser(input: Span) -> IResult<Span, Output, Error>
+//
+// Combinators are functions that take parsers and return new ones.
+// delimited(char('['), name, char(']')) is a parser for a name between
+// square brackets, built out of three smaller parsers. Each function
+// below parses one part of the format, and they're combined until
+// there's one for a whole line:
+//
+//     line    = space (header | entry)? space comment? (newline | end)
+//     header  = "[" space name space "]"
+//     entry   = key space "=" space value
+//     value   = quoted | bare
+//     comment = (";" | "#") anything up to the newline
+
+use nom::branch::alt;
+use nom::bytes::complete::{is_not, take_while1};
+use nom::character::complete::{char, line_ending, one_of, space0};
+use nom::combinator::{cut, eof, map, opt, value};
+use nom::error::context;
+use nom::sequence::{delimited, pair, preceded, separated_pair, terminated, tuple};
+use nom::IResult;
+use nom_locate::{position, LocatedSpan};
+
+use crate::error::{IniError, SyntaxError};
+use crate::ini::{Entry, Ini, Section};
+
+/// The input that our parsers work on. It's a &str, plus where that
+/// &str starts in the whole file (as an offset, line, and column).
+pub type Span<'a> = LocatedSpan<&'a str>;
+
+/// What all of our parsers return.
+type ParseResult<'a, T> = IResult<Span<'a>, T, SyntaxError<'a>>;
+
+/// Parses a whole INI file.
+pub fn parse(input: &str) -> Result<Ini, IniError> {
+    let mut ini = Ini::default();
+    let mut rest = Span::new(input);
+
+    // nom has combinators for repeating a parser (like many0), but a
+    // loop is simpler here, since each line changes the Ini differently.
+    while !rest.fragment().is_empty() {
+        let (next, item) = line(rest).map_err(|error| match error {
+            nom::Err::Error(error) | nom::Err::Failure(error) => IniError::new(error),
+            // Only "streaming" parsers return Incomplete, and we use the
+            // "complete" ones, which treat the end of the input as the
+            // end.
+            nom::Err::Incomplete(_) => unreachable!("complete parsers never return Incomplete"),
+        })?;
+        rest = next;
+
+        match item {
+            Some(Item::Header(name)) => ini.sections.push(Section {
+                name,
+                entries: Vec::new(),
+            }),
+            Some(Item::Entry(entry)) => {
+                // Entries before the first header go in a section with
+                // no name.
+                if ini.sections.is_empty() {
+                    ini.sections.push(Section {
+                        name: String::new(),
+                        entries: Vec::new(),
+                    });
+                }
+                ini.sections.last_mut().unwrap().entries.push(entry);
+            }
+            None => {}
+        }
+    }
+
+    Ok(ini)
+}
+
+/// What a line can hold. Blank and comment-only lines hold nothing.
+enum Item {
+    Header(String),
+    Entry(Entry),
+}
+
+/// One line of the file, including the newline at the end.
+fn line(input: Span) -> ParseResult<Option<Item>> {
+    let item = alt((map(header, Item::Header), map(entry, Item::Entry)));
+
+    delimited(space0, opt(item), end_of_line)(input)
+}
+
+/// Spaces, an optional comment, and then a newline or the end of the
+/// file.
+fn end_of_line(input: Span) -> ParseResult<()> {
+    let comment = pair(one_of(";#"), opt(is_not("\r\n")));
+    let end = context("the end of the line", alt((line_ending, eof)));
+
+    value((), tuple((space0, opt(comment), end)))(input)
+}
+
+/// A section header, like `[server]`.
+fn header(input: Span) -> ParseResult<String> {
+    // Once we've seen a `[`, this can only be a header. cut() turns any
+    // error after that point into a failure, which stops alt() from
+    // trying other parsers, and reports the error right where it
+    // happened.
+    let inside = terminated(
+        delimited(space0, context("a section name", name), space0),
+        context("`]` to close the section name", char(']')),
+    );
+
+    map(preceded(char('['), cut(inside)), |name: Span| name.to_string())(input)
+}
+
+/// A `key = value` line.
+fn entry(input: Span) -> ParseResult<Entry> {
+    // position() doesn't parse anything. It just returns where we are,
+    // so we know which line the entry is on.
+    let (input, start) = position(input)?;
+
+    let equals = context("`=` after the key", delimited(space0, char('='), space0));
+    let (input, (key, value)) = separated_pair(name, cut(equals), alt((quoted, bare)))(input)?;
+
+    let entry = Entry {
+        key: key.to_string(),
+        value,
+        line: start.location_line(),
+    };
+    Ok((input, entry))
+}
+
+/// A section name or key: letters, numbers, `_`, `-`, and `.`.
+fn name(input: Span) -> ParseResult<Span> {
+    take_while1(|c: char| c.is_alphanumeric() || "_-.".contains(c))(input)
+}
+
+/// A value in double quotes, which can hold anything except a `"` or a
+/// newline, including `;` and `#`, and spaces at the ends.
+fn quoted(input: Span) -> ParseResult<String> {
+    let contents = map(opt(is_not("\"\r\n")), |contents: Option<Span>| {
+        contents.map_or(String::new(), |contents| contents.to_string())
+    });
+    let close = context("a closing `\"`", char('"'));
+
+    preceded(char('"'), cut(terminated(contents, close)))(input)
+}
+
+/// A value without quotes. It goes until a comment or the end of the
+/// line, and spaces at the end are left off. It can be empty.
+fn bare(input: Span) -> ParseResult<String> {
+    map(opt(is_not(";#\r\n")), |value: Option<Span>| {
+        value.map_or(String::new(), |value| value.trim_end().to_string())
+    })(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs a parser on some input, and returns what it parsed, and the
+    /// rest of the input.
+    fn run<'a, T>(parser: fn(Span<'a>) -> ParseResult<'a, T>, input: &'a str) -> (T, &'a str) {
+        let (rest, output) = parser(Span::new(input)).unwrap();
+        (output, rest.fragment())
+    }
+
+    #[test]
+    fn parses_pieces() {
+        let (key, rest) = run(name, "server.port = 1");
+        assert_eq!((*key.fragment(), rest), ("server.port", " = 1"));
+
+        assert_eq!(run(header, "[ server ] ; comment").1, " ; comment");
+        assert_eq!(run(header, "[server]").0, "server");
+        assert_eq!(run(quoted, "\"a ; b\" rest"), ("a ; b".to_string(), " rest"));
+        assert_eq!(run(quoted, "\"\"").0, "");
+        assert_eq!(run(bare, "some value   # comment"), ("some value".to_string(), "# comment"));
+        assert_eq!(run(end_of_line, "  ; comment\r\nnext"), ((), "next"));
+    }
+
+    #[test]
+    fn parses_entries() {
+        let (port, rest) = run(entry, "port=8080\n");
+        assert_eq!((port.key.as_str(), port.value.as_str(), port.line), ("port", "8080", 1));
+        assert_eq!(rest, "\n");
+
+        let (empty, _) = run(entry, "empty =");
+        assert_eq!(empty.value, "");
+    }
+
+    #[test]
+    fn alt_backtracks_but_cut_does_not() {
+        // Not a header (no `[`), so line() goes on to try an entry.
+        assert!(line(Span::new("key = value")).is_ok());
+
+        // A `[` without a `]` is a Failure, not an Error, so alt() gives
+        // up straight away instead of trying an entry.
+        assert!(matches!(header(Span::new("[server")), Err(nom::Err::Failure(_))));
+    }
+}
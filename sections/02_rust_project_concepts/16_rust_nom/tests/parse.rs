@@ -0,0 +1,73 @@
+use project::{parse, Ini};
+
+const CONFIG: &str = "\
+; settings
+name = Example
+
+[server]
+host = 127.0.0.1
+port = 8080 # a comment
+
+[database]
+url = \"postgres://localhost/app\"
+password = \"  a;b#c  \"
+empty =
+";
+
+#[test]
+fn parses_a_whole_file() {
+    let ini = parse(CONFIG).unwrap();
+
+    let names: Vec<&str> = ini.sections.iter().map(|section| section.name.as_str()).collect();
+    assert_eq!(names, ["", "server", "database"]);
+
+    assert_eq!(ini.get("", "name"), Some("Example"));
+    assert_eq!(ini.get("server", "port"), Some("8080"));
+    assert_eq!(ini.get("database", "password"), Some("  a;b#c  "));
+    assert_eq!(ini.get("database", "empty"), Some(""));
+    assert_eq!(ini.get("database", "missing"), None);
+
+    // Lines are counted from 1, including blank and comment lines.
+    assert_eq!(ini.section("server").unwrap().entries[1].line, 6);
+}
+
+#[test]
+fn handles_odd_files() {
+    assert_eq!(parse("").unwrap(), Ini::default());
+    assert_eq!(parse("\n\n  ; only a comment").unwrap(), Ini::default());
+
+    // Windows line endings, and no newline at the end.
+    let ini = parse("[a]\r\nkey = 1\r\nkey = 2").unwrap();
+    assert_eq!(ini.get("a", "key"), Some("2"));
+}
+
+/// Parses input that has a mistake in it, and returns where the error
+/// is, and what was expected.
+fn error(input: &str) -> (u32, usize, String) {
+    let err = parse(input).unwrap_err();
+    (err.line, err.column, err.expected)
+}
+
+#[test]
+fn points_at_mistakes() {
+    assert_eq!(error("[server\nport = 1"), (1, 8, "`]` to close the section name".to_string()));
+    assert_eq!(error("[]"), (1, 2, "a section name".to_string()));
+    assert_eq!(error("a = 1\nport 8080"), (2, 6, "`=` after the key".to_string()));
+    assert_eq!(error("[a]\nkey = \"oops\n"), (2, 12, "a closing `\"`".to_string()));
+    assert_eq!(error("[a] b = 1"), (1, 5, "the end of the line".to_string()));
+    assert_eq!(error("= 1"), (1, 1, "the end of the line".to_string()));
+}
+
+#[test]
+fn shows_the_line_in_errors() {
+    let err = parse("a = 1\n[server\n").unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "\
+error on line 2, column 8: expected `]` to close the section name
+  |
+2 | [server
+  |        ^"
+    );
+}
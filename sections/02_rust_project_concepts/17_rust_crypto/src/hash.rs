@@ -0,0 +1,78 @@
+// Hashing files with SHA-256.
+//
+// A hash function turns any amount of data into a short, fixed-size
+// value (32 bytes, for SHA-256). The same data always gives the same
+// hash, but changing even one bit of the data gives a completely
+// different one. And with a cryptographic hash like SHA-256, nobody
+// knows how to find two different pieces of data with the same hash,
+// so if a file's hash hasn't changed, neither has the file.
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+/// A SHA-256 hash.
+pub type Hash = [u8; 32];
+
+/// How much of a file to read at once.
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// Hashes everything that can be read from `reader`.
+///
+/// The data is read a piece at a time, and each piece is fed into the
+/// hasher, so a file of any size can be hashed with only 64KB of
+/// memory.
+pub fn hash_reader(mut reader: impl Read) -> io::Result<Hash> {
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; BUFFER_SIZE];
+
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            // Interrupted means "nothing went wrong, try again".
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(hasher.finalize().into())
+}
+
+/// Hashes the contents of a file.
+pub fn hash_file(path: &Path) -> io::Result<Hash> {
+    hash_reader(File::open(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_known_hashes() {
+        // Test vectors from the SHA-2 standard, so we know we're using
+        // the library correctly.
+        assert_eq!(
+            hex::encode(hash_reader(&b""[..]).unwrap()),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            hex::encode(hash_reader(&b"abc"[..]).unwrap()),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn reads_in_pieces() {
+        // Bigger than the buffer, so it has to be read in several
+        // pieces, which should give the same hash as hashing it all at
+        // once.
+        let data = vec![7; BUFFER_SIZE * 3 + 5];
+        let expected: Hash = Sha256::digest(&data).into();
+
+        assert_eq!(hash_reader(&data[..]).unwrap(), expected);
+    }
+}
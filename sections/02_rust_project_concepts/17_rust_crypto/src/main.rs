@@ -0,0 +1,135 @@
+// A file integrity checker: it records the hash of every file in a
+// directory, and later tells you which ones have changed.
+// Try it with:
+//     export INTEGRITY_PASSPHRASE="a long passphrase"
+//     cargo run -- create src src.manifest
+//     cargo run -- check src src.manifest
+//     echo hunter2 | cargo run -- hash-password
+//
+// * hash.rs hashes files with SHA-256.
+// * manifest.rs keeps the list of hashes, signed with HMAC.
+// * password.rs uses Argon2 to turn a passphrase into a key, and to
+//   hash passwords for storing.
+
+mod hash;
+mod manifest;
+mod password;
+
+use clap::{Parser, Subcommand};
+use manifest::Manifest;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// The environment variable that holds the passphrase.
+///
+/// A passphrase given as an argument would be saved in the shell's
+/// history, and visible to every user on the computer while the
+/// program runs.
+const PASSPHRASE_VAR: &str = "INTEGRITY_PASSPHRASE";
+
+/// Checks whether files have changed.
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    /// The command to run.
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Every command that the tool supports.
+#[derive(Subcommand)]
+enum Command {
+    /// Hashes every file in a directory, and saves a signed manifest.
+    Create {
+        /// The directory to hash.
+        dir: PathBuf,
+        /// Where to save the manifest. Keep it outside the directory,
+        /// or it'll show up as an added file.
+        manifest: PathBuf,
+    },
+    /// Compares a directory to a manifest, and lists what's changed.
+    Check {
+        /// The directory to check.
+        dir: PathBuf,
+        /// The manifest from `create`.
+        manifest: PathBuf,
+    },
+    /// Reads a password from standard input, and prints a hash of it
+    /// that's safe to store.
+    HashPassword,
+    /// Reads a password from standard input, and checks it against a
+    /// hash from `hash-password`.
+    VerifyPassword {
+        /// The hash (put it in single quotes, since it has $ signs in).
+        hash: String,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let result = match &cli.command {
+        Command::Create { dir, manifest } => create(dir, manifest),
+        Command::Check { dir, manifest } => check(dir, manifest),
+        Command::HashPassword => read_password().map(|password| {
+            println!("{}", password::hash_password(&password));
+            true
+        }),
+        Command::VerifyPassword { hash } => read_password().map(|password| {
+            let correct = password::verify_password(&password, hash);
+            println!("{}", if correct { "Correct." } else { "Wrong password." });
+            correct
+        }),
+    };
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn passphrase() -> Result<String, String> {
+    std::env::var(PASSPHRASE_VAR).map_err(|_| format!("set the {PASSPHRASE_VAR} environment variable to a passphrase"))
+}
+
+fn read_password() -> Result<String, String> {
+    let mut line = String::new();
+    io::stdin().read_line(&mut line).map_err(|err| err.to_string())?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+fn create(dir: &Path, path: &Path) -> Result<bool, String> {
+    let passphrase = passphrase()?;
+    let manifest = Manifest::create(dir).map_err(|err| format!("couldn't read {}: {err}", dir.display()))?;
+
+    fs::write(path, manifest.sign(&passphrase)).map_err(|err| format!("couldn't write {}: {err}", path.display()))?;
+    println!("Saved the hashes of {} files to {}.", manifest.files.len(), path.display());
+
+    Ok(true)
+}
+
+/// Returns whether nothing has changed.
+fn check(dir: &Path, path: &Path) -> Result<bool, String> {
+    let passphrase = passphrase()?;
+    let text = fs::read_to_string(path).map_err(|err| format!("couldn't read {}: {err}", path.display()))?;
+
+    // If the manifest has been changed, stop here. It can't be trusted
+    // to say which files are right.
+    let manifest = Manifest::verify(&text, &passphrase).map_err(|err| format!("{}: {err}", path.display()))?;
+
+    let changes = manifest.check(dir).map_err(|err| format!("couldn't read {}: {err}", dir.display()))?;
+    for change in &changes {
+        println!("{change}");
+    }
+    if changes.is_empty() {
+        println!("All {} files are unchanged.", manifest.files.len());
+    }
+
+    Ok(changes.is_empty())
+}
@@ -0,0 +1,268 @@
+// A signed list of every file in a directory, and its hash.
+//
+// Saved to disk, a manifest looks like this:
+//
+//     salt 8f1c...
+//     2c26b46b68ffc68ff99b453c1d30413413422d706483bfa0f98a5e886266e7ae  notes.txt
+//     fcde2b2edba56bf408601fb721fe9b5c338d10ee429ea04fae5511b68fbf8fb9  src/main.rs
+//     hmac 6e1b...
+//
+// Hashes alone can tell us that a file changed by accident (like a bad
+// disk or a broken download). They can't stop someone from changing a
+// file on purpose, though, since they can just work out the new hash
+// and change the manifest too. The HMAC on the last line stops that:
+// making it needs a key, which comes from a passphrase that only we
+// know. Change anything in the manifest, and the HMAC won't match.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::hash::{hash_file, Hash};
+use crate::password::{derive_key, generate_salt, SALT_LEN};
+
+/// HMAC, using SHA-256 as its hash function.
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Debug, PartialEq)]
+pub struct Manifest {
+    /// The salt for turning the passphrase into a key.
+    pub salt: [u8; SALT_LEN],
+    /// Each file's path (relative to the directory, with / between
+    /// folders), and its hash. A BTreeMap keeps them sorted, so the
+    /// manifest is always written in the same order.
+    pub files: BTreeMap<String, Hash>,
+}
+
+/// A difference between a manifest and the files on disk.
+#[derive(Debug, PartialEq)]
+pub enum Change {
+    Modified(String),
+    Missing(String),
+    Added(String),
+}
+
+#[derive(Debug)]
+pub enum ManifestError {
+    /// The manifest isn't in the right format. The number is the line.
+    Malformed(usize),
+    /// The HMAC doesn't match, so either the manifest was changed, or
+    /// the passphrase is wrong. There's no way to tell which!
+    BadSignature,
+}
+
+impl Manifest {
+    /// Hashes every file in a directory, and the folders inside it.
+    pub fn create(dir: &Path) -> io::Result<Manifest> {
+        Ok(Manifest {
+            salt: generate_salt(),
+            files: hash_dir(dir)?,
+        })
+    }
+
+    /// Writes the manifest out as text, signed with a key made from the
+    /// passphrase.
+    pub fn sign(&self, passphrase: &str) -> String {
+        let mut text = format!("salt {}\n", hex::encode(self.salt));
+        for (path, hash) in &self.files {
+            // Two spaces, the same as the sha256sum command uses, so the
+            // lines can be checked with that too.
+            text += &format!("{}  {path}\n", hex::encode(hash));
+        }
+
+        let mut mac = new_mac(passphrase, &self.salt);
+        mac.update(text.as_bytes());
+        let tag = mac.finalize().into_bytes();
+
+        text + &format!("hmac {}\n", hex::encode(tag))
+    }
+
+    /// Reads a manifest written by sign, checking that it hasn't been
+    /// changed since.
+    pub fn verify(text: &str, passphrase: &str) -> Result<Manifest, ManifestError> {
+        let lines: Vec<&str> = text.lines().collect();
+        let (Some(first), Some(last)) = (lines.first(), lines.last()) else {
+            return Err(ManifestError::Malformed(1));
+        };
+
+        let salt = first
+            .strip_prefix("salt ")
+            .and_then(|salt| hex::decode(salt).ok())
+            .and_then(|salt| salt.try_into().ok())
+            .ok_or(ManifestError::Malformed(1))?;
+        let tag = last
+            .strip_prefix("hmac ")
+            .and_then(|tag| hex::decode(tag).ok())
+            .ok_or(ManifestError::Malformed(lines.len()))?;
+
+        // The HMAC covers everything before its own line.
+        let signed = &text[..text.rfind("hmac ").unwrap()];
+
+        // Check the signature *before* looking at anything else in the
+        // manifest. Until then, none of it can be trusted.
+        //
+        // verify_slice compares the tags in "constant time": it takes
+        // just as long whether the first byte is wrong or the last one
+        // is. Comparing with == stops at the first difference, and an
+        // attacker who can time that can work out a valid tag a byte
+        // at a time.
+        let mut mac = new_mac(passphrase, &salt);
+        mac.update(signed.as_bytes());
+        mac.verify_slice(&tag).map_err(|_| ManifestError::BadSignature)?;
+
+        let mut files = BTreeMap::new();
+        for (index, line) in lines[1..lines.len() - 1].iter().enumerate() {
+            let (hash, path) = line
+                .split_once("  ")
+                .and_then(|(hash, path)| Some((hex::decode(hash).ok()?.try_into().ok()?, path)))
+                .ok_or(ManifestError::Malformed(index + 2))?;
+            files.insert(path.to_string(), hash);
+        }
+
+        Ok(Manifest { salt, files })
+    }
+
+    /// Compares the manifest to the files in a directory now.
+    pub fn check(&self, dir: &Path) -> io::Result<Vec<Change>> {
+        let current = hash_dir(dir)?;
+        let mut changes = Vec::new();
+
+        for (path, hash) in &self.files {
+            match current.get(path) {
+                Some(current_hash) if current_hash != hash => changes.push(Change::Modified(path.clone())),
+                Some(_) => {}
+                None => changes.push(Change::Missing(path.clone())),
+            }
+        }
+        for path in current.keys() {
+            if !self.files.contains_key(path) {
+                changes.push(Change::Added(path.clone()));
+            }
+        }
+
+        Ok(changes)
+    }
+}
+
+/// Makes an HMAC with a key from the passphrase.
+fn new_mac(passphrase: &str, salt: &[u8; SALT_LEN]) -> HmacSha256 {
+    let key = derive_key(passphrase, salt);
+    HmacSha256::new_from_slice(&key).expect("HMAC can take a key of any size")
+}
+
+/// Hashes every file in a directory, and the folders inside it.
+fn hash_dir(dir: &Path) -> io::Result<BTreeMap<String, Hash>> {
+    let mut files = BTreeMap::new();
+    add_dir(dir, "", &mut files)?;
+    Ok(files)
+}
+
+fn add_dir(dir: &Path, prefix: &str, files: &mut BTreeMap<String, Hash>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let path = format!("{prefix}{name}");
+
+        // file_type() doesn't follow symlinks, so a link to a folder
+        // outside the directory (or to itself) is skipped, instead of
+        // being followed forever.
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            add_dir(&entry.path(), &format!("{path}/"), files)?;
+        } else if file_type.is_file() {
+            files.insert(path, hash_file(&entry.path())?);
+        }
+    }
+    Ok(())
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::Modified(path) => write!(f, "modified: {path}"),
+            Change::Missing(path) => write!(f, "missing:  {path}"),
+            Change::Added(path) => write!(f, "added:    {path}"),
+        }
+    }
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ManifestError::Malformed(line) => write!(f, "the manifest is malformed on line {line}"),
+            ManifestError::BadSignature => {
+                write!(f, "the manifest has been tampered with, or the passphrase is wrong")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ManifestError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSPHRASE: &str = "a long passphrase";
+
+    fn example_dir() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("notes.txt"), "hello").unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+        dir
+    }
+
+    #[test]
+    fn round_trips() {
+        let dir = example_dir();
+        let manifest = Manifest::create(dir.path()).unwrap();
+        let text = manifest.sign(PASSPHRASE);
+
+        assert!(text.contains("2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824  notes.txt\n"));
+        assert_eq!(Manifest::verify(&text, PASSPHRASE).unwrap(), manifest);
+        assert_eq!(manifest.check(dir.path()).unwrap(), []);
+    }
+
+    #[test]
+    fn finds_changes() {
+        let dir = example_dir();
+        let manifest = Manifest::create(dir.path()).unwrap();
+
+        fs::write(dir.path().join("notes.txt"), "hello!").unwrap();
+        fs::remove_file(dir.path().join("src/main.rs")).unwrap();
+        fs::write(dir.path().join("new.txt"), "").unwrap();
+
+        assert_eq!(
+            manifest.check(dir.path()).unwrap(),
+            [
+                Change::Modified("notes.txt".to_string()),
+                Change::Missing("src/main.rs".to_string()),
+                Change::Added("new.txt".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_tampering() {
+        let manifest = Manifest::create(example_dir().path()).unwrap();
+        let text = manifest.sign(PASSPHRASE);
+
+        // Someone changes notes.txt to "hello!", and puts its new hash in
+        // the manifest.
+        let tampered = text.replace(
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824",
+            "ce06092fb948d9ffac7d1a376e404b26b7575bcc11ee05a4615fef4fec3a308b",
+        );
+        assert_ne!(tampered, text);
+
+        assert!(matches!(Manifest::verify(&tampered, PASSPHRASE), Err(ManifestError::BadSignature)));
+        assert!(matches!(Manifest::verify(&text, "wrong"), Err(ManifestError::BadSignature)));
+        assert!(matches!(Manifest::verify("", PASSPHRASE), Err(ManifestError::Malformed(1))));
+    }
+}
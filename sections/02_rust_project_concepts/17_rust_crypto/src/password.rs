@@ -0,0 +1,102 @@
+// Password hashing with Argon2.
+//
+// Passwords are a special case. A person can only remember so much, so
+// passwords are easy to guess compared to a random key, and a fast hash
+// like SHA-256 lets an attacker try billions of guesses a second.
+// Argon2 is a hash made to be slow, and to need lots of memory, so
+// that each guess is expensive, even with special hardware.
+//
+// It's used two ways here:
+// * derive_key turns a passphrase into a key for HMAC.
+// * hash_password and verify_password store a password so it can be
+//   checked later, like a website does when you log in.
+
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use rand_core::{OsRng, RngCore};
+
+/// How long a salt is, in bytes.
+pub const SALT_LEN: usize = 16;
+
+/// A new random salt.
+///
+/// A salt is mixed into the hash, so that the same passphrase gives a
+/// different key each time a new salt is used. Otherwise, an attacker
+/// could work out the keys for common passphrases once, ahead of time,
+/// and use them against everyone.
+pub fn generate_salt() -> [u8; SALT_LEN] {
+    let mut salt = [0; SALT_LEN];
+    // OsRng asks the operating system for random numbers, which is what
+    // anything to do with security should use.
+    OsRng.fill_bytes(&mut salt);
+    salt
+}
+
+/// Turns a passphrase and a salt into a 32 byte key.
+pub fn derive_key(passphrase: &str, salt: &[u8; SALT_LEN]) -> [u8; 32] {
+    let mut key = [0; 32];
+    // Argon2::default() uses the settings recommended for passwords
+    // today. This only fails if the salt or key are too short or long.
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .expect("the salt and key are valid lengths");
+    key
+}
+
+/// Hashes a password so it can be stored, and checked later with
+/// verify_password.
+///
+/// The result is a string in the "PHC" format, which holds the
+/// algorithm, its settings, and the salt, as well as the hash:
+///
+///     $argon2id$v=19$m=19456,t=2,p=1$<salt>$<hash>
+///
+/// So it's the only thing that needs storing, and it can still be
+/// checked if the settings are made stronger later on.
+pub fn hash_password(password: &str) -> String {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .expect("the default settings are valid")
+        .to_string()
+}
+
+/// Checks a password against a hash from hash_password.
+pub fn verify_password(password: &str, hash: &str) -> bool {
+    let Ok(hash) = PasswordHash::new(hash) else {
+        return false;
+    };
+    // This reads the settings and salt back out of the hash, hashes the
+    // password the same way, and compares the results.
+    Argon2::default()
+        .verify_password(password.as_bytes(), &hash)
+        .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_the_same_key_from_the_same_salt() {
+        let salt = [1; SALT_LEN];
+
+        assert_eq!(derive_key("hunter2", &salt), derive_key("hunter2", &salt));
+        assert_ne!(derive_key("hunter2", &salt), derive_key("hunter3", &salt));
+        assert_ne!(derive_key("hunter2", &salt), derive_key("hunter2", &[2; SALT_LEN]));
+    }
+
+    #[test]
+    fn checks_passwords() {
+        let hash = hash_password("correct horse battery staple");
+
+        assert!(hash.starts_with("$argon2id$"));
+        assert!(verify_password("correct horse battery staple", &hash));
+        assert!(!verify_password("correct horse battery", &hash));
+        assert!(!verify_password("anything", "not a hash"));
+
+        // A new salt every time, so the same password never gives the
+        // same hash twice.
+        assert_ne!(hash, hash_password("correct horse battery staple"));
+    }
+}